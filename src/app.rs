@@ -19,18 +19,23 @@ use crate::{
         dashboard::DashboardComponent,
         edit_header_selector::EditHeaderSelector,
         edit_input::EditInput,
+        edit_param_selector::EditParamSelector,
         floating_windows::{
             add_project_variable::AddProjectVariable,
             app_theme_selector::AppThemeSelector,
+            benchmark::Benchmark,
             body_mode_selector::{BodyModeSelector, BodyModeSelectorState},
             button_style_selector::ButtonStyleSelector,
             code_gen::CodeGen,
+            collection_runner::CollectionRunner,
             commands::Commands,
             edit_endpoint_name::EditEndpointName,
             edit_project_name::EditProjectName,
             endpoints_selector::EndpointsSelector,
-            file_selector::FileSelector,
+            file_selector::{FileSelector, FileSelectorAction},
+            form_field_name::FormFieldName,
             project_variables::ProjectVariables,
+            request_preview::RequestPreview,
             syntax_theme_selector::SyntaxThemeSelector,
         },
         focusable_section::FocusableSection,
@@ -39,7 +44,10 @@ use crate::{
         options::OptionsView,
         project_window::ProjectWindow,
         request_headers_editor::{RequestHeadersEditor, RequestHeadersEditorState},
+        response_diff::ResponseDiff,
         response_renderer::ResponseRenderer,
+        response_tree::ResponseTree,
+        response_validation::ResponseValidation,
         row::{Row, RowState},
         textarea::{TextArea, TextAreaState},
         textinput::{InputState, TextInput, TEXTINPUT_TEMPLATE},
@@ -268,6 +276,33 @@ impl App {
             vec!["dashboard".to_string(), "request_body_section".to_string()],
         )?;
 
+        TextArea::register(
+            &self.component_ids,
+            builder,
+            "graphql_variables_input".to_string(),
+            template("templates/textarea"),
+            Some("endpoint_graphql_variables".to_string()),
+            vec!["dashboard".to_string(), "request_body_section".to_string()],
+        )?;
+
+        TextArea::register(
+            &self.component_ids,
+            builder,
+            "pre_request_script_input".to_string(),
+            template("templates/textarea"),
+            Some("endpoint_pre_request_script".to_string()),
+            vec!["dashboard".to_string()],
+        )?;
+
+        TextArea::register(
+            &self.component_ids,
+            builder,
+            "post_response_script_input".to_string(),
+            template("templates/textarea"),
+            Some("endpoint_post_response_script".to_string()),
+            vec!["dashboard".to_string()],
+        )?;
+
         EditInput::register(
             &self.component_ids,
             builder,
@@ -312,6 +347,51 @@ impl App {
             vec![],
         )?;
 
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "benchmark_concurrency",
+            template("templates/edit_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "benchmark_requests",
+            template("templates/edit_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "benchmark_duration",
+            template("templates/edit_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "benchmark_rate",
+            template("templates/edit_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "form_field_name_input",
+            template("templates/edit_input"),
+            None,
+            vec![],
+        )?;
+
         EditInput::register(
             &self.component_ids,
             builder,
@@ -321,6 +401,33 @@ impl App {
             vec![],
         )?;
 
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "response_query_input",
+            template("templates/response_query_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "response_diff_ignore_input",
+            template("templates/response_diff_ignore_input"),
+            None,
+            vec![],
+        )?;
+
+        EditInput::register(
+            &self.component_ids,
+            builder,
+            "response_schema_input",
+            template("templates/response_schema_input"),
+            None,
+            vec![],
+        )?;
+
         EditInput::register(
             &self.component_ids,
             builder,
@@ -374,6 +481,9 @@ impl App {
             builder,
             "code_sample_renderer".to_string(),
         )?;
+        ResponseTree::register(&self.component_ids, builder)?;
+        ResponseDiff::register(&self.component_ids, builder)?;
+        ResponseValidation::register(&self.component_ids, builder)?;
         AppLayoutComponent::register(&self.component_ids, builder)?;
         ProjectWindow::register(&self.component_ids, builder)?;
         EndpointsSelector::register(&self.component_ids, builder)?;
@@ -390,8 +500,36 @@ impl App {
         CodeGen::register(&self.component_ids, builder)?;
         AddProjectVariable::register(&self.component_ids, builder)?;
         ProjectVariables::register(&self.component_ids, builder)?;
-        FileSelector::register("postman_file_selector", &self.component_ids, builder)?;
+        RequestPreview::register(&self.component_ids, builder)?;
+        CollectionRunner::register(&self.component_ids, builder)?;
+        Benchmark::register(&self.component_ids, builder)?;
+        FormFieldName::register(&self.component_ids, builder)?;
+        FileSelector::register(
+            "postman_file_selector",
+            FileSelectorAction::PostmanImport,
+            &self.component_ids,
+            builder,
+        )?;
+        FileSelector::register(
+            "request_body_file_selector",
+            FileSelectorAction::RequestBody,
+            &self.component_ids,
+            builder,
+        )?;
+        FileSelector::register(
+            "dotenv_file_selector",
+            FileSelectorAction::DotenvFile,
+            &self.component_ids,
+            builder,
+        )?;
+        FileSelector::register(
+            "runner_data_file_selector",
+            FileSelectorAction::RunnerDataFile,
+            &self.component_ids,
+            builder,
+        )?;
         EditHeaderSelector::register(&self.component_ids, builder)?;
+        EditParamSelector::register(&self.component_ids, builder)?;
 
         TextArea::register(
             &self.component_ids,
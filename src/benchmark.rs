@@ -0,0 +1,390 @@
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::bail;
+
+/// Every connection is a thread, this keeps a typo from starting thousands
+const MAX_CONCURRENCY: usize = 1000;
+
+/// How many requests a benchmark sends and how fast
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkOptions {
+    /// The number of requests in flight at the same time
+    pub concurrency: usize,
+
+    /// Stops after this many requests
+    pub requests: Option<usize>,
+
+    /// Stops sending new requests after this much time
+    pub duration: Option<Duration>,
+
+    /// The most requests started per second, across every connection
+    pub rate: Option<f64>,
+}
+
+impl BenchmarkOptions {
+    /// Parses the benchmark window inputs, empty inputs are not set
+    pub fn parse(
+        concurrency: &str,
+        requests: &str,
+        duration: &str,
+        rate: &str,
+    ) -> anyhow::Result<Self> {
+        let concurrency = match concurrency.trim() {
+            "" => 1,
+            value => match value.parse::<usize>() {
+                Ok(concurrency) if (1..=MAX_CONCURRENCY).contains(&concurrency) => concurrency,
+                _ => bail!("Concurrency must be a number from 1 to {MAX_CONCURRENCY}"),
+            },
+        };
+
+        let requests = match requests.trim() {
+            "" => None,
+            value => match value.parse::<usize>() {
+                Ok(requests) if requests > 0 => Some(requests),
+                _ => bail!("The number of requests must be a number above 0"),
+            },
+        };
+
+        let duration = match duration.trim() {
+            "" => None,
+            value => match value.parse::<f64>() {
+                Ok(seconds) if seconds > 0.0 && seconds.is_finite() => {
+                    Some(Duration::from_secs_f64(seconds))
+                }
+                _ => bail!("The duration must be a number of seconds above 0"),
+            },
+        };
+
+        let rate = match rate.trim() {
+            "" => None,
+            value => match value.parse::<f64>() {
+                Ok(rate) if rate > 0.0 && rate.is_finite() => Some(rate),
+                _ => bail!("The rate limit must be a number of requests per second above 0"),
+            },
+        };
+
+        if requests.is_none() && duration.is_none() {
+            bail!("Set the number of requests, the duration or both");
+        }
+
+        Ok(BenchmarkOptions {
+            concurrency,
+            requests,
+            duration,
+            rate,
+        })
+    }
+}
+
+/// The outcome of one benchmark request
+#[derive(Debug)]
+struct Sample {
+    latency: Duration,
+    outcome: Result<u16, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct BenchmarkReport {
+    pub elapsed: Duration,
+
+    /// The latency of every request, sorted from fastest to slowest
+    pub latencies: Vec<Duration>,
+
+    /// The number of responses for each status code or error message
+    pub outcomes: BTreeMap<String, usize>,
+
+    /// Requests that failed or got an error status
+    pub errors: usize,
+}
+
+impl BenchmarkReport {
+    fn new(elapsed: Duration, samples: Vec<Sample>) -> Self {
+        let mut report = BenchmarkReport {
+            elapsed,
+            ..Default::default()
+        };
+
+        for sample in samples {
+            let key = match sample.outcome {
+                Ok(status) => {
+                    if status >= 400 {
+                        report.errors += 1;
+                    }
+
+                    status.to_string()
+                }
+
+                Err(error) => {
+                    report.errors += 1;
+                    error
+                }
+            };
+
+            *report.outcomes.entry(key).or_default() += 1;
+            report.latencies.push(sample.latency);
+        }
+
+        report.latencies.sort();
+
+        report
+    }
+
+    pub fn total(&self) -> usize {
+        self.latencies.len()
+    }
+
+    /// Requests per second
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            seconds => self.total() as f64 / seconds,
+        }
+    }
+
+    /// The nearest-rank percentile of the latencies
+    pub fn percentile(&self, percentile: f64) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+
+        let rank = (percentile / 100.0 * self.total() as f64).ceil() as usize;
+        self.latencies[rank.clamp(1, self.total()) - 1]
+    }
+
+    /// Counts the latencies in equal width buckets between the fastest and
+    /// slowest request. Returns the range label and count of every bucket.
+    pub fn histogram(&self, bucket_count: usize) -> Vec<(String, usize)> {
+        let (Some(fastest), Some(slowest)) = (self.latencies.first(), self.latencies.last()) else {
+            return vec![];
+        };
+
+        let bucket_count = match fastest == slowest {
+            true => 1,
+            false => bucket_count.max(1),
+        };
+
+        let width = (*slowest - *fastest).as_secs_f64() / bucket_count as f64;
+        let mut counts = vec![0; bucket_count];
+        for latency in &self.latencies {
+            let offset = (*latency - *fastest).as_secs_f64();
+            let index = match width {
+                0.0 => 0,
+                width => ((offset / width) as usize).min(bucket_count - 1),
+            };
+
+            counts[index] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(index, count)| {
+                let start = *fastest + Duration::from_secs_f64(width * index as f64);
+                let end = *fastest + Duration::from_secs_f64(width * (index + 1) as f64);
+                let label = format!("{:>9} - {:>9}", format_latency(start), format_latency(end));
+
+                (label, count)
+            })
+            .collect()
+    }
+
+    pub fn summary(&self) -> Vec<String> {
+        let outcomes: Vec<String> = self
+            .outcomes
+            .iter()
+            .map(|(outcome, count)| format!("{outcome}: {count}"))
+            .collect();
+
+        vec![
+            format!(
+                "{} requests in {:.2}s, {:.1} requests/s",
+                self.total(),
+                self.elapsed.as_secs_f64(),
+                self.throughput()
+            ),
+            format!(
+                "Latency p50 {}, p90 {}, p99 {}, max {}",
+                format_latency(self.percentile(50.0)),
+                format_latency(self.percentile(90.0)),
+                format_latency(self.percentile(99.0)),
+                format_latency(self.latencies.last().copied().unwrap_or_default())
+            ),
+            format!("Errors: {}", self.errors),
+            format!("Responses: {}", outcomes.join(", ")),
+        ]
+    }
+}
+
+pub fn format_latency(latency: Duration) -> String {
+    format!("{:.1}ms", latency.as_secs_f64() * 1000.0)
+}
+
+/// Sends requests with `send` from `concurrency` threads until the number of
+/// requests or the duration is reached, or until `cancel` is set. `send`
+/// returns the response status.
+pub fn run_benchmark<F>(options: &BenchmarkOptions, cancel: &AtomicBool, send: F) -> BenchmarkReport
+where
+    F: Fn() -> anyhow::Result<u16> + Sync,
+{
+    let start = Instant::now();
+    let started_requests = AtomicUsize::new(0);
+
+    let samples: Vec<Sample> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..options.concurrency)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut samples = vec![];
+
+                    loop {
+                        let index = started_requests.fetch_add(1, Ordering::SeqCst);
+                        if options.requests.is_some_and(|requests| index >= requests) {
+                            break;
+                        }
+
+                        // NOTE: Requests are spread evenly over time when there is a rate limit
+                        if let Some(rate) = options.rate {
+                            let due = start + Duration::from_secs_f64(index as f64 / rate);
+                            thread::sleep(due.saturating_duration_since(Instant::now()));
+                        }
+
+                        if cancel.load(Ordering::SeqCst)
+                            || options
+                                .duration
+                                .is_some_and(|duration| start.elapsed() >= duration)
+                        {
+                            break;
+                        }
+
+                        let request_start = Instant::now();
+                        let outcome = send().map_err(|error| error.to_string());
+
+                        samples.push(Sample {
+                            latency: request_start.elapsed(),
+                            outcome,
+                        });
+                    }
+
+                    samples
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap_or_default())
+            .collect()
+    });
+
+    BenchmarkReport::new(start.elapsed(), samples)
+}
+
+#[test]
+fn test_benchmark_options_parse() {
+    let options = BenchmarkOptions::parse("50", "1000", "", " 20 ").unwrap();
+    assert_eq!(
+        options,
+        BenchmarkOptions {
+            concurrency: 50,
+            requests: Some(1000),
+            duration: None,
+            rate: Some(20.0),
+        }
+    );
+
+    let options = BenchmarkOptions::parse("", "", "1.5", "").unwrap();
+    assert_eq!(options.concurrency, 1);
+    assert_eq!(options.duration, Some(Duration::from_millis(1500)));
+
+    assert!(BenchmarkOptions::parse("0", "10", "", "").is_err());
+    assert!(BenchmarkOptions::parse("5000", "10", "", "").is_err());
+    assert!(BenchmarkOptions::parse("5", "", "", "").is_err());
+    assert!(BenchmarkOptions::parse("5", "ten", "", "").is_err());
+    assert!(BenchmarkOptions::parse("5", "10", "", "-1").is_err());
+}
+
+#[test]
+fn test_run_benchmark() {
+    let options = BenchmarkOptions {
+        concurrency: 4,
+        requests: Some(20),
+        duration: None,
+        rate: None,
+    };
+
+    let sent = AtomicUsize::new(0);
+    let report = run_benchmark(&options, &AtomicBool::new(false), || {
+        match sent.fetch_add(1, Ordering::SeqCst) % 5 {
+            0 => Ok(500),
+            1 => bail!("Connection refused"),
+            _ => Ok(200),
+        }
+    });
+
+    assert_eq!(report.total(), 20);
+    assert_eq!(report.errors, 8);
+    assert_eq!(report.outcomes["200"], 12);
+    assert_eq!(report.outcomes["500"], 4);
+    assert_eq!(report.outcomes["Connection refused"], 4);
+}
+
+#[test]
+fn test_run_benchmark_cancel() {
+    let options = BenchmarkOptions {
+        concurrency: 2,
+        requests: Some(1000),
+        duration: None,
+        rate: None,
+    };
+
+    let cancel = AtomicBool::new(false);
+    let sent = AtomicUsize::new(0);
+    let report = run_benchmark(&options, &cancel, || {
+        if sent.fetch_add(1, Ordering::SeqCst) == 10 {
+            cancel.store(true, Ordering::SeqCst);
+        }
+
+        Ok(200)
+    });
+
+    assert!(report.total() > 10);
+    assert!(report.total() < 1000);
+}
+
+#[test]
+fn test_benchmark_report() {
+    let samples = (1..=100)
+        .rev()
+        .map(|millis| Sample {
+            latency: Duration::from_millis(millis),
+            outcome: Ok(200),
+        })
+        .collect();
+
+    let report = BenchmarkReport::new(Duration::from_secs(2), samples);
+
+    assert_eq!(report.throughput(), 50.0);
+    assert_eq!(report.percentile(50.0), Duration::from_millis(50));
+    assert_eq!(report.percentile(90.0), Duration::from_millis(90));
+    assert_eq!(report.percentile(99.0), Duration::from_millis(99));
+    assert_eq!(report.percentile(100.0), Duration::from_millis(100));
+
+    let histogram = report.histogram(3);
+    assert_eq!(histogram.len(), 3);
+    assert_eq!(histogram[0].0, "    1.0ms -    34.0ms");
+    assert_eq!(
+        histogram.iter().map(|(_, count)| count).collect::<Vec<_>>(),
+        vec![&33, &33, &34]
+    );
+
+    assert_eq!(
+        report.summary()[1],
+        "Latency p50 50.0ms, p90 90.0ms, p99 99.0ms, max 100.0ms"
+    );
+
+    assert!(BenchmarkReport::default().histogram(10).is_empty());
+}
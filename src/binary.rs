@@ -0,0 +1,415 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use encoding_rs::{Encoding, UTF_8};
+use mime::Mime;
+
+use crate::projects::Header;
+
+/// Bytes at the start of a response that are sniffed for binary content
+const SNIFF_LENGTH: usize = 8192;
+
+/// Bytes shown on every line of a hex dump
+const HEX_LINE_WIDTH: usize = 16;
+
+/// Signatures at the start of common binary files
+const MAGIC_NUMBERS: [&[u8]; 9] = [
+    b"\x89PNG",
+    b"\xff\xd8\xff",
+    b"GIF8",
+    b"%PDF-",
+    b"\x1f\x8b",
+    b"PK\x03\x04",
+    b"RIFF",
+    b"\x00asm",
+    b"\x28\xb5\x2f\xfd",
+];
+
+/// Content types whose file extension is not their subtype
+const FILE_EXTENSIONS: [(&str, &str); 8] = [
+    ("text/plain", "txt"),
+    ("text/javascript", "js"),
+    ("text/markdown", "md"),
+    ("application/javascript", "js"),
+    ("application/octet-stream", "bin"),
+    ("application/x-protobuf", "pb"),
+    ("image/jpeg", "jpg"),
+    ("image/svg+xml", "svg"),
+];
+
+/// The content type header of a response
+pub fn get_response_content_type(headers: &[Header]) -> Option<&str> {
+    headers
+        .iter()
+        .find(|header| header.name.eq_ignore_ascii_case("content-type"))
+        .map(|header| header.value.as_str())
+}
+
+/// How the text of a response is read from its bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResponseEncoding {
+    /// Not text, shown as a hex dump
+    Binary,
+    Utf8,
+
+    /// Decoded from the charset of the content type or the byte order mark
+    Charset(&'static Encoding),
+}
+
+impl ResponseEncoding {
+    /// Detects the encoding from the content type and the start of the body
+    pub fn detect(content_type: Option<&str>, body: &[u8]) -> Self {
+        if is_binary(content_type, body) {
+            return ResponseEncoding::Binary;
+        }
+
+        // NOTE: A byte order mark wins over the charset, UTF-8 responses with
+        // one are decoded to drop it
+        if let Some((encoding, _)) = Encoding::for_bom(body) {
+            return ResponseEncoding::Charset(encoding);
+        }
+
+        match content_type.and_then(get_charset) {
+            Some(encoding) if encoding != UTF_8 => ResponseEncoding::Charset(encoding),
+            _ => ResponseEncoding::Utf8,
+        }
+    }
+
+    pub fn detect_file(content_type: Option<&str>, path: &Path) -> anyhow::Result<Self> {
+        let mut sample = vec![];
+        File::open(path)?
+            .take(SNIFF_LENGTH as u64)
+            .read_to_end(&mut sample)?;
+
+        Ok(Self::detect(content_type, &sample))
+    }
+}
+
+/// Responses with a charset or a text content type are text, images, audio,
+/// video, fonts and archives are binary. Other responses are sniffed, they
+/// are binary when they start with the signature of a binary file or have
+/// null bytes or many control characters.
+fn is_binary(content_type: Option<&str>, body: &[u8]) -> bool {
+    let mime = content_type.and_then(|content_type| content_type.parse::<Mime>().ok());
+
+    match mime {
+        Some(mime) if mime.get_param(mime::CHARSET).is_some() || is_text_mime(&mime) => false,
+        Some(mime) if is_binary_mime(&mime) => true,
+        _ => sniff_binary(body),
+    }
+}
+
+/// The text a response is shown, scripted and compared with, binary
+/// responses are a hex dump. Bytes that are not valid in the encoding of the
+/// response are replaced.
+pub fn get_response_text(content_type: Option<&str>, body: &[u8]) -> String {
+    match ResponseEncoding::detect(content_type, body) {
+        ResponseEncoding::Binary => get_hex_dump(body),
+        ResponseEncoding::Utf8 => String::from_utf8_lossy(body).into_owned(),
+        ResponseEncoding::Charset(encoding) => encoding.decode(body).0.into_owned(),
+    }
+}
+
+/// Decodes text while it is read, so large responses are not held in memory.
+/// Bytes that are not valid in the encoding are replaced.
+pub fn write_decoded_text(
+    encoding: &'static Encoding,
+    mut reader: impl Read,
+    writer: impl Write,
+) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    let mut decoder = encoding.new_decoder();
+    let mut bytes = [0u8; SNIFF_LENGTH];
+
+    loop {
+        let length = reader.read(&mut bytes)?;
+        let last = length == 0;
+
+        // NOTE: The text has room for every decoded byte, so decoding never
+        // stops before the end of the bytes
+        let capacity = decoder
+            .max_utf8_buffer_length(length)
+            .unwrap_or(length * 3 + 4);
+        let mut text = String::with_capacity(capacity);
+        let _ = decoder.decode_to_string(&bytes[..length], &mut text, last);
+        writer.write_all(text.as_bytes())?;
+
+        if last {
+            break;
+        }
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn get_hex_dump(body: &[u8]) -> String {
+    body.chunks(HEX_LINE_WIDTH)
+        .enumerate()
+        .map(|(index, bytes)| get_hex_line(index * HEX_LINE_WIDTH, bytes))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Writes a hex dump while the bytes are read, so large responses are not
+/// held in memory
+pub fn write_hex_dump(mut reader: impl Read, writer: impl Write) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(writer);
+    let mut bytes = [0u8; HEX_LINE_WIDTH];
+    let mut offset = 0;
+
+    loop {
+        let length = read_line_bytes(&mut reader, &mut bytes)?;
+        if length == 0 {
+            break;
+        }
+
+        writeln!(writer, "{}", get_hex_line(offset, &bytes[..length]))?;
+        offset += length;
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// The file extension a response is saved with, from its content type
+pub fn get_file_extension(content_type: Option<&str>, binary: bool) -> String {
+    let fallback = match binary {
+        true => "bin",
+        false => "txt",
+    };
+
+    let Some(mime) = content_type.and_then(|content_type| content_type.parse::<Mime>().ok()) else {
+        return fallback.to_string();
+    };
+
+    let essence = mime.essence_str();
+    if let Some((_, extension)) = FILE_EXTENSIONS.iter().find(|(name, _)| *name == essence) {
+        return extension.to_string();
+    }
+
+    let subtype = mime.subtype().as_str();
+    let extensions = mime_guess::get_mime_extensions_str(essence).unwrap_or_default();
+
+    if extensions.contains(&subtype) {
+        return subtype.to_string();
+    }
+
+    mime.suffix()
+        .map(|suffix| suffix.as_str())
+        .or(extensions.first().copied())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+fn get_charset(content_type: &str) -> Option<&'static Encoding> {
+    let mime: Mime = content_type.parse().ok()?;
+    let charset = mime.get_param(mime::CHARSET)?;
+
+    Encoding::for_label(charset.as_str().as_bytes())
+}
+
+fn is_text_mime(mime: &Mime) -> bool {
+    let subtype = mime.subtype().as_str();
+    let is_text_suffix = mime
+        .suffix()
+        .is_some_and(|suffix| matches!(suffix.as_str(), "json" | "xml"));
+
+    mime.type_() == mime::TEXT
+        || is_text_suffix
+        || matches!(
+            subtype,
+            "json"
+                | "xml"
+                | "javascript"
+                | "ecmascript"
+                | "graphql"
+                | "x-www-form-urlencoded"
+                | "yaml"
+                | "x-yaml"
+                | "toml"
+                | "x-ndjson"
+        )
+}
+
+fn is_binary_mime(mime: &Mime) -> bool {
+    let subtype = mime.subtype().as_str();
+
+    matches!(mime.type_().as_str(), "image" | "audio" | "video" | "font")
+        || matches!(
+            subtype,
+            "pdf"
+                | "zip"
+                | "gzip"
+                | "x-gzip"
+                | "x-tar"
+                | "protobuf"
+                | "x-protobuf"
+                | "grpc"
+                | "msgpack"
+                | "x-msgpack"
+                | "cbor"
+                | "wasm"
+                | "vnd.apache.avro"
+        )
+}
+
+fn sniff_binary(body: &[u8]) -> bool {
+    let sample = &body[..body.len().min(SNIFF_LENGTH)];
+
+    if MAGIC_NUMBERS.iter().any(|magic| sample.starts_with(magic)) {
+        return true;
+    }
+
+    // NOTE: UTF-16 text has null bytes, it starts with a byte order mark
+    if Encoding::for_bom(sample).is_some() {
+        return false;
+    }
+
+    let control_characters = sample
+        .iter()
+        .filter(|byte| **byte < 0x20 && !b"\t\n\r\x0c\x1b".contains(byte))
+        .count();
+
+    sample.contains(&0) || control_characters * 10 > sample.len()
+}
+
+/// A line of a hex dump with the offset, the bytes in hex and the bytes as
+/// ASCII, e.g. `00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|`
+fn get_hex_line(offset: usize, bytes: &[u8]) -> String {
+    let mut hex = String::new();
+    for index in 0..HEX_LINE_WIDTH {
+        if index == HEX_LINE_WIDTH / 2 {
+            hex.push(' ');
+        }
+
+        match bytes.get(index) {
+            Some(byte) => hex.push_str(&format!("{byte:02x} ")),
+            None => hex.push_str("   "),
+        }
+    }
+
+    let ascii: String = bytes
+        .iter()
+        .map(|byte| match byte.is_ascii_graphic() || *byte == b' ' {
+            true => *byte as char,
+            false => '.',
+        })
+        .collect();
+
+    format!("{offset:08x}  {hex} |{ascii}|")
+}
+
+/// Fills the bytes of a hex dump line, readers can return fewer bytes than
+/// asked for before the end
+fn read_line_bytes(reader: &mut impl Read, bytes: &mut [u8]) -> io::Result<usize> {
+    let mut length = 0;
+    while length < bytes.len() {
+        match reader.read(&mut bytes[length..]) {
+            Ok(0) => break,
+            Ok(read) => length += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(length)
+}
+
+#[test]
+fn test_is_binary() {
+    let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+
+    assert!(is_binary(Some("image/png"), b"anything"));
+    assert!(is_binary(None, png));
+    assert!(is_binary(
+        Some("application/octet-stream"),
+        b"\x08\x96\x01\x12\x00"
+    ));
+    assert!(!is_binary(
+        Some("application/octet-stream"),
+        b"plain text\n"
+    ));
+    assert!(!is_binary(Some("application/problem+json"), b"{}"));
+    assert!(!is_binary(
+        Some("text/plain; charset=utf-16le"),
+        b"\xff\xfea\x00"
+    ));
+    assert!(!is_binary(None, "caf\u{e9}".as_bytes()));
+}
+
+#[test]
+fn test_get_response_text() {
+    let latin1 = b"caf\xe9";
+    let shift_jis = b"\x93\xfa\x96\x7b";
+
+    assert_eq!(
+        get_response_text(Some("text/plain; charset=ISO-8859-1"), latin1),
+        "café"
+    );
+    assert_eq!(
+        get_response_text(Some("text/html; charset=Shift_JIS"), shift_jis),
+        "日本"
+    );
+    assert_eq!(get_response_text(Some("text/plain"), latin1), "caf\u{fffd}");
+    assert_eq!(get_response_text(None, b"\xef\xbb\xbfok"), "ok");
+}
+
+#[test]
+fn test_write_decoded_text() {
+    let shift_jis = b"\x93\xfa\x96\x7b\x8c\xea".repeat(3000);
+    let encoding = ResponseEncoding::detect(Some("text/plain; charset=Shift_JIS"), &shift_jis);
+    let ResponseEncoding::Charset(encoding) = encoding else {
+        panic!("Shift_JIS text was detected as {encoding:?}");
+    };
+
+    let mut text = vec![];
+    write_decoded_text(encoding, shift_jis.as_slice(), &mut text).unwrap();
+
+    assert_eq!(String::from_utf8(text).unwrap(), "日本語".repeat(3000));
+    assert_eq!(
+        ResponseEncoding::detect(Some("application/json; charset=utf-8"), b"{}"),
+        ResponseEncoding::Utf8
+    );
+}
+
+#[test]
+fn test_write_hex_dump() {
+    let body = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR\x00\x01 ok";
+
+    let mut dump = vec![];
+    write_hex_dump(body.as_slice(), &mut dump).unwrap();
+    let dump = String::from_utf8(dump).unwrap();
+
+    assert_eq!(
+        dump,
+        "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n\
+         00000010  00 01 20 6f 6b                                    |.. ok|\n"
+    );
+    assert_eq!(get_hex_dump(body), dump.trim_end());
+}
+
+#[test]
+fn test_get_file_extension() {
+    assert_eq!(get_file_extension(Some("image/png"), true), "png");
+    assert_eq!(get_file_extension(Some("image/jpeg"), true), "jpg");
+    assert_eq!(
+        get_file_extension(Some("text/plain; charset=utf-8"), false),
+        "txt"
+    );
+    assert_eq!(get_file_extension(Some("application/json"), false), "json");
+    assert_eq!(
+        get_file_extension(Some("application/problem+json"), false),
+        "json"
+    );
+    assert_eq!(
+        get_file_extension(Some("application/x-unknown"), true),
+        "bin"
+    );
+    assert_eq!(get_file_extension(None, false), "txt");
+}
@@ -10,7 +10,13 @@ use void::Void;
 use crate::{
     components::floating_windows::add_project_variable::Variable,
     fs::get_documents_dir,
-    projects::{Header, PersistedEndpoint, PersistedProject, PersistedVariable, VariableType},
+    graphql::is_graphql_mode,
+    path_params::get_path_params_table,
+    projects::{
+        Header, PathParam, PersistedEndpoint, PersistedProject, PersistedVariable, QueryParam,
+        VariableType,
+    },
+    query_params::get_params_table,
 };
 
 const POSTMAN_JSON_SCHEMA: &str =
@@ -34,6 +40,68 @@ pub struct PostmanJson {
     info: PostmanInformation,
     item: Vec<PostmanItem>,
     variable: Option<Vec<PostmanVariable>>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    event: Vec<PostmanEvent>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize)]
+struct PostmanEvent {
+    /// `prerequest` or `test`
+    listen: String,
+    script: Option<PostmanScript>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize)]
+struct PostmanScript {
+    #[serde(default)]
+    exec: PostmanScriptExec,
+}
+
+/// Script source, as a list of lines or as a single string
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+enum PostmanScriptExec {
+    Lines(Vec<String>),
+    Text(String),
+}
+
+impl Default for PostmanScriptExec {
+    fn default() -> Self {
+        PostmanScriptExec::Lines(vec![])
+    }
+}
+
+impl PostmanScriptExec {
+    fn lines(&self) -> Vec<&str> {
+        match self {
+            PostmanScriptExec::Lines(lines) => lines.iter().flat_map(|line| line.lines()).collect(),
+            PostmanScriptExec::Text(text) => text.lines().collect(),
+        }
+    }
+}
+
+/// Postman scripts are JavaScript, they are imported commented out so they can
+/// be ported to Rhai
+fn get_event_script(events: &[PostmanEvent], listen: &str) -> String {
+    let lines: Vec<&str> = events
+        .iter()
+        .filter(|event| event.listen == listen)
+        .filter_map(|event| event.script.as_ref())
+        .flat_map(|script| script.exec.lines())
+        .collect();
+
+    if lines.iter().all(|line| line.trim().is_empty()) {
+        return String::new();
+    }
+
+    let mut script =
+        format!("// Imported from the Postman {listen} script, port it to Rhai to run it\n");
+    lines
+        .iter()
+        .for_each(|line| script.push_str(&format!("// {line}\n")));
+
+    script
 }
 
 #[derive(Default, Debug, Deserialize, Serialize)]
@@ -79,6 +147,7 @@ impl From<PostmanVariable> for PersistedVariable {
             name: postman_variable.name,
             system: postman_variable.system,
             disabled: postman_variable.disabled,
+            vault_id: None,
         }
     }
 }
@@ -110,11 +179,35 @@ struct PostmanItem {
     id: Option<String>,
     name: String,
     request: PostmanRequest,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    event: Vec<PostmanEvent>,
 }
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize)]
 struct PostmanRequestUrl {
     raw: String,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    query: Vec<PostmanQueryParam>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    variable: Vec<PostmanUrlVariable>,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize)]
+struct PostmanUrlVariable {
+    key: String,
+    value: Option<String>,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize)]
+struct PostmanQueryParam {
+    key: String,
+    value: Option<String>,
+
+    #[serde(default)]
+    disabled: bool,
 }
 
 impl FromStr for PostmanRequestUrl {
@@ -123,7 +216,11 @@ impl FromStr for PostmanRequestUrl {
     type Err = Void;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(PostmanRequestUrl { raw: s.to_string() })
+        Ok(PostmanRequestUrl {
+            raw: s.to_string(),
+            query: vec![],
+            variable: vec![],
+        })
     }
 }
 
@@ -262,15 +359,6 @@ impl From<PostmanKV> for String {
     }
 }
 
-impl From<GraphQL> for String {
-    fn from(graphql: GraphQL) -> Self {
-        match serde_json::to_string(&graphql) {
-            Ok(json) => json,
-            Err(_) => "".to_string(),
-        }
-    }
-}
-
 fn create_uuid(seed: &str) -> String {
     let uid = Uuid::new_v5(&Uuid::NAMESPACE_URL, seed.as_bytes());
 
@@ -315,7 +403,7 @@ impl From<PostmanJson> for PersistedProject {
                         },
 
                         PostmanBodyMode::GraphQL => match &postman_body.graphql {
-                            Some(graphql) => ("graphql", (*graphql).clone().into()),
+                            Some(graphql) => ("graphql", graphql.query.clone()),
                             None => ("graphql", "".to_string()),
                         },
 
@@ -326,6 +414,14 @@ impl From<PostmanJson> for PersistedProject {
                     None => ("raw", "".to_string()),
                 };
 
+                let graphql_variables = match &postman_item.request.body {
+                    Some(PostmanBody {
+                        graphql: Some(graphql),
+                        ..
+                    }) => graphql.variables.clone(),
+                    _ => "".to_string(),
+                };
+
                 let raw_type = match &postman_item.request.body {
                     // Some(body) => body.options.raw.language.to_string(),
                     Some(body) => match &body.options {
@@ -351,6 +447,35 @@ impl From<PostmanJson> for PersistedProject {
                     body,
                     body_mode: body_mode.to_string(),
                     raw_type,
+                    graphql_variables,
+                    disabled_params: postman_item
+                        .request
+                        .url
+                        .query
+                        .iter()
+                        .filter(|query_param| query_param.disabled)
+                        .map(|query_param| QueryParam {
+                            name: query_param.key.clone(),
+                            value: query_param.value.clone().unwrap_or_default(),
+                        })
+                        .collect(),
+                    path_params: postman_item
+                        .request
+                        .url
+                        .variable
+                        .iter()
+                        .map(|url_variable| PathParam {
+                            name: url_variable.key.clone(),
+                            value: url_variable.value.clone().unwrap_or_default(),
+                        })
+                        .collect(),
+                    variables: vec![],
+                    pre_request_script: get_event_script(&postman_item.event, "prerequest"),
+                    post_response_script: get_event_script(&postman_item.event, "test"),
+                    response_query: String::new(),
+                    diff_ignore_paths: vec![],
+                    snapshot_ignore_headers: vec![],
+                    response_schema: String::new(),
                 }
             })
             .collect();
@@ -375,6 +500,7 @@ impl From<PostmanJson> for PersistedProject {
                     system: postman_variable.system,
                     disabled: postman_variable.disabled,
                     private: None,
+                    vault_id: None,
                 })
                 .collect(),
             None => vec![],
@@ -384,6 +510,10 @@ impl From<PostmanJson> for PersistedProject {
             name: info.name,
             endpoints,
             variable,
+            client_certificates: vec![],
+            dotenv_files: vec![],
+            pre_request_script: get_event_script(&postman_json.event, "prerequest"),
+            post_response_script: get_event_script(&postman_json.event, "test"),
         }
     }
 }
@@ -423,6 +553,18 @@ impl From<PersistedProject> for PostmanJson {
                     .collect();
 
                 let body = match content_type.as_str() {
+                    _ if is_graphql_mode(&endpoint.body_mode) => Some(PostmanBody {
+                        mode: PostmanBodyMode::GraphQL,
+                        urlencoded: None,
+                        raw: None,
+                        graphql: Some(GraphQL {
+                            query: endpoint.body.clone(),
+                            variables: endpoint.graphql_variables.clone(),
+                        }),
+                        formdata: None,
+                        options: None,
+                    }),
+
                     "multipart/x-form-data" => {
                         todo!()
                     }
@@ -451,6 +593,21 @@ impl From<PersistedProject> for PostmanJson {
                 let request = PostmanRequest {
                     url: PostmanRequestUrl {
                         raw: endpoint.url.clone(),
+                        query: get_params_table(&endpoint.url, &endpoint.disabled_params)
+                            .into_iter()
+                            .map(|(param, enabled)| PostmanQueryParam {
+                                key: param.name,
+                                value: Some(param.value),
+                                disabled: !enabled,
+                            })
+                            .collect(),
+                        variable: get_path_params_table(&endpoint.url, &endpoint.path_params)
+                            .into_iter()
+                            .map(|param| PostmanUrlVariable {
+                                key: param.name,
+                                value: Some(param.value),
+                            })
+                            .collect(),
                     },
                     // TODO: Add descriptiong field/input in endpoint creation
                     description: Some("".to_string()),
@@ -459,10 +616,12 @@ impl From<PersistedProject> for PostmanJson {
                     body,
                 };
 
+                // NOTE: Tome scripts are Rhai, so they are not exported as Postman events
                 PostmanItem {
                     id: Some(id),
                     request,
                     name: endpoint.name.clone(),
+                    event: vec![],
                 }
             })
             .collect();
@@ -501,6 +660,7 @@ impl From<PersistedProject> for PostmanJson {
             info,
             item,
             variable,
+            event: vec![],
         }
     }
 }
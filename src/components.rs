@@ -9,6 +9,7 @@ pub mod button;
 pub mod confirm_action_window;
 pub mod dashboard;
 pub mod edit_header_selector;
+pub mod edit_param_selector;
 pub mod edit_input;
 pub mod floating_windows;
 pub mod focusable_section;
@@ -18,7 +19,10 @@ pub mod method_selector;
 pub mod options;
 pub mod project_window;
 pub mod request_headers_editor;
+pub mod response_diff;
 pub mod response_renderer;
+pub mod response_tree;
+pub mod response_validation;
 pub mod row;
 pub mod syntax_highlighter;
 pub mod textarea;
@@ -17,12 +17,17 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     app::GlobalEventHandler,
-    projects::{Header, HeaderState},
+    projects::{Header, HeaderState, PathParam, QueryParam},
     templates::template,
     theme::{get_app_theme, AppTheme},
 };
 
-use super::{dashboard::DashboardMessageHandler, floating_windows::FloatingWindow, send_message};
+use super::{
+    dashboard::{DashboardMessageHandler, HeaderWindowTarget},
+    edit_param_selector::{update_path_param, update_query_params},
+    floating_windows::FloatingWindow,
+    send_message,
+};
 
 #[derive(Default)]
 pub struct AddHeaderWindow {
@@ -56,6 +61,9 @@ impl AddHeaderWindow {
 #[derive(Deserialize, Serialize)]
 pub enum AddHeaderWindowMessages {
     Specifically((String, Header, Vec<String>)),
+
+    /// Opens the window with a title to add or edit a query param
+    QueryParam((String, Header)),
 }
 
 impl AddHeaderWindow {
@@ -119,6 +127,7 @@ pub struct AddHeaderWindowState {
     active: bool,
 
     header: Value<NewHeader>,
+    title: Value<String>,
 
     app_theme: Value<AppTheme>,
 
@@ -159,6 +168,7 @@ impl AddHeaderWindowState {
             current_names: vec![],
 
             header: NewHeader::default().into(),
+            title: "Add Header".to_string().into(),
             unique_name_error: "".to_string().into(),
 
             success_color_focused: submit_bg,
@@ -177,7 +187,7 @@ impl DashboardMessageHandler for AddHeaderWindow {
         state: &mut super::dashboard::DashboardState,
         mut context: Context<'_, super::dashboard::DashboardState>,
         _: Elements<'_, '_>,
-        _component_ids: Ref<'_, HashMap<String, ComponentId<String>>>,
+        component_ids: Ref<'_, HashMap<String, ComponentId<String>>>,
     ) {
         let event: String = ident.into();
         match event.as_str() {
@@ -204,6 +214,49 @@ impl DashboardMessageHandler for AddHeaderWindow {
                 state.floating_window.set(FloatingWindow::None);
                 context.set_focus("id", "app");
 
+                let target = std::mem::take(&mut state.header_window_target);
+                if let HeaderWindowTarget::QueryParam(index) = target {
+                    state.new_header_name.set("".to_string());
+                    state.new_header_value.set("".to_string());
+
+                    if header_name.trim().is_empty() {
+                        return;
+                    }
+
+                    let param = QueryParam {
+                        name: header_name,
+                        value: header_value,
+                    };
+
+                    let mut rows = state.get_query_params();
+                    match index.and_then(|index| rows.get_mut(index)) {
+                        Some(row) => row.0 = param,
+                        None => rows.push((param, true)),
+                    }
+
+                    update_query_params(&rows, state, &context, &component_ids);
+
+                    return;
+                }
+
+                if let HeaderWindowTarget::PathParam(index) = target {
+                    state.new_header_name.set("".to_string());
+                    state.new_header_value.set("".to_string());
+
+                    if header_name.trim().is_empty() {
+                        return;
+                    }
+
+                    let param = PathParam {
+                        name: header_name,
+                        value: header_value,
+                    };
+
+                    update_path_param(index, param, state, &context, &component_ids);
+
+                    return;
+                }
+
                 if header_name.trim().is_empty() || header_value.trim().is_empty() {
                     return;
                 }
@@ -217,6 +270,7 @@ impl DashboardMessageHandler for AddHeaderWindow {
                 state.endpoint.to_mut().headers.push(header);
             }
             "add_header__cancel" => {
+                state.header_window_target = HeaderWindowTarget::Headers;
                 state.floating_window.set(FloatingWindow::None);
                 state.new_header_name.set("".to_string());
                 state.new_header_value.set("".to_string());
@@ -274,6 +328,7 @@ impl Component for AddHeaderWindow {
         #[allow(clippy::single_match)]
         match message.as_str() {
             "open" => {
+                state.title.set("Add Header".to_string());
                 context.set_focus("id", "header_name_input");
 
                 state.active = true;
@@ -306,6 +361,19 @@ impl Component for AddHeaderWindow {
 
                         state.active = true;
                     }
+
+                    AddHeaderWindowMessages::QueryParam((title, param)) => {
+                        state.title.set(title);
+                        state.header.set(NewHeader {
+                            name: param.name.into(),
+                            value: param.value.into(),
+                            common: String::from(""),
+                        });
+
+                        self.set_values_for_inputs(state, context);
+
+                        state.active = true;
+                    }
                 }
             }
         }
@@ -248,6 +248,16 @@ impl Component for ConfirmActionWindow {
                                 }),
                             ),
 
+                            ConfirmAction::ConfirmReplaceRequestBody(ConfirmDetails {
+                                data: path,
+                                ..
+                            }) => DashboardMessages::Confirmations(
+                                ConfirmAction::ConfirmationReplaceRequestBody(ConfirmationAnswer {
+                                    data: path.clone(),
+                                    answer,
+                                }),
+                            ),
+
                             _ => unreachable!(),
                         };
 
@@ -303,6 +313,11 @@ impl Component for ConfirmActionWindow {
                 state.message.set(delete_header_details.message.clone());
             }
 
+            ConfirmAction::ConfirmReplaceRequestBody(replace_body_details) => {
+                state.title.set(replace_body_details.title.clone());
+                state.message.set(replace_body_details.message.clone());
+            }
+
             ConfirmAction::ConfirmDeletePersistedVariable(delete_persisted_variable_message) => {
                 state
                     .title
@@ -14,7 +14,10 @@ use std::{
     collections::HashMap,
     rc::Rc,
 };
-use std::{ops::Deref, sync::Arc};
+use std::{
+    ops::Deref,
+    sync::{atomic::AtomicBool, Arc},
+};
 
 use arboard::Clipboard;
 use serde::{Deserialize, Serialize};
@@ -22,10 +25,15 @@ use serde::{Deserialize, Serialize};
 use crate::{
     app::GlobalEventHandler,
     fs::save_response,
+    graphql::{complete, is_graphql_mode, load_schema, GraphQLSchema},
     messages::confirm_actions::ConfirmAction,
     options::get_button_caps,
-    projects::{Header, PersistedVariable},
-    requests::do_request,
+    path_params::{get_path_params_table, rename_path_param},
+    projects::{Header, PathParam, PersistedVariable, QueryParam, QueryParamState},
+    query_params::{apply_params_table, get_params_table},
+    requests::{do_request, preview::preview_request},
+    response_files::ResponseFiles,
+    snapshots::{save_snapshot, Snapshot},
     templates::template,
     theme::{get_app_theme, update_component_theme},
 };
@@ -43,6 +51,9 @@ use super::{
         edit_endpoint_name::EditEndpointNameMessages, edit_project_name::EditProjectNameMessages,
         FloatingWindow,
     },
+    response_diff::ResponseDiffMessages,
+    response_tree::ResponseTreeMessages,
+    response_validation::ResponseValidationMessages,
     send_message,
     syntax_highlighter::get_highlight_theme,
     textarea::TextAreaMessages,
@@ -50,9 +61,10 @@ use super::{
 };
 use super::{
     edit_header_selector::EditHeaderSelectorMessages,
+    edit_param_selector::{open_param_window, EditParamSelectorMessages},
     floating_windows::{
         add_project_variable::AddProjectVariableMessages,
-        endpoints_selector::EndpointsSelectorMessages,
+        endpoints_selector::EndpointsSelectorMessages, request_preview::RequestPreviewMessages,
     },
 };
 
@@ -65,10 +77,29 @@ mod keyboard_events;
 pub enum DashboardDisplay {
     RequestBody,
     RequestHeadersEditor,
+    RequestParamsEditor,
+    RequestScripts,
     ResponseBody,
+    ResponseTree,
+    ResponseDiff,
+    ResponseValidation,
     ResponseHeaders,
 }
 
+/// Where the add header window submits to, the window is shared by the
+/// request headers and the query params tables
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum HeaderWindowTarget {
+    #[default]
+    Headers,
+
+    /// Adds a new query param, or edits the param at the index of the params table
+    QueryParam(Option<usize>),
+
+    /// Edits the path param at the index of the path params table
+    PathParam(usize),
+}
+
 impl State for DashboardDisplay {
     fn to_common(&self) -> Option<CommonVal<'_>> {
         match self {
@@ -76,7 +107,12 @@ impl State for DashboardDisplay {
             DashboardDisplay::RequestHeadersEditor => {
                 Some(CommonVal::Str("request_headers_editor"))
             }
+            DashboardDisplay::RequestParamsEditor => Some(CommonVal::Str("request_params_editor")),
+            DashboardDisplay::RequestScripts => Some(CommonVal::Str("request_scripts")),
             DashboardDisplay::ResponseBody => Some(CommonVal::Str("response_body")),
+            DashboardDisplay::ResponseTree => Some(CommonVal::Str("response_tree")),
+            DashboardDisplay::ResponseDiff => Some(CommonVal::Str("response_diff")),
+            DashboardDisplay::ResponseValidation => Some(CommonVal::Str("response_validation")),
             DashboardDisplay::ResponseHeaders => Some(CommonVal::Str("response_headers")),
         }
     }
@@ -95,8 +131,9 @@ pub struct DashboardState {
 
     pub endpoint: Value<Endpoint>,
     pub response_headers: Value<List<HeaderState>>,
-    pub response: Value<String>,
     pub response_body_window_label: Value<String>,
+    pub response_timing: Value<String>,
+    pub response_sizes: Value<String>,
 
     pub error_message: Value<String>,
     pub message: Value<String>,
@@ -111,6 +148,20 @@ pub struct DashboardState {
     pub edit_header_name: Value<String>,
     pub edit_header_value: Value<String>,
 
+    #[state_ignore]
+    pub header_window_target: HeaderWindowTarget,
+
+    /// Stops the benchmark that is running
+    #[state_ignore]
+    pub benchmark_cancel: Option<Arc<AtomicBool>>,
+
+    /// Stops the collection run that is running
+    #[state_ignore]
+    pub runner_cancel: Option<Arc<AtomicBool>>,
+
+    pub query_params: Value<List<QueryParamState>>,
+    pub path_params: Value<List<QueryParamState>>,
+
     pub project: Value<Project>,
     // pub project_count: Value<u8>,
     pub endpoint_count: Value<u8>,
@@ -119,6 +170,8 @@ pub struct DashboardState {
     pub filter_total: Value<usize>,
     pub filter_nav_index: Value<usize>,
 
+    pub graphql_completions: Value<String>,
+
     pub app_bg: Value<String>,
     pub app_theme: Value<AppTheme>,
     pub button_cap_left: Value<String>,
@@ -148,15 +201,21 @@ impl DashboardState {
             endpoint_count: 0.into(),
             endpoint: Endpoint::new().into(),
 
-            response: "".to_string().into(),
             message: "".to_string().into(),
             message_label: "".to_string().into(),
             response_body_window_label: "".to_string().into(),
+            response_timing: "".to_string().into(),
+            response_sizes: "".to_string().into(),
             error_message: "".to_string().into(),
             new_header_name: "".to_string().into(),
             new_header_value: "".to_string().into(),
             edit_header_name: "".to_string().into(),
             edit_header_value: "".to_string().into(),
+            header_window_target: HeaderWindowTarget::Headers,
+            benchmark_cancel: None,
+            runner_cancel: None,
+            query_params: List::empty(),
+            path_params: List::empty(),
             floating_window: FloatingWindow::None.into(),
             // main_display: Value::<DashboardDisplay>::new(DashboardDisplay::RequestBody),
             main_display: DashboardDisplay::RequestBody.into(),
@@ -191,15 +250,109 @@ impl DashboardState {
             filter_indexes: List::empty(),
             filter_total: 0.into(),
             filter_nav_index: 0.into(),
+            graphql_completions: "".to_string().into(),
             app_bg: "#000000".to_string().into(),
             app_theme: app_theme.into(),
         }
     }
+
+    /// Rebuilds the path and query params tables from the endpoint url, its
+    /// path param values and its disabled params
+    pub fn refresh_query_params(&mut self) {
+        let path_rows = self.get_path_params();
+        let rows = self.get_query_params();
+
+        while self.path_params.len() > 0 {
+            self.path_params.remove(0);
+        }
+
+        path_rows.iter().for_each(|param| {
+            self.path_params.push(param.into());
+        });
+
+        while self.query_params.len() > 0 {
+            self.query_params.remove(0);
+        }
+
+        rows.iter().for_each(|(param, enabled)| {
+            self.query_params.push((param, *enabled).into());
+        });
+    }
+
+    /// Sets the path param at the index of the path params table, renaming
+    /// the param in the url when the name was changed
+    pub fn set_path_param(&mut self, index: usize, param: PathParam) -> String {
+        let mut url = self.endpoint.to_ref().url.to_ref().to_string();
+
+        let Some(current) = self.get_path_params().into_iter().nth(index) else {
+            return url;
+        };
+
+        if current.name != param.name {
+            url = rename_path_param(&url, &current.name, &param.name);
+        }
+
+        {
+            let mut endpoint = self.endpoint.to_mut();
+            endpoint.url.set(url.clone());
+            endpoint
+                .path_params
+                .retain(|existing| existing.name != current.name && existing.name != param.name);
+            endpoint.path_params.push(param);
+        }
+
+        self.refresh_query_params();
+
+        url
+    }
+
+    pub fn get_path_params(&self) -> Vec<PathParam> {
+        let endpoint = self.endpoint.to_ref();
+        let url = endpoint.url.to_ref().to_string();
+
+        get_path_params_table(&url, &endpoint.path_params)
+    }
+
+    /// Writes the rows of the query params table back into the endpoint, the
+    /// returned url still has to be sent to the url input
+    pub fn set_query_params(&mut self, rows: &[(QueryParam, bool)]) -> String {
+        let url = self.endpoint.to_ref().url.to_ref().to_string();
+        let (url, disabled_params) = apply_params_table(&url, rows);
+
+        {
+            let mut endpoint = self.endpoint.to_mut();
+            endpoint.url.set(url.clone());
+            endpoint.disabled_params = disabled_params;
+        }
+
+        self.refresh_query_params();
+
+        url
+    }
+
+    pub fn get_query_params(&self) -> Vec<(QueryParam, bool)> {
+        let endpoint = self.endpoint.to_ref();
+        let url = endpoint.url.to_ref().to_string();
+
+        get_params_table(&url, &endpoint.disabled_params)
+    }
 }
 
 pub struct DashboardComponent {
     pub component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
     test: bool,
+
+    /// Cached GraphQL schemas for query completion by endpoint url. Urls
+    /// without a schema are cached as None so the disk is not read on every
+    /// key, the cache is cleared when a schema is introspected.
+    graphql_schemas: HashMap<String, Option<GraphQLSchema>>,
+
+    /// The last response, it is saved as the snapshot of the endpoint when
+    /// it is approved
+    pub last_response: Option<Snapshot>,
+
+    /// Where the body of the last response was saved
+    pub response_files: Option<ResponseFiles>,
 }
 
 impl DashboardComponent {
@@ -221,6 +374,9 @@ impl DashboardComponent {
         let dashboard = DashboardComponent {
             component_ids: ids.clone(),
             test: false,
+            graphql_schemas: HashMap::new(),
+            last_response: None,
+            response_files: None,
         };
 
         let id = builder.register_component(
@@ -435,6 +591,94 @@ impl DashboardComponent {
         );
     }
 
+    fn show_query_params(
+        &self,
+        event: Option<KeyEvent>,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let ctrl_pressed = event.map_or_else(|| false, |event| event.ctrl);
+        if ctrl_pressed {
+            return;
+        }
+
+        state.refresh_query_params();
+        state
+            .main_display
+            .set(DashboardDisplay::RequestParamsEditor);
+
+        context.set_focus("id", "app");
+    }
+
+    /// Shows the script editors of the endpoint and focuses the pre-request
+    /// or the post-response script
+    fn show_request_scripts(
+        &self,
+        post_response: bool,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        state.main_display.set(DashboardDisplay::RequestScripts);
+
+        // NOTE: The editors are refreshed every time, the endpoint could have
+        // changed since they were last shown
+        let endpoint = state.endpoint.to_ref();
+        let scripts = [
+            (
+                "pre_request_script_input",
+                endpoint.pre_request_script.to_ref().to_string(),
+            ),
+            (
+                "post_response_script_input",
+                endpoint.post_response_script.to_ref().to_string(),
+            ),
+        ];
+        drop(endpoint);
+
+        if let Ok(ids) = self.component_ids.try_borrow() {
+            for (input, script) in scripts {
+                let textarea_msg = TextAreaMessages::SetInput(script);
+                if let Ok(message) = serde_json::to_string(&textarea_msg) {
+                    let _ = send_message(input, message, &ids, context.emitter);
+                }
+            }
+        }
+
+        match post_response {
+            true => context.set_focus("id", "post_response_script_textarea"),
+            false => context.set_focus("id", "pre_request_script_textarea"),
+        }
+    }
+
+    fn open_request_preview(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let project: PersistedProject = state.project.to_ref().deref().into();
+        let endpoint: PersistedEndpoint = state.endpoint.to_ref().deref().into();
+
+        let lines = match preview_request(&project, &endpoint) {
+            Ok(lines) => lines,
+            Err(error) => {
+                self.show_error(&error.to_string(), state);
+                return;
+            }
+        };
+
+        let message = RequestPreviewMessages::Preview(lines);
+        let Ok(message) = serde_json::to_string(&message) else {
+            return;
+        };
+
+        state.floating_window.set(FloatingWindow::RequestPreview);
+        context.set_focus("id", "request_preview");
+
+        if let Ok(ids) = self.component_ids.try_borrow() {
+            let _ = send_message("request_preview", message, &ids, context.emitter);
+        }
+    }
+
     fn send_request(
         &mut self,
         state: &mut DashboardState,
@@ -460,7 +704,12 @@ impl DashboardComponent {
         match main_display {
             DashboardDisplay::RequestBody => {}
             DashboardDisplay::RequestHeadersEditor => {}
+            DashboardDisplay::RequestParamsEditor => {}
+            DashboardDisplay::RequestScripts => {}
             DashboardDisplay::ResponseBody => save_response(self, state),
+            DashboardDisplay::ResponseTree => {}
+            DashboardDisplay::ResponseDiff => {}
+            DashboardDisplay::ResponseValidation => {}
             DashboardDisplay::ResponseHeaders => {}
         }
     }
@@ -476,10 +725,15 @@ impl DashboardComponent {
         match main_display {
             DashboardDisplay::RequestBody => self.open_body_mode_selector(state, context),
             DashboardDisplay::RequestHeadersEditor => {}
+            DashboardDisplay::RequestParamsEditor => {}
+            DashboardDisplay::RequestScripts => {}
             DashboardDisplay::ResponseBody => {
                 // Copy response body to clipboard
                 self.yank_response(state)
             }
+            DashboardDisplay::ResponseTree => {}
+            DashboardDisplay::ResponseDiff => {}
+            DashboardDisplay::ResponseValidation => {}
             DashboardDisplay::ResponseHeaders => {}
         }
     }
@@ -491,21 +745,70 @@ impl DashboardComponent {
         match main_display {
             DashboardDisplay::RequestBody => {}
             DashboardDisplay::RequestHeadersEditor => self.open_edit_header_window(state, context),
+            DashboardDisplay::RequestParamsEditor => self.open_edit_param_window(state, context),
+            DashboardDisplay::RequestScripts => {}
             DashboardDisplay::ResponseBody => {
                 state.main_display.set(DashboardDisplay::ResponseHeaders)
             }
+            DashboardDisplay::ResponseTree => {}
+            DashboardDisplay::ResponseDiff => {}
+            DashboardDisplay::ResponseValidation => {}
             DashboardDisplay::ResponseHeaders => {}
         }
     }
 
+    fn focus_graphql_variables(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let body_mode = state.endpoint.to_ref().body_mode.to_ref().to_string();
+        if !is_graphql_mode(&body_mode) {
+            return;
+        }
+
+        state.main_display.set(DashboardDisplay::RequestBody);
+        context.set_focus("id", "graphql_variables_textarea");
+    }
+
+    fn update_graphql_completions(&mut self, cursor_prefix: &str, state: &mut DashboardState) {
+        let body_mode = state.endpoint.to_ref().body_mode.to_ref().to_string();
+        if !is_graphql_mode(&body_mode) {
+            state.graphql_completions.set("".to_string());
+            return;
+        }
+
+        let url = state.endpoint.to_ref().url.to_ref().to_string();
+
+        // NOTE: Without a cached schema there is nothing to complete, the
+        // schema is fetched from the Commands window
+        let schema = self
+            .graphql_schemas
+            .entry(url)
+            .or_insert_with_key(|url| load_schema(url).ok());
+
+        let Some(schema) = schema else {
+            state.graphql_completions.set("".to_string());
+            return;
+        };
+
+        let completions = complete(schema, cursor_prefix);
+        state.graphql_completions.set(completions.join("  "));
+    }
+
     fn go_back(&self, state: &mut DashboardState, context: &mut Context<'_, DashboardState>) {
         let main_display = *state.main_display.to_ref();
         match main_display {
             DashboardDisplay::RequestBody => context.set_focus("id", "textarea"),
-            DashboardDisplay::RequestHeadersEditor => {
+            DashboardDisplay::RequestHeadersEditor
+            | DashboardDisplay::RequestParamsEditor
+            | DashboardDisplay::RequestScripts => {
                 state.main_display.set(DashboardDisplay::RequestBody);
             }
-            DashboardDisplay::ResponseBody => {
+            DashboardDisplay::ResponseBody
+            | DashboardDisplay::ResponseTree
+            | DashboardDisplay::ResponseDiff
+            | DashboardDisplay::ResponseValidation => {
                 state.main_display.set(DashboardDisplay::RequestBody);
                 context.set_focus("id", "app");
             }
@@ -515,6 +818,126 @@ impl DashboardComponent {
         }
     }
 
+    fn show_response_tree(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let Some(response_files) = &self.response_files else {
+            self.show_error("Send a request to see its response as a tree", state);
+            return;
+        };
+
+        state.main_display.set(DashboardDisplay::ResponseTree);
+        context.set_focus("id", "response_tree");
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let load = ResponseTreeMessages::Load(response_files.text.clone());
+        if let Ok(message) = serde_json::to_string(&load) {
+            let _ = send_message("response_tree", message, &ids, context.emitter);
+        }
+    }
+
+    /// Shows the diff of the last response with an earlier response, or with
+    /// the approved snapshot of the endpoint when the snapshot is given
+    pub fn show_response_diff(
+        &self,
+        snapshot: Option<Snapshot>,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        state.main_display.set(DashboardDisplay::ResponseDiff);
+        context.set_focus("id", "response_diff");
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let endpoint = state.endpoint.to_ref();
+        let load = ResponseDiffMessages::Load {
+            project: state.project.to_ref().name.to_ref().to_string(),
+            endpoint: endpoint.name.to_ref().to_string(),
+            ignore_paths: endpoint.diff_ignore_paths.clone(),
+            ignore_headers: endpoint.snapshot_ignore_headers.clone(),
+            snapshot,
+            response: self.response_files.clone(),
+        };
+
+        if let Ok(message) = serde_json::to_string(&load) {
+            let _ = send_message("response_diff", message, &ids, context.emitter);
+        }
+    }
+
+    /// Shows the violations of the last response against the JSON Schema of
+    /// the endpoint
+    pub fn show_response_validation(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        state.main_display.set(DashboardDisplay::ResponseValidation);
+        context.set_focus("id", "response_validation");
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let load = ResponseValidationMessages::Load {
+            schema: state.endpoint.to_ref().response_schema.clone(),
+            body: self
+                .last_response
+                .as_ref()
+                .map(|response| response.body.clone()),
+        };
+
+        if let Ok(message) = serde_json::to_string(&load) {
+            let _ = send_message("response_validation", message, &ids, context.emitter);
+        }
+    }
+
+    /// Saves the last response as the snapshot of the endpoint, the next
+    /// responses are compared with it
+    fn approve_snapshot(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let Some(response) = &self.last_response else {
+            self.show_error("Send a request before approving its response", state);
+            return;
+        };
+
+        let project_name = state.project.to_ref().name.to_ref().to_string();
+        let endpoint_name = state.endpoint.to_ref().name.to_ref().to_string();
+
+        if let Err(error) = save_snapshot(&project_name, &endpoint_name, response) {
+            self.show_error(&format!("Could not save the snapshot: {error}"), state);
+            return;
+        }
+
+        if *state.main_display.to_ref() == DashboardDisplay::ResponseDiff {
+            self.show_response_body(state, context);
+        }
+
+        self.show_message(
+            "Snapshot",
+            &format!("Approved the last response of {endpoint_name}"),
+            state,
+        );
+    }
+
+    fn show_response_body(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        state.main_display.set(DashboardDisplay::ResponseBody);
+        context.set_focus("id", "response_renderer");
+    }
+
     fn new_project(&self, state: &mut DashboardState, context: &mut Context<'_, DashboardState>) {
         self.save_project(state, false);
 
@@ -544,6 +967,12 @@ impl DashboardComponent {
             if let Ok(message) = serde_json::to_string(&textarea_msg) {
                 let _ = send_message(
                     "request_body_input",
+                    message.clone(),
+                    &component_ids,
+                    context.emitter,
+                );
+                let _ = send_message(
+                    "graphql_variables_input",
                     message,
                     &component_ids,
                     context.emitter,
@@ -651,6 +1080,30 @@ impl DashboardComponent {
         let _ = send_message("edit_header_selector", message, &ids, context.emitter);
     }
 
+    fn open_edit_param_window(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        state.floating_window.set(FloatingWindow::EditParamSelector);
+        context.set_focus("id", "edit_param_selector");
+
+        let edit_param_selector_messages = EditParamSelectorMessages::ParamsList((
+            state.get_path_params(),
+            state.get_query_params(),
+        ));
+
+        let Ok(message) = serde_json::to_string(&edit_param_selector_messages) else {
+            return;
+        };
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message("edit_param_selector", message, &ids, context.emitter);
+    }
+
     fn open_projects_window(
         &self,
         state: &mut DashboardState,
@@ -753,7 +1206,22 @@ impl DashboardComponent {
             return;
         };
 
-        let operation_text = state.response.to_ref().clone();
+        let Some(response_files) = &self.response_files else {
+            self.show_error("Send a request before copying its response", state);
+            return;
+        };
+
+        let operation_text = match response_files.read_text() {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                self.show_error("The response is too large to copy, save it with V", state);
+                return;
+            }
+            Err(error) => {
+                self.show_error(&error.to_string(), state);
+                return;
+            }
+        };
         let set_operation = clipboard.set();
         match set_operation.text(operation_text) {
             Ok(_) => self.show_message("Clipboard", "Response copied to clipboard", state),
@@ -799,6 +1267,18 @@ impl DashboardComponent {
         );
     }
 
+    fn open_add_param_window(
+        &self,
+        state: &mut DashboardState,
+        context: &mut Context<'_, DashboardState>,
+    ) {
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        open_param_window(None, state, context, &ids);
+    }
+
     fn confirm_action(
         &self,
         confirm_action: ConfirmAction,
@@ -857,7 +1337,13 @@ pub enum DashboardMessages {
     ShowError(String),
     Confirmations(ConfirmAction),
     BackToRequest,
+    ShowResponseTree,
+    ShowResponseDiff,
+    ShowResponseValidation,
+    ShowResponseBody,
+    ApproveSnapshot,
     KeyboardEvent(KeebEvent),
+    GraphQLSchemaUpdate,
 }
 
 fn update_theme(state: &mut DashboardState) {
@@ -3,7 +3,7 @@ use std::ops::Deref;
 use anathema::prelude::Context;
 
 use crate::{
-    components::floating_windows::FloatingWindow,
+    components::floating_windows::{file_selector::set_binary_body_file, FloatingWindow},
     messages::confirm_actions::ConfirmAction,
     projects::{
         delete_endpoint, delete_project, save_project, Endpoint, PersistedEndpoint,
@@ -200,6 +200,16 @@ pub fn handle_confirmations(
             }
         }
 
+        ConfirmAction::ConfirmationReplaceRequestBody(replace_body_answer) => {
+            let ids = dashboard.component_ids.try_borrow();
+            if let (true, Ok(ids)) = (replace_body_answer.answer, ids) {
+                set_binary_body_file(state, &replace_body_answer.data, &ids, context.emitter);
+            }
+
+            state.floating_window.set(FloatingWindow::None);
+            context.set_focus("id", "app");
+        }
+
         _ => {}
     }
 }
@@ -7,22 +7,28 @@ use crate::components::{
     add_header_window::AddHeaderWindow,
     confirm_action_window::ConfirmActionWindow,
     edit_header_selector::EditHeaderSelector,
+    edit_param_selector::EditParamSelector,
     floating_windows::{
         add_project_variable::{AddProjectVariable, AddProjectVariableMessages},
+        benchmark::Benchmark,
         body_mode_selector::BodyModeSelector,
         code_gen::CodeGen,
+        collection_runner::CollectionRunner,
         commands::Commands,
         edit_endpoint_name::EditEndpointName,
         edit_project_name::EditProjectName,
         endpoints_selector::EndpointsSelector,
         file_selector::FileSelector,
+        form_field_name::FormFieldName,
         project_variables::ProjectVariables,
+        request_preview::RequestPreview,
         FloatingWindow,
     },
     method_selector::MethodSelector,
     project_window::ProjectWindow,
     send_message,
 };
+use crate::response_diff::{parse_ignore_paths, split_ignore_entries};
 
 use super::{DashboardComponent, DashboardDisplay, DashboardMessageHandler, DashboardState};
 
@@ -37,6 +43,8 @@ pub fn associated_functions(
     let current_display = *state.main_display.to_ref();
     let is_request_body = current_display == DashboardDisplay::RequestBody;
     let is_headers_editor = current_display == DashboardDisplay::RequestHeadersEditor;
+    let is_params_editor = current_display == DashboardDisplay::RequestParamsEditor;
+    let is_scripts_editor = current_display == DashboardDisplay::RequestScripts;
     let is_response_body = current_display == DashboardDisplay::ResponseBody;
     let is_response_headers = current_display == DashboardDisplay::ResponseHeaders;
 
@@ -80,6 +88,23 @@ pub fn associated_functions(
             dashboard.send_save_response(state);
         }
 
+        "response_query_change" => {
+            state.endpoint.to_mut().response_query = value.to_string();
+        }
+
+        "response_diff_ignore_change" => {
+            let entries = parse_ignore_paths(&value.to_string());
+            let (paths, headers) = split_ignore_entries(entries);
+
+            let mut endpoint = state.endpoint.to_mut();
+            endpoint.diff_ignore_paths = paths;
+            endpoint.snapshot_ignore_headers = headers;
+        }
+
+        "response_schema_change" => {
+            state.endpoint.to_mut().response_schema = value.to_string();
+        }
+
         "send_request_click" if is_request_body => {
             dashboard.send_request(state, &mut context, &elements)
         }
@@ -88,6 +113,18 @@ pub fn associated_functions(
             dashboard.show_request_headers(None, state, &mut context)
         }
 
+        "show_query_params" if is_request_body => {
+            dashboard.show_query_params(None, state, &mut context)
+        }
+
+        "show_request_preview" if is_request_body => {
+            dashboard.open_request_preview(state, &mut context)
+        }
+
+        "show_request_scripts" if is_request_body => {
+            dashboard.show_request_scripts(false, state, &mut context)
+        }
+
         "send_request_click_request_body" if is_headers_editor => {
             dashboard.send_request(state, &mut context, &elements)
         }
@@ -104,6 +141,38 @@ pub fn associated_functions(
             state.main_display.set(DashboardDisplay::RequestBody)
         }
 
+        "send_request_click_params" if is_params_editor => {
+            dashboard.send_request(state, &mut context, &elements)
+        }
+
+        "add_param_click" if is_params_editor => {
+            dashboard.open_add_param_window(state, &mut context)
+        }
+
+        "edit_param_click" if is_params_editor => {
+            dashboard.open_edit_param_window(state, &mut context)
+        }
+
+        "back_to_request_from_params_click" if is_params_editor => {
+            state.main_display.set(DashboardDisplay::RequestBody)
+        }
+
+        "send_request_click_scripts" if is_scripts_editor => {
+            dashboard.send_request(state, &mut context, &elements)
+        }
+
+        "pre_request_script_click" if is_scripts_editor => {
+            dashboard.show_request_scripts(false, state, &mut context)
+        }
+
+        "post_response_script_click" if is_scripts_editor => {
+            dashboard.show_request_scripts(true, state, &mut context)
+        }
+
+        "back_to_request_from_scripts_click" if is_scripts_editor => {
+            state.main_display.set(DashboardDisplay::RequestBody)
+        }
+
         "save_project_click" => dashboard.save_project(state, true),
         "save_endpoint_click" => dashboard.save_endpoint(state, true),
         "swap_project_click" => dashboard.open_projects_window(state, &mut context),
@@ -191,6 +260,32 @@ pub fn associated_functions(
                 Commands::handle_message(value, ident, state, context, elements, component_ids);
             }
 
+            "request_preview" => RequestPreview::handle_message(
+                value,
+                ident,
+                state,
+                context,
+                elements,
+                component_ids,
+            ),
+
+            "collection_runner" => CollectionRunner::handle_message(
+                value,
+                ident,
+                state,
+                context,
+                elements,
+                component_ids,
+            ),
+
+            "benchmark" => {
+                Benchmark::handle_message(value, ident, state, context, elements, component_ids);
+            }
+
+            "form_field_name" => {
+                FormFieldName::handle_message(value, ident, state, context, elements, component_ids)
+            }
+
             "codegen" => {
                 CodeGen::handle_message(value, ident, state, context, elements, component_ids);
             }
@@ -217,6 +312,17 @@ pub fn associated_functions(
                 );
             }
 
+            "edit_param_selector" => {
+                EditParamSelector::handle_message(
+                    value,
+                    ident,
+                    state,
+                    context,
+                    elements,
+                    component_ids,
+                );
+            }
+
             "method_selector" => {
                 MethodSelector::handle_message(
                     value,
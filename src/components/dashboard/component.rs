@@ -52,6 +52,26 @@ impl Component for DashboardComponent {
                     context.set_focus("id", "app");
                 }
 
+                DashboardMessages::ShowResponseTree => {
+                    self.show_response_tree(state, &mut context);
+                }
+
+                DashboardMessages::ShowResponseDiff => {
+                    self.show_response_diff(None, state, &mut context);
+                }
+
+                DashboardMessages::ShowResponseValidation => {
+                    self.show_response_validation(state, &mut context);
+                }
+
+                DashboardMessages::ApproveSnapshot => {
+                    self.approve_snapshot(state, &mut context);
+                }
+
+                DashboardMessages::ShowResponseBody => {
+                    self.show_response_body(state, &mut context);
+                }
+
                 DashboardMessages::Confirmations(confirm_action) => {
                     self.confirm_action(confirm_action, state, context);
                 }
@@ -64,6 +84,10 @@ impl Component for DashboardComponent {
                     self.show_error(&message, state);
                 }
 
+                DashboardMessages::GraphQLSchemaUpdate => {
+                    self.graphql_schemas.clear();
+                }
+
                 DashboardMessages::ThemeUpdate => {
                     // TODO: Use this message again when the state update bug is fixed in anathema
                     // println!("Changing dashboard theme");
@@ -98,6 +122,7 @@ impl Component for DashboardComponent {
                     TextInputMessages::Update(text_update) => match text_update.id.as_str() {
                         "endpoint_url_input" => {
                             state.endpoint.to_mut().url.set(text_update.value);
+                            state.refresh_query_params();
                         }
 
                         _ => {}
@@ -122,12 +147,39 @@ impl Component for DashboardComponent {
                     },
                 },
 
-                // TODO: Refactor this message to not be 100% coupled to only editing the
-                // endpoint body
                 DashboardMessages::TextArea(text_area_message) => match text_area_message {
-                    TextAreaMessages::InputChange(value) => {
-                        state.endpoint.to_mut().body.set(value);
-                    }
+                    TextAreaMessages::InputUpdate(text_update) => match text_update.id.as_str() {
+                        "endpoint_request_body" => {
+                            state.endpoint.to_mut().body.set(text_update.value);
+                            self.update_graphql_completions(&text_update.cursor_prefix, state);
+                        }
+
+                        "endpoint_graphql_variables" => {
+                            state
+                                .endpoint
+                                .to_mut()
+                                .graphql_variables
+                                .set(text_update.value);
+                        }
+
+                        "endpoint_pre_request_script" => {
+                            state
+                                .endpoint
+                                .to_mut()
+                                .pre_request_script
+                                .set(text_update.value);
+                        }
+
+                        "endpoint_post_response_script" => {
+                            state
+                                .endpoint
+                                .to_mut()
+                                .post_response_script
+                                .set(text_update.value);
+                        }
+
+                        _ => {}
+                    },
 
                     // NOTE: SetInput is only used for sending the TextArea a new value
                     TextAreaMessages::SetInput(_) => todo!(),
@@ -165,13 +217,19 @@ impl Component for DashboardComponent {
     ) {
         update_theme(state);
 
-        match *state.main_display.to_ref() {
+        let main_display = *state.main_display.to_ref();
+        match main_display {
             DashboardDisplay::RequestBody => context.set_focus("id", "request"),
 
             DashboardDisplay::RequestHeadersEditor => {
                 context.set_focus("id", "request_headers_editor")
             }
+            DashboardDisplay::RequestParamsEditor => state.refresh_query_params(),
+            DashboardDisplay::RequestScripts => {}
             DashboardDisplay::ResponseBody => context.set_focus("id", "response_renderer"),
+            DashboardDisplay::ResponseTree => context.set_focus("id", "response_tree"),
+            DashboardDisplay::ResponseDiff => context.set_focus("id", "response_diff"),
+            DashboardDisplay::ResponseValidation => context.set_focus("id", "response_validation"),
             DashboardDisplay::ResponseHeaders => context.set_focus("id", "response_headers"),
         }
 
@@ -184,6 +242,7 @@ impl Component for DashboardComponent {
             FloatingWindow::Error => {}
             FloatingWindow::Message => {}
             FloatingWindow::EditHeaderSelector => context.set_focus("id", "edit_header_selector"),
+            FloatingWindow::EditParamSelector => context.set_focus("id", "edit_param_selector"),
             FloatingWindow::Project => context.set_focus("id", "project_selector"),
             FloatingWindow::ConfirmAction => context.set_focus("id", "confirm_action_window"),
             FloatingWindow::ChangeEndpointName => context.set_focus("id", "edit_endpoint_name"),
@@ -194,9 +253,20 @@ impl Component for DashboardComponent {
             FloatingWindow::Commands => context.set_focus("id", "commands_window"),
             FloatingWindow::CodeGen => context.set_focus("id", "codegen_window"),
             FloatingWindow::PostmanFileSelector => context.set_focus("id", "postman_file_selector"),
+            FloatingWindow::RequestBodyFileSelector => {
+                context.set_focus("id", "request_body_file_selector")
+            }
+            FloatingWindow::DotenvFileSelector => context.set_focus("id", "dotenv_file_selector"),
             FloatingWindow::BodyModeSelector => context.set_focus("id", "body_mode_selector"),
             FloatingWindow::AddProjectVariable => context.set_focus("id", "add_project_variable"),
             FloatingWindow::ViewProjectVariables => context.set_focus("id", "project_variables"),
+            FloatingWindow::RequestPreview => context.set_focus("id", "request_preview"),
+            FloatingWindow::CollectionRunner => context.set_focus("id", "collection_runner"),
+            FloatingWindow::RunnerDataFileSelector => {
+                context.set_focus("id", "runner_data_file_selector")
+            }
+            FloatingWindow::Benchmark => context.set_focus("id", "benchmark_window"),
+            FloatingWindow::FormFieldName => context.set_focus("id", "form_field_name"),
         }
 
         if self.test {
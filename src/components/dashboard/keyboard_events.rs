@@ -52,9 +52,21 @@ pub fn keyboard_events(
                 // Show request body editor window
                 'b' => dashboard.go_back(state, &mut context),
 
+                // Focus the GraphQL variables editor
+                'g' => dashboard.focus_graphql_variables(state, &mut context),
+
                 // Show request headers editor window
                 'd' => dashboard.show_request_headers(Some(event), state, &mut context),
 
+                // Show query params editor window
+                'l' => dashboard.show_query_params(Some(event), state, &mut context),
+
+                // Preview the request with variables replaced
+                'k' => dashboard.open_request_preview(state, &mut context),
+
+                // Edit the pre-request script
+                'x' => dashboard.show_request_scripts(false, state, &mut context),
+
                 // Open Endpoints selector
                 'e' => {
                     dashboard.open_endpoints_selector(state, &mut context);
@@ -78,7 +90,17 @@ pub fn keyboard_events(
                         // Open header window
                         dashboard.open_add_header_window(state, &mut context);
                     }
+                    DashboardDisplay::RequestParamsEditor => {
+                        dashboard.open_add_param_window(state, &mut context);
+                    }
+                    DashboardDisplay::RequestScripts => {
+                        // Edit the post-response script
+                        dashboard.show_request_scripts(true, state, &mut context);
+                    }
                     DashboardDisplay::ResponseBody => {}
+                    DashboardDisplay::ResponseTree => {}
+                    DashboardDisplay::ResponseDiff => {}
+                    DashboardDisplay::ResponseValidation => {}
                     DashboardDisplay::ResponseHeaders => {}
                 },
 
@@ -0,0 +1,567 @@
+use std::{
+    cell::{Ref, RefCell},
+    cmp::{max, min},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use anathema::{
+    component::{Component, ComponentId, MouseEvent},
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    components::dashboard::{DashboardMessageHandler, DashboardState, HeaderWindowTarget},
+    projects::{Header, PathParam, QueryParam, QueryParamState},
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::{
+    add_header_window::AddHeaderWindowMessages, floating_windows::FloatingWindow, send_message,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum EditParamSelectorMessages {
+    ParamsList((Vec<PathParam>, Vec<(QueryParam, bool)>)),
+}
+
+/// A row of the selector, path params are listed before the query params and
+/// can only be edited
+#[derive(Debug)]
+enum ParamItem {
+    Path(PathParam),
+    Query(QueryParam, bool),
+}
+
+#[derive(Default, State)]
+pub struct EditParamSelectorState {
+    #[state_ignore]
+    active: bool,
+
+    cursor: Value<u8>,
+    current_first_index: Value<u8>,
+    current_last_index: Value<u8>,
+    visible_rows: Value<u8>,
+    window_list: Value<List<QueryParamState>>,
+    count: Value<u8>,
+    selected_item: Value<String>,
+    app_theme: Value<AppTheme>,
+}
+
+impl EditParamSelectorState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        EditParamSelectorState {
+            active: false,
+            cursor: 0.into(),
+            count: 0.into(),
+            current_first_index: 0.into(),
+            current_last_index: 4.into(),
+            visible_rows: 5.into(),
+            window_list: List::empty(),
+            selected_item: "".to_string().into(),
+            app_theme: app_theme.into(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct EditParamSelector {
+    #[allow(dead_code)]
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+    items_list: Vec<ParamItem>,
+}
+
+impl EditParamSelector {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "edit_param_selector",
+            template("templates/edit_param_selector"),
+            EditParamSelector::new(ids.clone()),
+            EditParamSelectorState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("edit_param_selector"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut EditParamSelectorState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    pub fn new(component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>) -> Self {
+        EditParamSelector {
+            component_ids,
+            items_list: vec![],
+        }
+    }
+
+    fn move_cursor_down(&self, state: &mut EditParamSelectorState) {
+        let last_complete_list_index = self.items_list.len().saturating_sub(1);
+        let new_cursor = min(*state.cursor.to_ref() + 1, last_complete_list_index as u8);
+        state.cursor.set(new_cursor);
+
+        let mut first_index = *state.current_first_index.to_ref();
+        let mut last_index = *state.current_last_index.to_ref();
+
+        if new_cursor > last_index {
+            last_index = new_cursor;
+            first_index = new_cursor - (*state.visible_rows.to_ref() - 1);
+
+            state.current_first_index.set(first_index);
+            state.current_last_index.set(last_index);
+        }
+
+        self.update_list(
+            first_index.into(),
+            last_index.into(),
+            new_cursor.into(),
+            state,
+        );
+    }
+
+    fn move_cursor_up(&self, state: &mut EditParamSelectorState) {
+        let new_cursor = max(state.cursor.to_ref().saturating_sub(1), 0);
+        state.cursor.set(new_cursor);
+
+        let mut first_index = *state.current_first_index.to_ref();
+        let mut last_index = *state.current_last_index.to_ref();
+
+        if new_cursor < first_index {
+            first_index = new_cursor;
+            last_index = new_cursor + (*state.visible_rows.to_ref() - 1);
+
+            state.current_first_index.set(first_index);
+            state.current_last_index.set(last_index);
+        }
+
+        self.update_list(
+            first_index.into(),
+            last_index.into(),
+            new_cursor.into(),
+            state,
+        );
+    }
+
+    fn update_list(
+        &self,
+        first_index: usize,
+        last_index: usize,
+        selected_index: usize,
+        state: &mut EditParamSelectorState,
+    ) {
+        loop {
+            if state.window_list.len() > 0 {
+                state.window_list.pop_front();
+            } else {
+                break;
+            }
+        }
+
+        if self.items_list.is_empty() {
+            return;
+        }
+
+        let range_end = min(last_index, self.items_list.len().saturating_sub(1));
+        let display_items = &self.items_list[first_index..=range_end];
+
+        let app_theme = state.app_theme.to_ref();
+        let background = app_theme.overlay_background.to_ref().clone();
+        let foreground = app_theme.overlay_foreground.to_ref().clone();
+        drop(app_theme);
+
+        let visible_index = selected_index.saturating_sub(first_index);
+        display_items.iter().enumerate().for_each(|(index, item)| {
+            let mut param_state: QueryParamState = match item {
+                ParamItem::Path(param) => param.into(),
+                ParamItem::Query(param, enabled) => (param, *enabled).into(),
+            };
+
+            if index == visible_index {
+                param_state.row_fg_color = background.clone().into();
+                param_state.row_color = foreground.clone().into();
+            } else {
+                param_state.row_fg_color = foreground.clone().into();
+                param_state.row_color = background.clone().into();
+            }
+
+            state.window_list.push(param_state);
+        });
+    }
+
+    fn publish_selected(
+        &self,
+        event: &str,
+        state: &mut EditParamSelectorState,
+        context: &mut RefCell<Context<'_, EditParamSelectorState>>,
+    ) {
+        let selected_index = *state.cursor.to_ref() as usize;
+
+        match self.items_list.get(selected_index) {
+            // NOTE: Path params come from the url and can not be toggled or deleted
+            Some(ParamItem::Path(_)) if event != "edit_param_selector__edit" => {}
+
+            Some(_) => {
+                state.selected_item.set(selected_index.to_string());
+                context
+                    .borrow_mut()
+                    .publish(event, |state| &state.selected_item)
+            }
+
+            None => context
+                .borrow_mut()
+                .publish("edit_param_selector__cancel", |state| &state.cursor),
+        }
+    }
+
+    fn add_param(&self, context: &mut RefCell<Context<'_, EditParamSelectorState>>) {
+        context
+            .borrow_mut()
+            .publish("edit_param_selector__add", |state| &state.cursor);
+    }
+}
+
+/// Opens the add header window to add a query param, or to edit the param at
+/// the index of the selector, which lists the path params first
+pub fn open_param_window(
+    index: Option<usize>,
+    state: &mut DashboardState,
+    context: &mut Context<'_, DashboardState>,
+    component_ids: &Ref<'_, HashMap<String, ComponentId<String>>>,
+) {
+    let path_params = state.get_path_params();
+
+    let (title, target, header) = match index {
+        Some(index) if index < path_params.len() => {
+            let param = &path_params[index];
+            let header = Header {
+                name: param.name.clone(),
+                value: param.value.clone(),
+            };
+
+            (
+                "Edit Path Param",
+                HeaderWindowTarget::PathParam(index),
+                header,
+            )
+        }
+
+        Some(index) => {
+            let query_index = index - path_params.len();
+            let param = state
+                .get_query_params()
+                .into_iter()
+                .nth(query_index)
+                .map(|(param, _)| param)
+                .unwrap_or_default();
+            let header = Header {
+                name: param.name,
+                value: param.value,
+            };
+
+            (
+                "Edit Query Param",
+                HeaderWindowTarget::QueryParam(Some(query_index)),
+                header,
+            )
+        }
+
+        None => (
+            "Add Query Param",
+            HeaderWindowTarget::QueryParam(None),
+            Header::default(),
+        ),
+    };
+
+    state.header_window_target = target;
+    state.new_header_name.set(header.name.clone());
+    state.new_header_value.set(header.value.clone());
+
+    let Ok(message) =
+        serde_json::to_string(&AddHeaderWindowMessages::QueryParam((title.into(), header)))
+    else {
+        return;
+    };
+
+    state.floating_window.set(FloatingWindow::AddHeader);
+    context.set_focus("id", "add_header_window");
+
+    let _ = send_message("add_header_window", message, component_ids, context.emitter);
+}
+
+/// Applies the rows of the params table and sends the new url to the url input
+pub fn update_query_params(
+    rows: &[(QueryParam, bool)],
+    state: &mut DashboardState,
+    context: &Context<'_, DashboardState>,
+    component_ids: &Ref<'_, HashMap<String, ComponentId<String>>>,
+) {
+    let url = state.set_query_params(rows);
+
+    let _ = send_message("url_text_input", url, component_ids, context.emitter);
+}
+
+/// Sets a path param value and sends the url to the url input, in case the
+/// param was renamed
+pub fn update_path_param(
+    index: usize,
+    param: PathParam,
+    state: &mut DashboardState,
+    context: &Context<'_, DashboardState>,
+    component_ids: &Ref<'_, HashMap<String, ComponentId<String>>>,
+) {
+    let url = state.set_path_param(index, param);
+
+    let _ = send_message("url_text_input", url, component_ids, context.emitter);
+}
+
+impl DashboardMessageHandler for EditParamSelector {
+    fn handle_message(
+        value: anathema::state::CommonVal<'_>,
+        ident: impl Into<String>,
+        state: &mut DashboardState,
+        mut context: anathema::prelude::Context<'_, DashboardState>,
+        _: Elements<'_, '_>,
+        component_ids: std::cell::Ref<'_, HashMap<String, ComponentId<String>>>,
+    ) {
+        let event: String = ident.into();
+        let selected_index = value.to_string().parse::<usize>().ok();
+
+        match event.as_str() {
+            "edit_param_selector__add" => {
+                open_param_window(None, state, &mut context, &component_ids);
+            }
+
+            "edit_param_selector__edit" => {
+                let Some(index) = selected_index else {
+                    return;
+                };
+
+                open_param_window(Some(index), state, &mut context, &component_ids);
+            }
+
+            "edit_param_selector__toggle" | "edit_param_selector__delete" => {
+                let Some(index) = selected_index else {
+                    return;
+                };
+
+                let path_params = state.get_path_params();
+                let Some(index) = index.checked_sub(path_params.len()) else {
+                    return;
+                };
+
+                let mut rows = state.get_query_params();
+                if index >= rows.len() {
+                    return;
+                }
+
+                match event.as_str() {
+                    "edit_param_selector__toggle" => rows[index].1 = !rows[index].1,
+                    _ => {
+                        rows.remove(index);
+                    }
+                }
+
+                update_query_params(&rows, state, &context, &component_ids);
+
+                let Ok(message) = serde_json::to_string(&EditParamSelectorMessages::ParamsList((
+                    path_params,
+                    rows,
+                ))) else {
+                    return;
+                };
+
+                let _ = send_message(
+                    "edit_param_selector",
+                    message,
+                    &component_ids,
+                    context.emitter,
+                );
+            }
+
+            "edit_param_selector__cancel" => {
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            _ => {}
+        }
+    }
+}
+
+impl Component for EditParamSelector {
+    type State = EditParamSelectorState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: anathema::prelude::Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+        state.active = true;
+    }
+
+    fn on_mouse(
+        &mut self,
+        mouse: MouseEvent,
+        state: &mut Self::State,
+        mut elements: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        // TODO: Remove this state.active after Anathema update
+        if !state.active {
+            return;
+        }
+
+        let mut context_ref = RefCell::new(context);
+
+        elements
+            .at_position(mouse.pos())
+            .by_attribute("id", "add_button")
+            .first(|_, _| {
+                if mouse.lsb_up() {
+                    self.add_param(&mut context_ref);
+                }
+            });
+
+        elements
+            .at_position(mouse.pos())
+            .by_attribute("id", "edit_button")
+            .first(|_, _| {
+                if mouse.lsb_up() {
+                    self.publish_selected("edit_param_selector__edit", state, &mut context_ref);
+                }
+            });
+
+        elements
+            .at_position(mouse.pos())
+            .by_attribute("id", "toggle_button")
+            .first(|_, _| {
+                if mouse.lsb_up() {
+                    self.publish_selected("edit_param_selector__toggle", state, &mut context_ref);
+                }
+            });
+
+        elements
+            .at_position(mouse.pos())
+            .by_attribute("id", "delete_button")
+            .first(|_, _| {
+                if mouse.lsb_up() {
+                    self.publish_selected("edit_param_selector__delete", state, &mut context_ref);
+                }
+            });
+    }
+
+    fn on_blur(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        state.active = false;
+    }
+
+    fn on_key(
+        &mut self,
+        event: anathema::component::KeyEvent,
+        state: &mut Self::State,
+        _: anathema::widgets::Elements<'_, '_>,
+        mut context: anathema::prelude::Context<'_, Self::State>,
+    ) {
+        match event.code {
+            anathema::component::KeyCode::Char(char) => match char {
+                'j' => self.move_cursor_down(state),
+                'k' => self.move_cursor_up(state),
+                'a' => self.add_param(&mut context.into()),
+                'e' => {
+                    self.publish_selected("edit_param_selector__edit", state, &mut context.into())
+                }
+                't' => {
+                    self.publish_selected("edit_param_selector__toggle", state, &mut context.into())
+                }
+                'd' => {
+                    self.publish_selected("edit_param_selector__delete", state, &mut context.into())
+                }
+                _ => {}
+            },
+
+            anathema::component::KeyCode::Up => self.move_cursor_up(state),
+            anathema::component::KeyCode::Down => self.move_cursor_down(state),
+
+            anathema::component::KeyCode::Esc => {
+                // NOTE: This sends cursor to satisfy publish() but is not used
+                context.publish("edit_param_selector__cancel", |state| &state.cursor)
+            }
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: anathema::widgets::Elements<'_, '_>,
+        _: anathema::prelude::Context<'_, Self::State>,
+    ) {
+        let Ok(EditParamSelectorMessages::ParamsList((path_params, query_params))) =
+            serde_json::from_str::<EditParamSelectorMessages>(&message)
+        else {
+            return;
+        };
+
+        self.items_list = path_params
+            .into_iter()
+            .map(ParamItem::Path)
+            .chain(
+                query_params
+                    .into_iter()
+                    .map(|(param, enabled)| ParamItem::Query(param, enabled)),
+            )
+            .collect();
+
+        // NOTE: The cursor stays on the same row when the list is sent again
+        // after toggling or deleting a param
+        let visible_rows = *state.visible_rows.to_ref() as usize;
+        let cursor = min(
+            *state.cursor.to_ref() as usize,
+            self.items_list.len().saturating_sub(1),
+        );
+
+        let mut first_index = *state.current_first_index.to_ref() as usize;
+        if cursor < first_index || cursor >= first_index + visible_rows {
+            first_index = cursor.saturating_sub(visible_rows - 1);
+        }
+        first_index = min(
+            first_index,
+            self.items_list.len().saturating_sub(visible_rows),
+        );
+
+        let last_index = min(first_index + visible_rows, self.items_list.len()).saturating_sub(1);
+
+        state.cursor.set(cursor as u8);
+        state.current_first_index.set(first_index as u8);
+        state.current_last_index.set(last_index as u8);
+
+        self.update_list(first_index, last_index, cursor, state);
+    }
+}
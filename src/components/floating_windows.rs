@@ -2,15 +2,19 @@ use anathema::state::{CommonVal, State};
 
 pub mod add_project_variable;
 pub mod app_theme_selector;
+pub mod benchmark;
 pub mod body_mode_selector;
 pub mod button_style_selector;
 pub mod code_gen;
+pub mod collection_runner;
 pub mod commands;
 pub mod edit_endpoint_name;
 pub mod edit_project_name;
 pub mod endpoints_selector;
 pub mod file_selector;
+pub mod form_field_name;
 pub mod project_variables;
+pub mod request_preview;
 pub mod syntax_theme_selector;
 
 #[derive(PartialEq, Eq)]
@@ -20,6 +24,7 @@ pub enum FloatingWindow {
     AddHeader,
     Error,
     EditHeaderSelector,
+    EditParamSelector,
     Project,
     ConfirmAction,
     Message,
@@ -29,9 +34,16 @@ pub enum FloatingWindow {
     Commands,
     CodeGen,
     PostmanFileSelector,
+    RequestBodyFileSelector,
+    DotenvFileSelector,
     BodyModeSelector,
     AddProjectVariable,
     ViewProjectVariables,
+    RequestPreview,
+    CollectionRunner,
+    RunnerDataFileSelector,
+    Benchmark,
+    FormFieldName,
 }
 
 impl State for FloatingWindow {
@@ -42,6 +54,7 @@ impl State for FloatingWindow {
             FloatingWindow::AddHeader => Some(CommonVal::Str("AddHeader")),
             FloatingWindow::Error => Some(CommonVal::Str("Error")),
             FloatingWindow::EditHeaderSelector => Some(CommonVal::Str("EditHeaderSelector")),
+            FloatingWindow::EditParamSelector => Some(CommonVal::Str("EditParamSelector")),
             FloatingWindow::Project => Some(CommonVal::Str("Project")),
             FloatingWindow::ConfirmAction => Some(CommonVal::Str("ConfirmAction")),
             FloatingWindow::Message => Some(CommonVal::Str("Message")),
@@ -51,9 +64,20 @@ impl State for FloatingWindow {
             FloatingWindow::Commands => Some(CommonVal::Str("Commands")),
             FloatingWindow::CodeGen => Some(CommonVal::Str("CodeGen")),
             FloatingWindow::PostmanFileSelector => Some(CommonVal::Str("PostmanFileSelector")),
+            FloatingWindow::RequestBodyFileSelector => {
+                Some(CommonVal::Str("RequestBodyFileSelector"))
+            }
+            FloatingWindow::DotenvFileSelector => Some(CommonVal::Str("DotenvFileSelector")),
             FloatingWindow::BodyModeSelector => Some(CommonVal::Str("BodyModeSelector")),
             FloatingWindow::AddProjectVariable => Some(CommonVal::Str("AddProjectVariable")),
             FloatingWindow::ViewProjectVariables => Some(CommonVal::Str("ViewProjectVariables")),
+            FloatingWindow::RequestPreview => Some(CommonVal::Str("RequestPreview")),
+            FloatingWindow::CollectionRunner => Some(CommonVal::Str("CollectionRunner")),
+            FloatingWindow::RunnerDataFileSelector => {
+                Some(CommonVal::Str("RunnerDataFileSelector"))
+            }
+            FloatingWindow::Benchmark => Some(CommonVal::Str("Benchmark")),
+            FloatingWindow::FormFieldName => Some(CommonVal::Str("FormFieldName")),
         }
     }
 }
@@ -320,6 +320,7 @@ impl Variable {
             name: Some(self.name.to_ref().to_string()),
             system: Some(false),
             disabled: Some(false),
+            vault_id: None,
         };
 
         let Ok(common_val_str) = serde_json::to_string(&persisted_variable) else {
@@ -0,0 +1,382 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use anathema::{
+    component::{self, Component, ComponentId},
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{CommonVal, List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    benchmark::{run_benchmark, BenchmarkOptions, BenchmarkReport},
+    components::{
+        dashboard::{DashboardMessageHandler, DashboardState},
+        send_message,
+    },
+    projects::{PersistedEndpoint, PersistedProject},
+    requests::BenchmarkRequest,
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::FloatingWindow;
+
+const HISTOGRAM_BUCKETS: usize = 8;
+const HISTOGRAM_WIDTH: usize = 40;
+
+/// The ids of the inputs of the benchmark window
+const INPUTS: [&str; 4] = [
+    "benchmark_concurrency",
+    "benchmark_requests",
+    "benchmark_duration",
+    "benchmark_rate",
+];
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum BenchmarkMessages {
+    /// Opens the window for the endpoint with this name
+    Open(String),
+    Report {
+        summary: Vec<String>,
+        histogram: Vec<(String, usize)>,
+    },
+}
+
+/// The benchmark window inputs, published to the dashboard as JSON
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BenchmarkSettings {
+    pub concurrency: String,
+    pub requests: String,
+    pub duration: String,
+    pub rate: String,
+}
+
+#[derive(Debug, State)]
+pub struct HistogramBar {
+    label: Value<String>,
+    bar: Value<String>,
+    count: Value<usize>,
+}
+
+#[derive(Default, State)]
+pub struct BenchmarkState {
+    app_theme: Value<AppTheme>,
+    endpoint_name: Value<String>,
+    summary: Value<List<String>>,
+    histogram: Value<List<HistogramBar>>,
+    settings: Value<String>,
+
+    #[state_ignore]
+    concurrency: String,
+    #[state_ignore]
+    requests: String,
+    #[state_ignore]
+    duration: String,
+    #[state_ignore]
+    rate: String,
+}
+
+impl BenchmarkState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        BenchmarkState {
+            app_theme: app_theme.into(),
+            endpoint_name: String::new().into(),
+            summary: List::from_iter([String::from("Press R to run")]),
+            histogram: List::empty(),
+            settings: String::new().into(),
+            concurrency: String::from("10"),
+            requests: String::from("100"),
+            duration: String::new(),
+            rate: String::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Benchmark {
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+}
+
+impl Benchmark {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "benchmark_window",
+            template("floating_windows/templates/benchmark"),
+            Benchmark {
+                component_ids: ids.clone(),
+            },
+            BenchmarkState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("benchmark_window"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut BenchmarkState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn set_input_values(&self, state: &BenchmarkState, context: &mut Context<'_, BenchmarkState>) {
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let values = [
+            &state.concurrency,
+            &state.requests,
+            &state.duration,
+            &state.rate,
+        ];
+
+        for (input, value) in INPUTS.iter().zip(values) {
+            let _ = send_message(input, value.clone(), &ids, context.emitter);
+        }
+    }
+
+    fn update_report(
+        &self,
+        summary: Vec<String>,
+        histogram: Vec<(String, usize)>,
+        state: &mut BenchmarkState,
+    ) {
+        while state.summary.len() > 0 {
+            state.summary.pop_front();
+        }
+        summary
+            .into_iter()
+            .for_each(|line| state.summary.push(line));
+
+        while state.histogram.len() > 0 {
+            state.histogram.pop_front();
+        }
+
+        let most = histogram.iter().map(|(_, count)| *count).max().unwrap_or(0);
+        histogram.into_iter().for_each(|(label, count)| {
+            let width = match (most, count) {
+                (_, 0) => 0,
+                (most, count) => (count * HISTOGRAM_WIDTH / most).max(1),
+            };
+
+            state.histogram.push(HistogramBar {
+                label: label.into(),
+                bar: "█".repeat(width).into(),
+                count: count.into(),
+            });
+        });
+    }
+
+    fn run(&self, state: &mut BenchmarkState, mut context: Context<'_, BenchmarkState>) {
+        let settings = BenchmarkSettings {
+            concurrency: state.concurrency.clone(),
+            requests: state.requests.clone(),
+            duration: state.duration.clone(),
+            rate: state.rate.clone(),
+        };
+
+        let Ok(settings) = serde_json::to_string(&settings) else {
+            return;
+        };
+
+        self.update_report(
+            vec![String::from("Running, press Esc to cancel")],
+            vec![],
+            state,
+        );
+
+        state.settings.set(settings);
+        context.publish("benchmark__run", |state| &state.settings);
+    }
+}
+
+impl Component for Benchmark {
+    type State = BenchmarkState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+    }
+
+    fn on_key(
+        &mut self,
+        event: component::KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match event.code {
+            component::KeyCode::Char(char) => match char {
+                'c' => context.set_focus("id", "benchmark_concurrency"),
+                'n' => context.set_focus("id", "benchmark_requests"),
+                'd' => context.set_focus("id", "benchmark_duration"),
+                't' => context.set_focus("id", "benchmark_rate"),
+                'r' => self.run(state, context),
+                _ => {}
+            },
+
+            component::KeyCode::Esc => {
+                // NOTE: This sends settings to satisfy publish() but is not used
+                context.publish("benchmark__cancel", |state| &state.settings)
+            }
+
+            _ => {}
+        }
+    }
+
+    fn receive(
+        &mut self,
+        ident: &str,
+        value: CommonVal<'_>,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match ident {
+            "benchmark_concurrency_update" => state.concurrency = value.to_string(),
+            "benchmark_requests_update" => state.requests = value.to_string(),
+            "benchmark_duration_update" => state.duration = value.to_string(),
+            "benchmark_rate_update" => state.rate = value.to_string(),
+
+            "benchmark_input_escape" => context.set_focus("id", "benchmark_window"),
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        let Ok(benchmark_message) = serde_json::from_str::<BenchmarkMessages>(&message) else {
+            return;
+        };
+
+        match benchmark_message {
+            BenchmarkMessages::Open(endpoint_name) => {
+                state.endpoint_name.set(endpoint_name);
+                self.set_input_values(state, &mut context);
+            }
+
+            BenchmarkMessages::Report { summary, histogram } => {
+                self.update_report(summary, histogram, state);
+            }
+        }
+    }
+}
+
+impl DashboardMessageHandler for Benchmark {
+    fn handle_message(
+        value: CommonVal<'_>,
+        ident: impl Into<String>,
+        state: &mut DashboardState,
+        mut context: Context<'_, DashboardState>,
+        _: Elements<'_, '_>,
+        component_ids: Ref<'_, HashMap<String, ComponentId<String>>>,
+    ) {
+        let event: String = ident.into();
+
+        match event.as_str() {
+            "benchmark__cancel" => {
+                if let Some(cancel) = state.benchmark_cancel.take() {
+                    cancel.store(true, Ordering::SeqCst);
+                }
+
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            "benchmark__run" => {
+                let Ok(settings) = serde_json::from_str::<BenchmarkSettings>(&value.to_string())
+                else {
+                    return;
+                };
+
+                let Some(window_id) = component_ids.get("benchmark_window").copied() else {
+                    return;
+                };
+
+                let project: PersistedProject = (&*state.project.to_ref()).into();
+                let endpoint: PersistedEndpoint = (&*state.endpoint.to_ref()).into();
+
+                let cancel = Arc::new(AtomicBool::new(false));
+                if let Some(running) = state.benchmark_cancel.replace(cancel.clone()) {
+                    running.store(true, Ordering::SeqCst);
+                }
+
+                // NOTE: The benchmark runs on its own thread so the app keeps
+                // drawing, the report is sent to the window when it is done
+                let emitter = context.emitter.clone();
+                thread::spawn(move || {
+                    let benchmark = benchmark_endpoint(&project, &endpoint, &settings, &cancel);
+
+                    // NOTE: Errors are shown in the report so the window stays open
+                    let benchmark_message = match benchmark {
+                        Ok(report) => BenchmarkMessages::Report {
+                            summary: report.summary(),
+                            histogram: report.histogram(HISTOGRAM_BUCKETS),
+                        },
+                        Err(error) => BenchmarkMessages::Report {
+                            summary: vec![error.to_string()],
+                            histogram: vec![],
+                        },
+                    };
+
+                    if let Ok(message) = serde_json::to_string(&benchmark_message) {
+                        let _ = emitter.emit(window_id, message);
+                    }
+                });
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn benchmark_endpoint(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    settings: &BenchmarkSettings,
+    cancel: &AtomicBool,
+) -> anyhow::Result<BenchmarkReport> {
+    let options = BenchmarkOptions::parse(
+        &settings.concurrency,
+        &settings.requests,
+        &settings.duration,
+        &settings.rate,
+    )?;
+
+    let request = BenchmarkRequest::new(project, endpoint, options.concurrency)?;
+
+    Ok(run_benchmark(&options, cancel, || request.send()))
+}
@@ -56,7 +56,10 @@ impl DashboardMessageHandler for BodyModeSelector {
 
         match event.as_str() {
             "body_mode_selector__cancel" => {
-                state.floating_window.set(FloatingWindow::None);
+                // NOTE: Choosing Binary opens the file selector, leave it open
+                if *state.floating_window.to_ref() == FloatingWindow::BodyModeSelector {
+                    state.floating_window.set(FloatingWindow::None);
+                }
             }
 
             "body_mode_selector__selection" => {
@@ -68,6 +71,14 @@ impl DashboardMessageHandler for BodyModeSelector {
                         state.endpoint.to_mut().raw_type.set(value.to_string());
                     }
 
+                    "Binary" => {
+                        state.endpoint.to_mut().raw_type.set("".to_string());
+                        state.endpoint.to_mut().body_mode.set(value.to_string());
+                        state
+                            .floating_window
+                            .set(FloatingWindow::RequestBodyFileSelector);
+                    }
+
                     _ => {
                         state.endpoint.to_mut().raw_type.set("".to_string());
                         state.endpoint.to_mut().body_mode.set(value.to_string());
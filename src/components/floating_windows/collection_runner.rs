@@ -0,0 +1,410 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use anathema::{
+    component::{self, Component, ComponentId},
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    components::dashboard::{DashboardMessageHandler, DashboardState},
+    projects::PersistedProject,
+    runner::{get_summary, load_data_file, run_collection},
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::FloatingWindow;
+
+const VISIBLE_ENDPOINTS: usize = 8;
+const VISIBLE_RESULTS: usize = 10;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum CollectionRunnerMessages {
+    SetEndpoints(Vec<String>),
+    SetDataFile(String),
+    SetResults(Vec<String>),
+}
+
+/// The endpoints and data file of a run, published to the dashboard as JSON
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RunnerSelection {
+    pub endpoints: Vec<String>,
+    pub data_file: Option<String>,
+}
+
+#[derive(Debug, State)]
+pub struct RunnerEndpoint {
+    name: Value<String>,
+    checkbox: Value<String>,
+    row_color: Value<String>,
+    row_fg_color: Value<String>,
+}
+
+#[derive(Default, State)]
+pub struct CollectionRunnerState {
+    app_theme: Value<AppTheme>,
+    window_list: Value<List<RunnerEndpoint>>,
+    data_file: Value<String>,
+    results: Value<List<String>>,
+    result_count: Value<usize>,
+    selection: Value<String>,
+}
+
+impl CollectionRunnerState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        CollectionRunnerState {
+            app_theme: app_theme.into(),
+            window_list: List::empty(),
+            data_file: String::from("None").into(),
+            results: List::empty(),
+            result_count: 0.into(),
+            selection: String::new().into(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CollectionRunner {
+    /// The endpoint names and whether they run
+    endpoints: Vec<(String, bool)>,
+    data_file: Option<String>,
+    results: Vec<String>,
+    cursor: usize,
+    first_endpoint: usize,
+    first_result: usize,
+}
+
+impl CollectionRunner {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "collection_runner",
+            template("floating_windows/templates/collection_runner"),
+            CollectionRunner::default(),
+            CollectionRunnerState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("collection_runner"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut CollectionRunnerState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn move_cursor(&mut self, state: &mut CollectionRunnerState, down: bool) {
+        let last_index = self.endpoints.len().saturating_sub(1);
+        self.cursor = match down {
+            true => (self.cursor + 1).min(last_index),
+            false => self.cursor.saturating_sub(1),
+        };
+
+        if self.cursor < self.first_endpoint {
+            self.first_endpoint = self.cursor;
+        } else if self.cursor >= self.first_endpoint + VISIBLE_ENDPOINTS {
+            self.first_endpoint = self.cursor + 1 - VISIBLE_ENDPOINTS;
+        }
+
+        self.update_endpoints(state);
+    }
+
+    fn toggle_endpoint(&mut self, state: &mut CollectionRunnerState) {
+        if let Some((_, selected)) = self.endpoints.get_mut(self.cursor) {
+            *selected = !*selected;
+        }
+
+        self.update_endpoints(state);
+    }
+
+    /// Selects every endpoint, or none when they are all selected
+    fn toggle_all_endpoints(&mut self, state: &mut CollectionRunnerState) {
+        let select = !self.endpoints.iter().all(|(_, selected)| *selected);
+        self.endpoints
+            .iter_mut()
+            .for_each(|(_, selected)| *selected = select);
+
+        self.update_endpoints(state);
+    }
+
+    fn scroll_results(&mut self, state: &mut CollectionRunnerState, down: bool) {
+        let last_first_result = self.results.len().saturating_sub(VISIBLE_RESULTS);
+        self.first_result = match down {
+            true => (self.first_result + 1).min(last_first_result),
+            false => self.first_result.saturating_sub(1),
+        };
+
+        self.update_results(state);
+    }
+
+    fn update_endpoints(&self, state: &mut CollectionRunnerState) {
+        let app_theme = state.app_theme.to_ref();
+        let foreground = app_theme.overlay_foreground.to_ref().clone();
+        let background = app_theme.overlay_background.to_ref().clone();
+        drop(app_theme);
+
+        while state.window_list.len() > 0 {
+            state.window_list.pop_front();
+        }
+
+        self.endpoints
+            .iter()
+            .enumerate()
+            .skip(self.first_endpoint)
+            .take(VISIBLE_ENDPOINTS)
+            .for_each(|(index, (name, selected))| {
+                let (row_fg_color, row_color) = match index == self.cursor {
+                    true => (background.clone(), foreground.clone()),
+                    false => (foreground.clone(), background.clone()),
+                };
+
+                let checkbox = match selected {
+                    true => "[x]",
+                    false => "[ ]",
+                };
+
+                state.window_list.push(RunnerEndpoint {
+                    name: name.clone().into(),
+                    checkbox: checkbox.to_string().into(),
+                    row_color: row_color.into(),
+                    row_fg_color: row_fg_color.into(),
+                });
+            });
+    }
+
+    fn update_results(&self, state: &mut CollectionRunnerState) {
+        while state.results.len() > 0 {
+            state.results.pop_front();
+        }
+
+        self.results
+            .iter()
+            .skip(self.first_result)
+            .take(VISIBLE_RESULTS)
+            .for_each(|line| state.results.push(line.clone()));
+
+        state.result_count.set(self.results.len());
+    }
+
+    fn run(
+        &mut self,
+        state: &mut CollectionRunnerState,
+        mut context: Context<'_, CollectionRunnerState>,
+    ) {
+        let selection = RunnerSelection {
+            endpoints: self
+                .endpoints
+                .iter()
+                .filter(|(_, selected)| *selected)
+                .map(|(name, _)| name.clone())
+                .collect(),
+            data_file: self.data_file.clone(),
+        };
+
+        let Ok(selection) = serde_json::to_string(&selection) else {
+            return;
+        };
+
+        self.results = vec![String::from("Running, press Esc to cancel")];
+        self.first_result = 0;
+        self.update_results(state);
+
+        state.selection.set(selection);
+        context.publish("collection_runner__run", |state| &state.selection);
+    }
+}
+
+impl Component for CollectionRunner {
+    type State = CollectionRunnerState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+        self.update_endpoints(state);
+        self.update_results(state);
+    }
+
+    fn on_key(
+        &mut self,
+        event: component::KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match event.code {
+            component::KeyCode::Char(char) => match char {
+                'j' => self.move_cursor(state, true),
+                'k' => self.move_cursor(state, false),
+                'J' => self.scroll_results(state, true),
+                'K' => self.scroll_results(state, false),
+                ' ' => self.toggle_endpoint(state),
+                'a' => self.toggle_all_endpoints(state),
+                'r' => self.run(state, context),
+
+                'd' => {
+                    // NOTE: This sends selection to satisfy publish() but is not used
+                    context.publish("collection_runner__data_file", |state| &state.selection)
+                }
+
+                'c' => {
+                    self.data_file = None;
+                    state.data_file.set(String::from("None"));
+                }
+
+                _ => {}
+            },
+
+            component::KeyCode::Down => self.move_cursor(state, true),
+            component::KeyCode::Up => self.move_cursor(state, false),
+
+            component::KeyCode::Esc => {
+                // NOTE: This sends selection to satisfy publish() but is not used
+                context.publish("collection_runner__cancel", |state| &state.selection)
+            }
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        let Ok(collection_runner_message) =
+            serde_json::from_str::<CollectionRunnerMessages>(&message)
+        else {
+            return;
+        };
+
+        match collection_runner_message {
+            CollectionRunnerMessages::SetEndpoints(names) => {
+                self.endpoints = names.into_iter().map(|name| (name, true)).collect();
+                self.cursor = 0;
+                self.first_endpoint = 0;
+                self.results = vec![];
+                self.first_result = 0;
+
+                self.update_endpoints(state);
+                self.update_results(state);
+            }
+
+            CollectionRunnerMessages::SetDataFile(path) => {
+                state.data_file.set(path.clone());
+                self.data_file = Some(path);
+            }
+
+            CollectionRunnerMessages::SetResults(lines) => {
+                self.results = lines;
+                self.first_result = 0;
+
+                self.update_results(state);
+            }
+        }
+    }
+}
+
+impl DashboardMessageHandler for CollectionRunner {
+    fn handle_message(
+        value: component::CommonVal<'_>,
+        ident: impl Into<String>,
+        state: &mut DashboardState,
+        mut context: Context<'_, DashboardState>,
+        _: Elements<'_, '_>,
+        component_ids: Ref<'_, HashMap<String, ComponentId<String>>>,
+    ) {
+        let event: String = ident.into();
+
+        match event.as_str() {
+            "collection_runner__cancel" => {
+                if let Some(cancel) = state.runner_cancel.take() {
+                    cancel.store(true, Ordering::SeqCst);
+                }
+
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            "collection_runner__data_file" => {
+                state
+                    .floating_window
+                    .set(FloatingWindow::RunnerDataFileSelector);
+                context.set_focus("id", "runner_data_file_selector");
+            }
+
+            "collection_runner__run" => {
+                let Ok(selection) = serde_json::from_str::<RunnerSelection>(&value.to_string())
+                else {
+                    return;
+                };
+
+                let Some(window_id) = component_ids.get("collection_runner").copied() else {
+                    return;
+                };
+
+                let project: PersistedProject = (&*state.project.to_ref()).into();
+
+                let cancel = Arc::new(AtomicBool::new(false));
+                if let Some(running) = state.runner_cancel.replace(cancel.clone()) {
+                    running.store(true, Ordering::SeqCst);
+                }
+
+                // NOTE: The run has its own thread so the app keeps drawing,
+                // the summary is sent to the window when it is done
+                let emitter = context.emitter.clone();
+                thread::spawn(move || {
+                    let rows = match &selection.data_file {
+                        Some(path) => load_data_file(path),
+                        None => Ok(vec![]),
+                    };
+
+                    // NOTE: Errors are shown in the results so the runner stays open
+                    let lines = match rows.and_then(|rows| {
+                        run_collection(&project, &selection.endpoints, &rows, false, &cancel)
+                    }) {
+                        Ok(results) => get_summary(&results),
+                        Err(error) => vec![error.to_string()],
+                    };
+
+                    let runner_message = CollectionRunnerMessages::SetResults(lines);
+                    if let Ok(message) = serde_json::to_string(&runner_message) {
+                        let _ = emitter.emit(window_id, message);
+                    }
+                });
+            }
+
+            _ => {}
+        }
+    }
+}
@@ -19,13 +19,15 @@ use crate::{
         dashboard::{DashboardMessageHandler, DashboardMessages, DashboardState},
         send_message,
     },
-    projects::{PersistedProject, PersistedVariable},
+    projects::{PersistedEndpoint, PersistedProject, PersistedVariable},
+    requests::introspect_graphql_schema,
     templates::template,
     theme::{get_app_theme, AppTheme},
 };
 
 use super::{
-    add_project_variable::AddProjectVariableMessages, project_variables::ProjectVariablesMessages,
+    add_project_variable::AddProjectVariableMessages, benchmark::BenchmarkMessages,
+    collection_runner::CollectionRunnerMessages, project_variables::ProjectVariablesMessages,
     FloatingWindow,
 };
 
@@ -167,17 +169,24 @@ impl DashboardMessageHandler for Commands {
                         })
                         .collect();
 
-                    let project_variables_messages = ProjectVariablesMessages::SetList(variables);
-                    let Ok(message) = serde_json::to_string(&project_variables_messages) else {
-                        return;
-                    };
-
-                    let _ = send_message(
-                        "project_variables",
-                        message,
-                        &component_ids,
-                        context.emitter,
-                    );
+                    let dotenv_files = state.project.to_ref().dotenv_files.clone();
+
+                    let messages = [
+                        ProjectVariablesMessages::SetList(variables),
+                        ProjectVariablesMessages::SetDotenvFiles(dotenv_files),
+                    ];
+                    for project_variables_message in messages {
+                        let Ok(message) = serde_json::to_string(&project_variables_message) else {
+                            return;
+                        };
+
+                        let _ = send_message(
+                            "project_variables",
+                            message,
+                            &component_ids,
+                            context.emitter,
+                        );
+                    }
                 }
 
                 "g" => {
@@ -192,6 +201,89 @@ impl DashboardMessageHandler for Commands {
                     context.set_focus("id", "postman_file_selector");
                 }
 
+                "s" => {
+                    state.floating_window.set(FloatingWindow::None);
+                    context.set_focus("id", "app");
+
+                    let project: PersistedProject = (&*state.project.to_ref()).into();
+                    let endpoint: PersistedEndpoint = (&*state.endpoint.to_ref()).into();
+
+                    let dashboard_message = match introspect_graphql_schema(&project, &endpoint) {
+                        Ok(schema) => {
+                            let title = "GraphQL Schema".to_string();
+                            let message = format!(
+                                "Cached {} types from {}",
+                                schema.types.len(),
+                                endpoint.url
+                            );
+
+                            DashboardMessages::ShowSucces((title, message))
+                        }
+                        Err(error) => DashboardMessages::ShowError(error.to_string()),
+                    };
+
+                    let messages = [dashboard_message, DashboardMessages::GraphQLSchemaUpdate];
+                    for message in messages {
+                        if let Ok(msg) = serde_json::to_string(&message) {
+                            let _ = send_message("dashboard", msg, &component_ids, context.emitter);
+                        }
+                    }
+                }
+
+                "f" => {
+                    state
+                        .floating_window
+                        .set(FloatingWindow::RequestBodyFileSelector);
+                    context.set_focus("id", "request_body_file_selector");
+                }
+
+                "d" => {
+                    state
+                        .floating_window
+                        .set(FloatingWindow::DotenvFileSelector);
+                    context.set_focus("id", "dotenv_file_selector");
+                }
+
+                "r" => {
+                    state.floating_window.set(FloatingWindow::CollectionRunner);
+                    context.set_focus("id", "collection_runner");
+
+                    let endpoints: Vec<String> = state
+                        .project
+                        .to_ref()
+                        .endpoints
+                        .to_ref()
+                        .iter()
+                        .map(|endpoint| endpoint.to_ref().name.to_ref().to_string())
+                        .collect();
+
+                    let runner_message = CollectionRunnerMessages::SetEndpoints(endpoints);
+                    if let Ok(message) = serde_json::to_string(&runner_message) {
+                        let _ = send_message(
+                            "collection_runner",
+                            message,
+                            &component_ids,
+                            context.emitter,
+                        );
+                    }
+                }
+
+                "b" => {
+                    state.floating_window.set(FloatingWindow::Benchmark);
+                    context.set_focus("id", "benchmark_window");
+
+                    let endpoint_name = state.endpoint.to_ref().name.to_ref().to_string();
+                    let benchmark_message = BenchmarkMessages::Open(endpoint_name);
+                    if let Ok(message) = serde_json::to_string(&benchmark_message) {
+                        let _ = send_message(
+                            "benchmark_window",
+                            message,
+                            &component_ids,
+                            context.emitter,
+                        );
+                    }
+                }
+
                 "e" => {
                     state.floating_window.set(FloatingWindow::CodeGen);
                     context.set_focus("id", "codegen_window");
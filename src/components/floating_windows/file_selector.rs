@@ -1,5 +1,5 @@
 use std::{
-    cell::RefCell,
+    cell::{Ref, RefCell},
     cmp::{max, min},
     collections::HashMap,
     env,
@@ -10,7 +10,7 @@ use std::{
 };
 
 use anathema::{
-    component::{Component, ComponentId},
+    component::{Component, ComponentId, Emitter},
     prelude::{Context, TuiBackend},
     runtime::RuntimeBuilder,
     state::{List, State, Value},
@@ -24,13 +24,19 @@ use crate::{
     components::{
         dashboard::{DashboardMessageHandler, DashboardMessages, DashboardState},
         send_message,
+        textarea::TextAreaMessages,
     },
+    environment::load_dotenv_files,
+    messages::confirm_actions::{ConfirmAction, ConfirmDetails},
     projects::save_project,
     templates::template,
     theme::{get_app_theme, AppTheme},
 };
 
-use super::FloatingWindow;
+use super::{
+    collection_runner::CollectionRunnerMessages, form_field_name::FormFieldNameMessages,
+    FloatingWindow,
+};
 
 #[derive(Debug, Default, State)]
 pub struct FileSelectorState {
@@ -100,23 +106,35 @@ impl FileSelectorState {
     }
 }
 
+/// What the file selector does with the file the user chooses
+#[derive(Default, Clone, Copy)]
+pub enum FileSelectorAction {
+    #[default]
+    PostmanImport,
+    RequestBody,
+    DotenvFile,
+    RunnerDataFile,
+}
+
 #[derive(Default)]
 pub struct FileSelector {
     #[allow(dead_code)]
     component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
     items_list: Vec<Entry>,
+    action: FileSelectorAction,
 }
 
 impl FileSelector {
     pub fn register(
         ident: &str,
+        action: FileSelectorAction,
         ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
         builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
     ) -> anyhow::Result<()> {
         let id = builder.register_component(
             ident,
             template("floating_windows/templates/file_selector"),
-            FileSelector::new(ids.clone()),
+            FileSelector::new(ids.clone(), action),
             FileSelectorState::new(),
         )?;
 
@@ -188,10 +206,14 @@ impl FileSelector {
         }
     }
 
-    pub fn new(component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>) -> Self {
+    pub fn new(
+        component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        action: FileSelectorAction,
+    ) -> Self {
         FileSelector {
             component_ids,
             items_list: vec![],
+            action,
         }
     }
 
@@ -319,7 +341,40 @@ impl FileSelector {
         state.window_list = new_list_state;
     }
 
-    fn handle_file(&self, entry: &Entry, context: Context<'_, FileSelectorState>) {
+    fn handle_file(
+        &self,
+        entry: &Entry,
+        state: &mut FileSelectorState,
+        mut context: Context<'_, FileSelectorState>,
+    ) {
+        if let FileSelectorAction::RequestBody = self.action {
+            let path = entry.path_buf.to_string_lossy().to_string();
+            state.selected_item.set(path);
+
+            context.publish("file_selector__request_body_file", |state| {
+                &state.selected_item
+            });
+            return;
+        }
+
+        if let FileSelectorAction::DotenvFile = self.action {
+            let path = entry.path_buf.to_string_lossy().to_string();
+            state.selected_item.set(path);
+
+            context.publish("file_selector__dotenv_file", |state| &state.selected_item);
+            return;
+        }
+
+        if let FileSelectorAction::RunnerDataFile = self.action {
+            let path = entry.path_buf.to_string_lossy().to_string();
+            state.selected_item.set(path);
+
+            context.publish("file_selector__runner_data_file", |state| {
+                &state.selected_item
+            });
+            return;
+        }
+
         let error_message = "Invalid Postman file type to import, choose a .json file".to_string();
 
         match entry.path_buf.extension() {
@@ -420,22 +475,134 @@ impl FileSelector {
 
 impl DashboardMessageHandler for FileSelector {
     fn handle_message(
-        _: anathema::state::CommonVal<'_>,
+        value: anathema::state::CommonVal<'_>,
         ident: impl Into<String>,
         state: &mut DashboardState,
         mut context: anathema::prelude::Context<'_, DashboardState>,
         _: Elements<'_, '_>,
-        _: std::cell::Ref<'_, HashMap<String, ComponentId<String>>>,
+        component_ids: std::cell::Ref<'_, HashMap<String, ComponentId<String>>>,
     ) {
         let event: String = ident.into();
 
-        #[allow(clippy::single_match)]
         match event.as_str() {
             "file_selector__cancel" => {
                 state.floating_window.set(FloatingWindow::None);
                 context.set_focus("id", "app");
             }
 
+            "file_selector__request_body_file" => {
+                let path = value.to_string();
+                let body_mode = state.endpoint.to_ref().body_mode.to_ref().to_lowercase();
+                let is_binary = matches!(body_mode.as_str(), "binary" | "file");
+                let replaces_body =
+                    !is_binary && !state.endpoint.to_ref().body.to_ref().trim().is_empty();
+
+                // NOTE: Form data bodies get a new file field named by the
+                // user, any other body mode is switched to a binary body that
+                // sends the file once replacing the body is confirmed
+                match body_mode.as_str() {
+                    "formdata" => {
+                        state.floating_window.set(FloatingWindow::FormFieldName);
+                        context.set_focus("id", "form_field_name");
+
+                        let message = FormFieldNameMessages::Open(path);
+                        if let Ok(message) = serde_json::to_string(&message) {
+                            let _ = send_message(
+                                "form_field_name",
+                                message,
+                                &component_ids,
+                                context.emitter,
+                            );
+                        }
+                    }
+
+                    _ if replaces_body => {
+                        state.floating_window.set(FloatingWindow::ConfirmAction);
+                        context.set_focus("id", "confirm_action_window");
+
+                        let confirm_message =
+                            ConfirmAction::ConfirmReplaceRequestBody(ConfirmDetails {
+                                title: "Replace Request Body".to_string(),
+                                message: format!("Replace the request body with {path}?"),
+                                data: path,
+                            });
+
+                        if let Ok(message) = serde_json::to_string(&confirm_message) {
+                            let _ = send_message(
+                                "confirm_action_window",
+                                message,
+                                &component_ids,
+                                context.emitter,
+                            );
+                        }
+                    }
+
+                    _ => {
+                        set_binary_body_file(state, &path, &component_ids, context.emitter);
+
+                        state.floating_window.set(FloatingWindow::None);
+                        context.set_focus("id", "app");
+                    }
+                }
+            }
+
+            "file_selector__dotenv_file" => {
+                let path = value.to_string();
+
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+
+                // NOTE: Choosing a file the project already uses removes it
+                let mut project = state.project.to_mut();
+                let dashboard_message =
+                    match project.dotenv_files.iter().position(|file| *file == path) {
+                        Some(index) => {
+                            project.dotenv_files.remove(index);
+
+                            let title = ".env Files".to_string();
+                            let message = format!("Removed {path} from the project");
+                            DashboardMessages::ShowSucces((title, message))
+                        }
+
+                        None => match load_dotenv_files(&[path.clone()]) {
+                            Ok(variables) => {
+                                project.dotenv_files.push(path.clone());
+
+                                let title = ".env Files".to_string();
+                                let message = format!(
+                                    "Added {} variables from {path} to the project",
+                                    variables.len()
+                                );
+                                DashboardMessages::ShowSucces((title, message))
+                            }
+                            Err(error) => DashboardMessages::ShowError(error.to_string()),
+                        },
+                    };
+                drop(project);
+
+                if let Ok(message) = serde_json::to_string(&dashboard_message) {
+                    let _ = send_message("dashboard", message, &component_ids, context.emitter);
+                }
+            }
+
+            "file_selector__runner_data_file" => {
+                let path = value.to_string();
+
+                // NOTE: Goes back to the runner that asked for the data file
+                state.floating_window.set(FloatingWindow::CollectionRunner);
+                context.set_focus("id", "collection_runner");
+
+                let runner_message = CollectionRunnerMessages::SetDataFile(path);
+                if let Ok(message) = serde_json::to_string(&runner_message) {
+                    let _ = send_message(
+                        "collection_runner",
+                        message,
+                        &component_ids,
+                        context.emitter,
+                    );
+                }
+            }
+
             _ => {}
         }
     }
@@ -494,7 +661,7 @@ impl Component for FileSelector {
                 match entry {
                     Some(entry) => {
                         if entry.path_buf.is_file() {
-                            self.handle_file(entry, context);
+                            self.handle_file(entry, state, context);
                         } else if entry.path_buf.is_dir() {
                             self.handle_directory(entry, state, context);
                         }
@@ -507,3 +674,30 @@ impl Component for FileSelector {
         }
     }
 }
+
+/// Sets the request body of the endpoint and shows it in the request body input
+pub fn set_request_body(
+    state: &mut DashboardState,
+    body: String,
+    component_ids: &Ref<'_, HashMap<String, ComponentId<String>>>,
+    emitter: &Emitter,
+) {
+    state.endpoint.to_mut().body.set(body.clone());
+
+    if let Ok(message) = serde_json::to_string(&TextAreaMessages::SetInput(body)) {
+        let _ = send_message("request_body_input", message, component_ids, emitter);
+    }
+}
+
+/// Switches the endpoint to a binary body that sends the file
+pub fn set_binary_body_file(
+    state: &mut DashboardState,
+    path: &str,
+    component_ids: &Ref<'_, HashMap<String, ComponentId<String>>>,
+    emitter: &Emitter,
+) {
+    state.endpoint.to_mut().body_mode.set("Binary".to_string());
+    state.endpoint.to_mut().raw_type.set("".to_string());
+
+    set_request_body(state, format!("@{path}"), component_ids, emitter);
+}
@@ -0,0 +1,232 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use anathema::{
+    component::{self, Component, ComponentId},
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{CommonVal, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    components::{
+        dashboard::{DashboardMessageHandler, DashboardState},
+        send_message,
+    },
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::{file_selector::set_request_body, FloatingWindow};
+
+/// The field name used until the user changes it
+const DEFAULT_FIELD_NAME: &str = "file";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum FormFieldNameMessages {
+    /// Opens the window for the file at this path
+    Open(String),
+}
+
+/// A file field of a form data body, published to the dashboard as JSON
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FormFileField {
+    pub name: String,
+    pub path: String,
+}
+
+#[derive(Default, State)]
+pub struct FormFieldNameState {
+    app_theme: Value<AppTheme>,
+    path: Value<String>,
+    field: Value<String>,
+
+    #[state_ignore]
+    name: String,
+}
+
+impl FormFieldNameState {
+    pub fn new() -> Self {
+        FormFieldNameState {
+            app_theme: get_app_theme().into(),
+            path: String::new().into(),
+            field: String::new().into(),
+            name: String::from(DEFAULT_FIELD_NAME),
+        }
+    }
+}
+
+/// Asks for the name of the form data field that sends a file
+#[derive(Default)]
+pub struct FormFieldName {
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+}
+
+impl FormFieldName {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "form_field_name",
+            template("floating_windows/templates/form_field_name"),
+            FormFieldName {
+                component_ids: ids.clone(),
+            },
+            FormFieldNameState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("form_field_name"), id);
+
+        Ok(())
+    }
+
+    fn submit(&self, state: &mut FormFieldNameState, mut context: Context<'_, FormFieldNameState>) {
+        let name = state.name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let field = FormFileField {
+            name: name.to_string(),
+            path: state.path.to_ref().to_string(),
+        };
+
+        let Ok(field) = serde_json::to_string(&field) else {
+            return;
+        };
+
+        state.field.set(field);
+        context.publish("form_field_name__submit", |state| &state.field);
+    }
+}
+
+impl Component for FormFieldName {
+    type State = FormFieldNameState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        state.app_theme.set(get_app_theme());
+    }
+
+    fn on_key(
+        &mut self,
+        event: component::KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match event.code {
+            component::KeyCode::Char(char) => match char {
+                'f' => context.set_focus("id", "form_field_name_input"),
+                'a' => self.submit(state, context),
+                _ => {}
+            },
+
+            component::KeyCode::Esc => {
+                context.publish("form_field_name__cancel", |state| &state.field)
+            }
+
+            _ => {}
+        }
+    }
+
+    fn receive(
+        &mut self,
+        ident: &str,
+        value: CommonVal<'_>,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match ident {
+            "form_field_name_update" => state.name = value.to_string(),
+            "form_field_name_escape" => context.set_focus("id", "form_field_name"),
+            "form_field_name_enter" => self.submit(state, context),
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        let Ok(FormFieldNameMessages::Open(path)) =
+            serde_json::from_str::<FormFieldNameMessages>(&message)
+        else {
+            return;
+        };
+
+        state.path.set(path);
+        state.name = String::from(DEFAULT_FIELD_NAME);
+
+        if let Ok(ids) = self.component_ids.try_borrow() {
+            let _ = send_message(
+                "form_field_name_input",
+                state.name.clone(),
+                &ids,
+                context.emitter,
+            );
+        }
+
+        context.set_focus("id", "form_field_name_input");
+    }
+}
+
+impl DashboardMessageHandler for FormFieldName {
+    fn handle_message(
+        value: CommonVal<'_>,
+        ident: impl Into<String>,
+        state: &mut DashboardState,
+        mut context: Context<'_, DashboardState>,
+        _: Elements<'_, '_>,
+        component_ids: Ref<'_, HashMap<String, ComponentId<String>>>,
+    ) {
+        let event: String = ident.into();
+
+        match event.as_str() {
+            "form_field_name__cancel" => {
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            "form_field_name__submit" => {
+                let Ok(field) = serde_json::from_str::<FormFileField>(&value.to_string()) else {
+                    return;
+                };
+
+                let mut body = state.endpoint.to_ref().body.to_ref().to_string();
+                if !body.is_empty() && !body.ends_with('\n') {
+                    body.push('\n');
+                }
+                body.push_str(&format!("{}=@{}", field.name, field.path));
+
+                set_request_body(state, body, &component_ids, context.emitter);
+
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            _ => {}
+        }
+    }
+}
@@ -35,6 +35,11 @@ pub struct ProjectVariablesState {
     project_count: Value<u8>,
     selected_variable: Value<String>,
     app_theme: Value<AppTheme>,
+
+    /// Private values are masked until they are revealed
+    show_private: Value<bool>,
+
+    dotenv_files: Value<String>,
 }
 
 impl ProjectVariablesState {
@@ -50,6 +55,8 @@ impl ProjectVariablesState {
             window_list: List::empty(),
             selected_variable: "".to_string().into(),
             app_theme: app_theme.into(),
+            show_private: false.into(),
+            dotenv_files: String::new().into(),
         }
     }
 }
@@ -57,6 +64,7 @@ impl ProjectVariablesState {
 #[derive(Debug, Deserialize, Serialize)]
 pub enum ProjectVariablesMessages {
     SetList(Vec<PersistedVariable>),
+    SetDotenvFiles(Vec<String>),
 }
 
 #[derive(Default)]
@@ -396,6 +404,10 @@ impl Component for ProjectVariables {
                 'a' => self.open_add_variable_window(&mut context),
                 'e' => self.open_edit_variable_window(state, context),
                 'd' => self.open_delete_variable_window(state, context),
+                'r' => {
+                    let show_private = !*state.show_private.to_ref();
+                    state.show_private.set(show_private);
+                }
 
                 _ => {}
             },
@@ -457,6 +469,7 @@ impl Component for ProjectVariables {
         match project_variables_messages {
             ProjectVariablesMessages::SetList(vec) => {
                 self.variables_list = vec;
+                state.show_private.set(false);
 
                 let first_index = 0;
                 let last_index = 4;
@@ -464,6 +477,13 @@ impl Component for ProjectVariables {
 
                 self.update_project_list(first_index, last_index, selected_index, state);
             }
+
+            ProjectVariablesMessages::SetDotenvFiles(files) => {
+                state.dotenv_files.set(match files.is_empty() {
+                    true => String::from("None"),
+                    false => files.join(", "),
+                });
+            }
         }
     }
 }
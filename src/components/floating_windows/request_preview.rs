@@ -0,0 +1,250 @@
+use std::{
+    cell::{Ref, RefCell},
+    collections::HashMap,
+    rc::Rc,
+};
+
+use anathema::{
+    component::{self, Component, ComponentId},
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    components::dashboard::{DashboardMessageHandler, DashboardState},
+    requests::preview::PreviewLine,
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::FloatingWindow;
+
+const VISIBLE_LINES: usize = 20;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum RequestPreviewMessages {
+    Preview(Vec<PreviewLine>),
+}
+
+#[derive(Debug, State)]
+pub struct PreviewLineState {
+    spans: Value<List<PreviewSpan>>,
+}
+
+#[derive(Debug, State)]
+pub struct PreviewSpan {
+    text: Value<String>,
+    foreground: Value<String>,
+    background: Value<String>,
+}
+
+#[derive(Default, State)]
+pub struct RequestPreviewState {
+    app_theme: Value<AppTheme>,
+    lines: Value<List<PreviewLineState>>,
+
+    /// The index of the first visible line
+    first_line: Value<usize>,
+    line_count: Value<usize>,
+    unresolved: Value<String>,
+}
+
+impl RequestPreviewState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        RequestPreviewState {
+            app_theme: app_theme.into(),
+            lines: List::empty(),
+            first_line: 0.into(),
+            line_count: 0.into(),
+            unresolved: String::new().into(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RequestPreview {
+    lines: Vec<PreviewLine>,
+}
+
+impl RequestPreview {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "request_preview",
+            template("floating_windows/templates/request_preview"),
+            RequestPreview::default(),
+            RequestPreviewState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("request_preview"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut RequestPreviewState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn scroll(&self, state: &mut RequestPreviewState, down: bool) {
+        let last_first_line = self.lines.len().saturating_sub(VISIBLE_LINES);
+        let first_line = *state.first_line.to_ref();
+
+        let first_line = match down {
+            true => (first_line + 1).min(last_first_line),
+            false => first_line.saturating_sub(1),
+        };
+
+        state.first_line.set(first_line);
+        self.update_lines(state);
+    }
+
+    fn update_lines(&self, state: &mut RequestPreviewState) {
+        let app_theme = state.app_theme.to_ref();
+        let foreground = app_theme.overlay_foreground.to_ref().clone();
+        let background = app_theme.overlay_background.to_ref().clone();
+        let unresolved_foreground = app_theme.overlay_cancel_foreground.to_ref().clone();
+        let unresolved_background = app_theme.overlay_cancel_background.to_ref().clone();
+        drop(app_theme);
+
+        while state.lines.len() > 0 {
+            state.lines.pop_front();
+        }
+
+        let first_line = *state.first_line.to_ref();
+        self.lines
+            .iter()
+            .skip(first_line)
+            .take(VISIBLE_LINES)
+            .for_each(|line| {
+                let mut spans = List::<PreviewSpan>::empty();
+
+                line.iter().for_each(|segment| {
+                    let (fg, bg) = match segment.resolved {
+                        true => (foreground.clone(), background.clone()),
+                        false => (unresolved_foreground.clone(), unresolved_background.clone()),
+                    };
+
+                    spans.push(PreviewSpan {
+                        text: segment.text.clone().into(),
+                        foreground: fg.into(),
+                        background: bg.into(),
+                    });
+                });
+
+                state.lines.push(PreviewLineState {
+                    spans: spans.into(),
+                });
+            });
+    }
+}
+
+impl Component for RequestPreview {
+    type State = RequestPreviewState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+    }
+
+    fn on_key(
+        &mut self,
+        event: component::KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match event.code {
+            component::KeyCode::Char(char) => match char {
+                'j' => self.scroll(state, true),
+                'k' => self.scroll(state, false),
+                _ => {}
+            },
+
+            component::KeyCode::Down => self.scroll(state, true),
+            component::KeyCode::Up => self.scroll(state, false),
+
+            component::KeyCode::Esc => {
+                // NOTE: This sends first_line to satisfy publish() but is not used
+                context.publish("request_preview__cancel", |state| &state.first_line)
+            }
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        let Ok(request_preview_message) = serde_json::from_str::<RequestPreviewMessages>(&message)
+        else {
+            return;
+        };
+
+        match request_preview_message {
+            RequestPreviewMessages::Preview(lines) => {
+                let unresolved_count = lines
+                    .iter()
+                    .flatten()
+                    .filter(|segment| !segment.resolved)
+                    .count();
+
+                self.lines = lines;
+
+                state.line_count.set(self.lines.len());
+                state.unresolved.set(match unresolved_count {
+                    0 => String::new(),
+                    1 => String::from(" 1 unresolved placeholder "),
+                    count => format!(" {count} unresolved placeholders "),
+                });
+                state.first_line.set(0);
+                self.update_lines(state);
+            }
+        }
+    }
+}
+
+impl DashboardMessageHandler for RequestPreview {
+    fn handle_message(
+        _: component::CommonVal<'_>,
+        ident: impl Into<String>,
+        state: &mut DashboardState,
+        mut context: Context<'_, DashboardState>,
+        _: Elements<'_, '_>,
+        _: Ref<'_, HashMap<String, ComponentId<String>>>,
+    ) {
+        let event: String = ident.into();
+
+        #[allow(clippy::single_match)]
+        match event.as_str() {
+            "request_preview__cancel" => {
+                state.floating_window.set(FloatingWindow::None);
+                context.set_focus("id", "app");
+            }
+
+            _ => {}
+        }
+    }
+}
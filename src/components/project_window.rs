@@ -348,6 +348,21 @@ impl DashboardMessageHandler for ProjectWindow {
                             &component_ids,
                             context.emitter,
                         );
+
+                        let textarea_msg = TextAreaMessages::SetInput(
+                            state_endpoint.graphql_variables.to_ref().to_string(),
+                        );
+
+                        let Ok(message) = serde_json::to_string(&textarea_msg) else {
+                            return;
+                        };
+
+                        let _ = send_message(
+                            "graphql_variables_input",
+                            message,
+                            &component_ids,
+                            context.emitter,
+                        );
                     }
                     Err(_) => todo!(),
                 }
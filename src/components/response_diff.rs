@@ -0,0 +1,690 @@
+use std::{cell::RefCell, collections::HashMap, fs, rc::Rc};
+
+use anathema::{
+    component::{Component, ComponentId, KeyCode, KeyEvent},
+    geometry::Size,
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{CommonVal, List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    history::{get_compared_entries, get_history_response_path, load_history, HistoryEntry},
+    response_diff::{
+        diff_responses, get_diff_summary, parse_ignore_paths, split_ignore_entries, DiffKind,
+        DiffRow,
+    },
+    response_files::ResponseFiles,
+    snapshots::{compare_snapshot, load_snapshot, Snapshot},
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::{dashboard::DashboardMessages, send_message};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ResponseDiffMessages {
+    /// Lists the saved responses the last response of the endpoint can be
+    /// compared with, or compares the last response with the approved
+    /// snapshot of the endpoint when it is given
+    Load {
+        project: String,
+        endpoint: String,
+        ignore_paths: Vec<String>,
+        ignore_headers: Vec<String>,
+        snapshot: Option<Snapshot>,
+
+        /// The files of the last response, None before the first request
+        response: Option<ResponseFiles>,
+    },
+}
+
+/// What the last response is compared with
+enum Compared {
+    History(HistoryEntry),
+
+    /// The approved snapshot, with the last response as a snapshot
+    Snapshot(Snapshot),
+}
+
+#[derive(Debug, State)]
+pub struct DiffLine {
+    left: Value<String>,
+    right: Value<String>,
+    left_color: Value<String>,
+    right_color: Value<String>,
+}
+
+#[derive(Debug, State)]
+pub struct ComparedLine {
+    text: Value<String>,
+    row_color: Value<String>,
+    row_fg_color: Value<String>,
+}
+
+#[derive(Default, State)]
+pub struct ResponseDiffState {
+    app_theme: Value<AppTheme>,
+
+    /// The saved responses are listed until one is chosen
+    choosing: Value<bool>,
+    compared_lines: Value<List<ComparedLine>>,
+    lines: Value<List<DiffLine>>,
+
+    /// What the last response is compared with
+    title: Value<String>,
+
+    /// How many values or lines changed, or why the diff failed
+    summary: Value<String>,
+    ignored: Value<String>,
+    position: Value<String>,
+    transient_event_value: Value<String>,
+}
+
+impl ResponseDiffState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        ResponseDiffState {
+            app_theme: app_theme.into(),
+            choosing: true.into(),
+            compared_lines: List::empty(),
+            lines: List::empty(),
+            title: String::new().into(),
+            summary: String::new().into(),
+            ignored: String::new().into(),
+            position: String::new().into(),
+            transient_event_value: String::new().into(),
+        }
+    }
+}
+
+pub struct ResponseDiff {
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+    size: Option<Size>,
+    project: String,
+    endpoint: String,
+    ignore_paths: Vec<String>,
+    ignore_headers: Vec<String>,
+    response: Option<ResponseFiles>,
+
+    /// The saved responses that can be compared with the last response
+    compared_entries: Vec<HistoryEntry>,
+
+    compared: Option<Compared>,
+    rows: Vec<DiffRow>,
+    show_unchanged: bool,
+
+    /// Index of the selected saved response
+    cursor: usize,
+
+    /// Index of the first visible row
+    offset: usize,
+}
+
+impl ResponseDiff {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "response_diff",
+            template("templates/response_diff"),
+            ResponseDiff {
+                component_ids: ids.clone(),
+                size: None,
+                project: String::new(),
+                endpoint: String::new(),
+                ignore_paths: vec![],
+                ignore_headers: vec![],
+                response: None,
+                compared_entries: vec![],
+                compared: None,
+                rows: vec![],
+                show_unchanged: true,
+                cursor: 0,
+                offset: 0,
+            },
+            ResponseDiffState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("response_diff"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut ResponseDiffState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn update_size(&mut self, context: Context<'_, ResponseDiffState>) {
+        let size = context.viewport.size();
+
+        let app_titles = 2; // top/bottom menus of dashboard
+        let url_method_inputs = 3; // height of url and method inputs with borders
+        let response_borders = 2; // borders around the diff
+        let response_metrics = 2; // timing and size lines under the diff
+
+        let total_height_offset =
+            app_titles + url_method_inputs + response_borders + response_metrics;
+
+        self.size = Some(Size {
+            width: size.width.saturating_sub(2),
+            height: size.height.saturating_sub(total_height_offset),
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn load(
+        &mut self,
+        project: String,
+        endpoint: String,
+        ignore_paths: Vec<String>,
+        ignore_headers: Vec<String>,
+        snapshot: Option<Snapshot>,
+        response: Option<ResponseFiles>,
+        state: &mut ResponseDiffState,
+        context: Context<'_, ResponseDiffState>,
+    ) {
+        let entries = load_history(&project).unwrap_or_default();
+
+        self.compared_entries = get_compared_entries(&entries, &endpoint);
+        self.compared = snapshot.map(Compared::Snapshot);
+        self.rows.clear();
+        self.cursor = 0;
+        self.offset = 0;
+        self.project = project;
+        self.endpoint = endpoint;
+        self.ignore_paths = ignore_paths;
+        self.ignore_headers = ignore_headers;
+        self.response = response;
+
+        state.choosing.set(self.compared.is_none());
+        state.summary.set(String::new());
+        state.ignored.set(self.get_ignored());
+
+        self.update_diff(state);
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message(
+            "response_diff_ignore_input",
+            self.get_ignored(),
+            &ids,
+            context.emitter,
+        );
+        drop(ids);
+
+        self.render(state);
+    }
+
+    /// The ignored JSON paths and headers as they are typed in the input
+    fn get_ignored(&self) -> String {
+        self.ignore_paths
+            .iter()
+            .chain(&self.ignore_headers)
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn choose_compared(&mut self, state: &mut ResponseDiffState) {
+        self.cursor = 0;
+        self.offset = 0;
+        state.choosing.set(true);
+
+        self.render(state);
+    }
+
+    fn compare(&mut self, state: &mut ResponseDiffState) {
+        let Some(entry) = self.compared_entries.get(self.cursor).cloned() else {
+            return;
+        };
+
+        self.compared = Some(Compared::History(entry));
+        self.offset = 0;
+        state.choosing.set(false);
+
+        self.update_diff(state);
+        self.render(state);
+    }
+
+    /// Compares the chosen saved response or the approved snapshot with the
+    /// last response, the last rows are kept when the diff fails
+    fn update_diff(&mut self, state: &mut ResponseDiffState) {
+        let rows = match &self.compared {
+            None => return,
+
+            Some(Compared::History(entry)) => {
+                state.title.set(get_entry_label(entry, &self.endpoint));
+
+                read_response(&self.project, entry, self.response.as_ref()).and_then(
+                    |(compared, last)| diff_responses(&compared, &last, &self.ignore_paths),
+                )
+            }
+
+            Some(Compared::Snapshot(response)) => {
+                state.title.set(format!("Snapshot of {}", self.endpoint));
+
+                load_snapshot(&self.project, &self.endpoint).and_then(|approved| {
+                    let Some(approved) = approved else {
+                        anyhow::bail!("{} has no approved snapshot", self.endpoint);
+                    };
+
+                    compare_snapshot(
+                        &approved,
+                        response,
+                        &self.ignore_paths,
+                        &self.ignore_headers,
+                    )
+                })
+            }
+        };
+
+        match rows {
+            Ok(rows) => {
+                state.summary.set(get_diff_summary(&rows));
+                self.rows = rows;
+            }
+
+            Err(error) => state.summary.set(error.to_string()),
+        }
+    }
+
+    fn update_ignored(&mut self, text: &str, state: &mut ResponseDiffState) {
+        let (ignore_paths, ignore_headers) = split_ignore_entries(parse_ignore_paths(text));
+        self.ignore_paths = ignore_paths;
+        self.ignore_headers = ignore_headers;
+        state.ignored.set(self.get_ignored());
+
+        self.update_diff(state);
+        self.render(state);
+    }
+
+    fn render(&mut self, state: &mut ResponseDiffState) {
+        match *state.choosing.to_ref() {
+            true => self.render_compared_entries(state),
+            false => self.render_rows(state),
+        }
+    }
+
+    fn render_compared_entries(&mut self, state: &mut ResponseDiffState) {
+        while state.compared_lines.len() > 0 {
+            state.compared_lines.pop_front();
+        }
+
+        state
+            .title
+            .set(String::from("Compare the last response with"));
+
+        if self.compared_entries.is_empty() {
+            state.position.set(String::new());
+            state.summary.set(String::from(
+                "There are no saved responses to compare with yet",
+            ));
+
+            return;
+        }
+
+        let height = self.get_page_size();
+        let width = self.size.map_or(usize::MAX, |size| size.width);
+
+        self.cursor = self.cursor.min(self.compared_entries.len() - 1);
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + height {
+            self.offset = self.cursor + 1 - height;
+        }
+
+        let app_theme = state.app_theme.to_ref();
+        let foreground = app_theme.foreground.to_ref().clone();
+        let background = app_theme.background.to_ref().clone();
+
+        let visible_lines: Vec<ComparedLine> = self
+            .compared_entries
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(index, entry)| {
+                let (row_fg_color, row_color) = match index == self.cursor {
+                    true => (background.clone(), foreground.clone()),
+                    false => (foreground.clone(), background.clone()),
+                };
+
+                let label = get_entry_label(entry, &self.endpoint);
+
+                ComparedLine {
+                    text: fit_line(&label, width).into(),
+                    row_color: row_color.into(),
+                    row_fg_color: row_fg_color.into(),
+                }
+            })
+            .collect();
+        drop(app_theme);
+
+        visible_lines
+            .into_iter()
+            .for_each(|line| state.compared_lines.push(line));
+
+        state.summary.set(String::new());
+        state.position.set(format!(
+            "{}/{}",
+            self.cursor + 1,
+            self.compared_entries.len()
+        ));
+    }
+
+    fn render_rows(&mut self, state: &mut ResponseDiffState) {
+        while state.lines.len() > 0 {
+            state.lines.pop_front();
+        }
+
+        let height = self.get_page_size();
+        let width = self.size.map_or(80, |size| size.width);
+        let side_width = width.saturating_sub(3) / 2; // " │ " between the sides
+
+        let visible_rows = get_visible_rows(&self.rows, self.show_unchanged);
+        self.offset = self.offset.min(visible_rows.len().saturating_sub(height));
+
+        let app_theme = state.app_theme.to_ref();
+        let foreground = app_theme.foreground.to_ref().clone();
+        let removed = app_theme.overlay_cancel_background.to_ref().clone();
+        let added = app_theme.overlay_submit_background.to_ref().clone();
+        let changed = app_theme.menu_color_4.to_ref().clone();
+
+        let visible_lines: Vec<DiffLine> = visible_rows
+            .iter()
+            .skip(self.offset)
+            .take(height)
+            .map(|row| {
+                let (left_color, right_color) = match row.kind {
+                    DiffKind::Unchanged => (&foreground, &foreground),
+                    DiffKind::Removed => (&removed, &foreground),
+                    DiffKind::Added => (&foreground, &added),
+                    DiffKind::Changed => (&changed, &changed),
+                };
+
+                DiffLine {
+                    left: fit_line(&row.left, side_width).into(),
+                    right: fit_line(&row.right, side_width).into(),
+                    left_color: left_color.clone().into(),
+                    right_color: right_color.clone().into(),
+                }
+            })
+            .collect();
+        drop(app_theme);
+
+        visible_lines
+            .into_iter()
+            .for_each(|line| state.lines.push(line));
+
+        let last_line = (self.offset + height).min(visible_rows.len());
+        state.position.set(format!(
+            "{}-{last_line}/{}",
+            (self.offset + 1).min(last_line),
+            visible_rows.len()
+        ));
+    }
+
+    fn get_page_size(&self) -> usize {
+        self.size.map_or(1, |size| size.height.max(1))
+    }
+
+    fn move_cursor(&mut self, rows: isize, state: &mut ResponseDiffState) {
+        match *state.choosing.to_ref() {
+            true => self.cursor = self.cursor.saturating_add_signed(rows),
+            false => self.offset = self.offset.saturating_add_signed(rows),
+        }
+
+        self.render(state);
+    }
+
+    /// Scrolls to the next or previous row that is not unchanged
+    fn go_to_change(&mut self, forward: bool, state: &mut ResponseDiffState) {
+        let visible_rows = get_visible_rows(&self.rows, self.show_unchanged);
+
+        let change = match forward {
+            true => visible_rows
+                .iter()
+                .enumerate()
+                .skip(self.offset + 1)
+                .find(|(_, row)| row.kind != DiffKind::Unchanged)
+                .map(|(index, _)| index),
+            false => visible_rows
+                .iter()
+                .enumerate()
+                .take(self.offset)
+                .rfind(|(_, row)| row.kind != DiffKind::Unchanged)
+                .map(|(index, _)| index),
+        };
+
+        if let Some(index) = change {
+            self.offset = index;
+            self.render(state);
+        }
+    }
+
+    fn toggle_unchanged(&mut self, state: &mut ResponseDiffState) {
+        self.show_unchanged = !self.show_unchanged;
+        self.offset = 0;
+
+        self.render(state);
+    }
+
+    fn send_dashboard_message(
+        &self,
+        dashboard_message: DashboardMessages,
+        context: Context<'_, ResponseDiffState>,
+    ) {
+        let Ok(message) = serde_json::to_string(&dashboard_message) else {
+            return;
+        };
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message("dashboard", message, &ids, context.emitter);
+    }
+}
+
+impl Component for ResponseDiff {
+    type State = ResponseDiffState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn resize(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn receive(
+        &mut self,
+        ident: &str,
+        value: CommonVal<'_>,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match ident {
+            "response_diff_ignore__input_update" => {
+                self.update_ignored(&value.to_string(), state);
+                context.publish("response_diff_ignore_change", |state| &state.ignored);
+            }
+
+            "response_diff_ignore__input_escape" => {
+                context.set_focus("id", "response_diff");
+            }
+
+            _ => {
+                context.publish(ident, |state| &state.transient_event_value);
+            }
+        }
+    }
+
+    fn on_key(
+        &mut self,
+        event: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        let page_size = self.get_page_size() as isize;
+
+        match event.code {
+            KeyCode::Char(char) => match event.ctrl {
+                true => match char {
+                    'd' => self.move_cursor(page_size, state),
+                    'u' => self.move_cursor(-page_size, state),
+                    _ => {}
+                },
+
+                false => match char {
+                    'j' => self.move_cursor(1, state),
+                    'k' => self.move_cursor(-1, state),
+                    'n' => self.go_to_change(true, state),
+                    'p' => self.go_to_change(false, state),
+                    'u' => self.toggle_unchanged(state),
+                    'c' => self.choose_compared(state),
+                    'a' => self.send_dashboard_message(DashboardMessages::ApproveSnapshot, context),
+                    'i' => context.set_focus("id", "response_diff_ignore_input"),
+                    'r' => {
+                        self.send_dashboard_message(DashboardMessages::ShowResponseBody, context)
+                    }
+                    'b' => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+                    _ => {}
+                },
+            },
+
+            KeyCode::Down => self.move_cursor(1, state),
+            KeyCode::Up => self.move_cursor(-1, state),
+            KeyCode::Enter if *state.choosing.to_ref() => self.compare(state),
+            KeyCode::Esc => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        let Ok(response_diff_message) = serde_json::from_str::<ResponseDiffMessages>(&message)
+        else {
+            return;
+        };
+
+        match response_diff_message {
+            ResponseDiffMessages::Load {
+                project,
+                endpoint,
+                ignore_paths,
+                ignore_headers,
+                snapshot,
+                response,
+            } => self.load(
+                project,
+                endpoint,
+                ignore_paths,
+                ignore_headers,
+                snapshot,
+                response,
+                state,
+                context,
+            ),
+        }
+    }
+}
+
+/// The saved response of the entry and the last response
+fn read_response(
+    project: &str,
+    entry: &HistoryEntry,
+    response: Option<&ResponseFiles>,
+) -> anyhow::Result<(String, String)> {
+    let Some(compared_path) = get_history_response_path(project, entry) else {
+        anyhow::bail!("The response of this request was not saved");
+    };
+
+    let Some(response) = response else {
+        anyhow::bail!("Send a request to compare its response");
+    };
+
+    let Some(last) = response.read_text()? else {
+        anyhow::bail!("The last response is too large to compare");
+    };
+
+    let compared = String::from_utf8_lossy(&fs::read(compared_path)?).to_string();
+
+    Ok((compared, last))
+}
+
+/// e.g. `History: 2026-01-02 10:00:00 GET 200` for the earlier responses of
+/// the endpoint and `Get Users: ...` for the last response of other endpoints
+fn get_entry_label(entry: &HistoryEntry, endpoint: &str) -> String {
+    let source = match entry.endpoint == endpoint {
+        true => "History",
+        false => entry.endpoint.as_str(),
+    };
+
+    let time = entry.timestamp.get(..19).unwrap_or(&entry.timestamp);
+
+    format!(
+        "{source}: {} {} {} {}",
+        time.replace('T', " "),
+        entry.method,
+        entry.status,
+        entry.url
+    )
+}
+
+/// Cuts lines longer than the width and pads shorter ones so the right side
+/// of the diff lines up
+fn fit_line(line: &str, width: usize) -> String {
+    let length = line.chars().count();
+
+    match length > width {
+        true => {
+            let truncated: String = line.chars().take(width.saturating_sub(3)).collect();
+            format!("{truncated}...")
+        }
+        false if width == usize::MAX => line.to_string(),
+        false => format!("{line}{}", " ".repeat(width - length)),
+    }
+}
+
+fn get_visible_rows(rows: &[DiffRow], show_unchanged: bool) -> Vec<&DiffRow> {
+    rows.iter()
+        .filter(|row| show_unchanged || row.kind != DiffKind::Unchanged)
+        .collect()
+}
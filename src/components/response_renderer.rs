@@ -1,10 +1,5 @@
 use std::{
-    cell::RefCell,
-    cmp::min,
-    collections::HashMap,
-    fs::File,
-    io::{BufReader, Read},
-    rc::Rc,
+    cell::RefCell, cmp::min, collections::HashMap, fs::File, io::BufReader, path::Path, rc::Rc,
 };
 
 use anathema::{
@@ -15,13 +10,21 @@ use anathema::{
     state::{CommonVal, Hex, List, State, Value},
     widgets::{components::events::KeyState, Elements},
 };
-use log::info;
+use anyhow::bail;
+use log::{error, info};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use syntect::highlighting::Theme;
 
 use crate::{
     app::GlobalEventHandler,
-    options::get_syntax_theme,
+    binary::write_hex_dump,
+    filter_options::{get_match_ranges, get_regex_error, FilterOptions},
+    json_query::JsonQuery,
+    options::{get_options, get_response_wrap, get_syntax_theme, save_options, ResponseWrap},
+    pretty::{pretty_print, PrettyFormat},
+    response_files::{write_once, ResponseFiles},
+    response_lines::{IndexedLines, ResponseLines},
     templates::template,
     theme::{get_app_theme, get_app_theme_persisted, AppTheme},
 };
@@ -34,27 +37,66 @@ use super::{
 
 pub const CODE_SAMPLE: &str = include_str!("../../themes/code_sample.rs");
 
+/// Columns moved by each horizontal scroll
+const HORIZONTAL_SCROLL_STEP: usize = 8;
+
 #[derive(Debug)]
 enum ScrollDirection {
     Up,
     Down,
+    Left,
+    Right,
 }
 
 pub struct ResponseRenderer {
     #[allow(unused)]
     component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
     text_filter: TextFilter,
+    filter_options: FilterOptions,
+
+    /// None when the filter is empty
+    filter_regex: Option<Regex>,
     theme: Option<Theme>,
 
     // overflow: Option<&'app mut Overflow>,
     size: Option<Size>,
-    response_reader: Option<BufReader<File>>,
+    response_files: Option<ResponseFiles>,
     response_offset: usize,
     viewport_height: usize,
     extension: String,
 
-    // All lines from the response
-    response_lines: Vec<String>,
+    wrap: ResponseWrap,
+
+    /// The first column shown when lines are not wrapped
+    horizontal_offset: usize,
+
+    /// The response line and the first column of every row on the screen
+    viewable_rows: Vec<(usize, usize)>,
+
+    /// How many response lines fit on the screen, wrapped lines take more
+    /// than one row
+    visible_lines: usize,
+
+    /// Shows JSON, XML and HTML responses formatted instead of as they arrived
+    pretty: bool,
+
+    /// The response is not text, it is shown as a hex dump
+    binary: bool,
+
+    /// Shows a text response as a hex dump
+    hex: bool,
+
+    /// The response could not be pretty printed, it is not tried again
+    unformatted: bool,
+
+    /// The response parsed when the first JSON query runs on it
+    response_json: Option<serde_json::Value>,
+
+    // All lines from the response, read from its file when they are shown
+    response_lines: ResponseLines,
+
+    /// The response lines on the screen, from the response offset on
+    screen_lines: Vec<String>,
 
     code_sample: Option<String>,
     code_ext: Option<String>,
@@ -91,13 +133,25 @@ impl ResponseRenderer {
             text_filter: TextFilter {
                 ..Default::default()
             },
+            filter_options: FilterOptions::default(),
+            filter_regex: None,
             theme: None,
-            response_reader: None,
+            response_files: None,
             response_offset: 0,
             viewport_height: 0,
             size: None,
             extension: "".to_string(),
-            response_lines: vec![],
+            wrap: get_response_wrap(),
+            horizontal_offset: 0,
+            viewable_rows: vec![],
+            visible_lines: 0,
+            pretty: true,
+            binary: false,
+            hex: false,
+            unformatted: false,
+            response_json: None,
+            response_lines: ResponseLines::default(),
+            screen_lines: vec![],
             code_ext: None,
             code_sample: None,
         }
@@ -154,9 +208,8 @@ impl ResponseRenderer {
         extension: String,
         state: &mut ResponseRendererState,
         offset: usize,
-        context: Context<'_, ResponseRendererState>,
     ) {
-        if self.response_reader.is_none() {
+        if self.response_files.is_none() {
             return;
         }
 
@@ -167,27 +220,162 @@ impl ResponseRenderer {
         self.extension = extension;
 
         let size = self.size.unwrap();
-        let response_reader = self.response_reader.as_mut().unwrap();
         self.response_offset = offset;
         self.viewport_height = size.height;
 
-        let mut buf: Vec<u8> = vec![];
-        match response_reader.read_to_end(&mut buf) {
-            Ok(_) => {
-                let response = String::from_utf8(buf).unwrap_or(String::from("oops"));
-                let lines = response.lines();
-                let response_lines: Vec<String> = lines.map(|s| s.to_string()).collect();
-                self.response_lines = response_lines;
+        self.scroll_response(state, offset);
+    }
+
+    /// Opens the response, formatted when pretty printing is on and the
+    /// extension is JSON, XML or HTML. Responses that can not be formatted
+    /// are opened as they arrived. Binary responses, and text responses when
+    /// hex is on, are opened as a hex dump. Only the lines on the screen are
+    /// read from the opened file.
+    fn open_response(
+        &mut self,
+        extension: &str,
+        state: &mut ResponseRendererState,
+    ) -> anyhow::Result<ResponseLines> {
+        let Some(files) = &self.response_files else {
+            return Ok(ResponseLines::default());
+        };
+
+        if self.binary {
+            state.response_view.set(String::from("Hex"));
+
+            return open_lines(&files.text);
+        }
+
+        if self.hex {
+            format_hex_response(files)?;
+            state.response_view.set(String::from("Hex"));
+
+            return open_lines(&files.hex);
+        }
+
+        let format = PrettyFormat::from_extension(extension).filter(|_| self.pretty);
+        if let Some(format) = format.filter(|_| !self.unformatted) {
+            if format_response(format, files).is_ok() {
+                state.response_view.set(String::from("Pretty"));
+
+                return open_lines(&files.pretty);
             }
 
-            Err(error) => {
-                let error_message = format!("There was an error reading the response: {}", error);
+            self.unformatted = true;
+        }
 
-                self.send_error_message(&error_message, context);
+        state.response_view.set(String::from("Raw"));
+
+        open_lines(&files.text)
+    }
+
+    fn toggle_pretty(
+        &mut self,
+        state: &mut ResponseRendererState,
+        elements: Elements<'_, '_>,
+        context: Context<'_, ResponseRendererState>,
+    ) {
+        self.pretty = !self.pretty;
+        self.reload_response(state, elements, context);
+    }
+
+    fn toggle_hex(
+        &mut self,
+        state: &mut ResponseRendererState,
+        elements: Elements<'_, '_>,
+        context: Context<'_, ResponseRendererState>,
+    ) {
+        // NOTE: Binary responses can only be shown as a hex dump
+        if self.binary {
+            return;
+        }
+
+        self.hex = !self.hex;
+        self.reload_response(state, elements, context);
+    }
+
+    /// Reads the response again, when there is a JSON query the values it
+    /// finds are shown instead of the response
+    fn reload_response(
+        &mut self,
+        state: &mut ResponseRendererState,
+        elements: Elements<'_, '_>,
+        context: Context<'_, ResponseRendererState>,
+    ) {
+        if self.response_files.is_none() {
+            return;
+        }
+
+        let query = state.query.to_ref().trim().to_string();
+        state.has_query_status.set(!query.is_empty());
+
+        if query.is_empty() {
+            let extension = self.extension.clone();
+            match self.open_response(&extension, state) {
+                Ok(response_lines) => self.response_lines = response_lines,
+                Err(error) => {
+                    let error_message =
+                        format!("There was an error reading the response: {}", error);
+                    self.send_error_message(&error_message, context);
+
+                    return;
+                }
             }
+        } else if let Err(error) = self.read_query_lines(&query, state) {
+            // NOTE: The last results stay visible while the query is being typed
+            state.query_status.set(error.to_string());
+
+            return;
         }
 
-        self.scroll_response(state, offset);
+        self.scroll_response(state, 0);
+
+        if !state.filter.to_ref().is_empty() {
+            let filter = state.filter.to_ref().to_string();
+            self.apply_response_filter(filter, state, context, elements);
+        }
+    }
+
+    fn read_query_lines(
+        &mut self,
+        query: &str,
+        state: &mut ResponseRendererState,
+    ) -> anyhow::Result<()> {
+        let query = JsonQuery::parse(query)?;
+
+        let Some(files) = &self.response_files else {
+            return Ok(());
+        };
+
+        if self.response_json.is_none() {
+            let response_reader = BufReader::new(File::open(&files.text)?);
+            let Ok(response_json) = serde_json::from_reader(response_reader) else {
+                bail!("The response is not valid JSON");
+            };
+
+            self.response_json = Some(response_json);
+        }
+
+        let Some(response_json) = &self.response_json else {
+            return Ok(());
+        };
+
+        let values = query.run(response_json)?;
+
+        let mut lines = vec![];
+        for value in &values {
+            let pretty_value = serde_json::to_string_pretty(value)?;
+            lines.extend(pretty_value.lines().map(String::from));
+        }
+
+        self.response_lines = ResponseLines::Memory(lines);
+        state.response_view.set(String::from("Query"));
+        state.query_status.set(match values.len() {
+            1 => String::from("1 result"),
+            count => format!("{count} results"),
+        });
+
+        Ok(())
     }
 
     fn send_error_message(&self, error_message: &str, context: Context<'_, ResponseRendererState>) {
@@ -202,7 +390,7 @@ impl ResponseRenderer {
     }
 
     fn scroll_response(&mut self, state: &mut ResponseRendererState, offset: usize) {
-        if self.response_reader.is_none() {
+        if self.response_files.is_none() {
             return;
         }
 
@@ -217,31 +405,49 @@ impl ResponseRenderer {
         self.viewport_height = size.height;
 
         let mut viewable_lines: Vec<String> = vec![];
+        let mut viewable_rows: Vec<(usize, usize)> = vec![];
+        let mut visible_lines = 0;
 
         let last_response_line_index = self.response_lines.len();
-        let last_viewable_index = self.response_offset + self.viewport_height;
-        let ending_index = min(last_viewable_index, last_response_line_index);
+        let width = size.width.saturating_sub(2).max(1); // borders around response
 
-        info!(
-            "Rendering from {} to {}",
-            self.response_offset, ending_index
-        );
+        info!("Rendering from {}", self.response_offset);
 
-        for index in self.response_offset..ending_index {
-            info!("Rendering index: {index}");
+        // NOTE: Only the lines that can fit on the screen are read, a line
+        // takes at least one row
+        let screen_lines = match self
+            .response_lines
+            .get_lines(self.response_offset, self.viewport_height)
+        {
+            Ok(screen_lines) => screen_lines,
+            Err(error) => {
+                error!("Could not read the response lines: {error}");
+                self.response_offset = last_offset;
 
-            let line = &self.response_lines[index];
-            info!("Rendering line: {line}");
+                return;
+            }
+        };
 
-            if line.len() > size.width {
-                let (new_line, _) = line.split_at(size.width.saturating_sub(5));
+        // NOTE: Rows keep the index of their response line so filtering and
+        // percent scrolled count response lines and not wrapped rows
+        for (index, line) in (self.response_offset..).zip(&screen_lines) {
+            let rows = get_line_rows(line, width, self.wrap, self.horizontal_offset);
 
-                let t = format!("{new_line}...");
+            if viewable_lines.len() + rows.len() > self.viewport_height {
+                let free_rows = self.viewport_height - viewable_lines.len();
+                rows.into_iter().take(free_rows).for_each(|(column, row)| {
+                    viewable_rows.push((index, column));
+                    viewable_lines.push(row);
+                });
 
-                viewable_lines.push(t);
-            } else {
-                viewable_lines.push(line.to_string());
+                break;
             }
+
+            rows.into_iter().for_each(|(column, row)| {
+                viewable_rows.push((index, column));
+                viewable_lines.push(row);
+            });
+            visible_lines += 1;
         }
 
         let theme = get_syntax_theme();
@@ -259,6 +465,10 @@ impl ResponseRenderer {
 
         state.percent_scrolled.set(percent_scrolled);
 
+        self.viewable_rows = viewable_rows;
+        self.visible_lines = visible_lines;
+        self.screen_lines = screen_lines;
+
         info!("viewable_response: {viewable_response}");
 
         self.set_response(state, viewable_response, Some(theme));
@@ -280,8 +490,12 @@ impl ResponseRenderer {
             state.lines.remove(0);
         }
 
-        let (highlighted_lines, parsed_theme) =
-            highlight(&viewable_response, &self.extension, theme);
+        // NOTE: Hex dumps are not highlighted with the syntax of the response
+        let extension = match self.binary || self.hex {
+            true => "txt",
+            false => self.extension.as_str(),
+        };
+        let (highlighted_lines, parsed_theme) = highlight(&viewable_response, extension, theme);
 
         let bg = parsed_theme.settings.background;
         if self.theme.is_none() {
@@ -384,6 +598,7 @@ impl ResponseRenderer {
         } else {
             current_index + 1
         };
+        self.update_filter_position(state);
 
         let line = self
             .text_filter
@@ -407,6 +622,7 @@ impl ResponseRenderer {
         } else {
             self.text_filter.indexes.len().saturating_sub(1)
         };
+        self.update_filter_position(state);
 
         let line = self
             .text_filter
@@ -419,27 +635,90 @@ impl ResponseRenderer {
     fn scroll(
         &mut self,
         state: &mut ResponseRendererState,
-        elements: Elements<'_, '_>,
-        context: Context<'_, ResponseRendererState>,
+        _elements: Elements<'_, '_>,
+        _context: Context<'_, ResponseRendererState>,
         direction: ScrollDirection,
     ) {
         info!("scroll() direction: {direction:?}");
 
+        // NOTE: Wrapped lines take more than one row, a page is the lines
+        // that fit on the screen
+        let page = match self.wrap {
+            ResponseWrap::NoWrap => self.viewport_height,
+            ResponseWrap::SoftWrap => self.visible_lines.max(1),
+        };
+
         let new_offset = match direction {
-            ScrollDirection::Up => self.response_offset.saturating_sub(self.viewport_height),
-            ScrollDirection::Down => self.response_offset + self.viewport_height,
+            ScrollDirection::Up => self.response_offset.saturating_sub(page),
+            ScrollDirection::Down => self.response_offset + page,
+            ScrollDirection::Left | ScrollDirection::Right => {
+                if self.wrap == ResponseWrap::SoftWrap {
+                    return;
+                }
+
+                self.horizontal_offset = self.get_horizontal_offset(direction);
+                self.response_offset
+            }
         };
 
         info!("new_offset: {new_offset}");
 
         self.scroll_response(state, new_offset);
 
-        if !state.filter.to_ref().is_empty() {
-            let filter = state.filter.to_ref().to_string();
-            self.apply_response_filter(filter, state, context, elements);
+        // NOTE: Scrolling keeps the matches, finding them again would read
+        // the whole response
+        self.apply_filter_highlights(state);
+    }
+
+    /// Horizontal scrolling stops when the end of the longest line on the
+    /// screen is visible
+    fn get_horizontal_offset(&self, direction: ScrollDirection) -> usize {
+        let Some(size) = self.size else {
+            return 0;
+        };
+
+        let longest_line = self
+            .screen_lines
+            .iter()
+            .take(self.viewport_height)
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or_default();
+        let max_offset = longest_line.saturating_sub(size.width.saturating_sub(2));
+
+        match direction {
+            ScrollDirection::Left => self
+                .horizontal_offset
+                .saturating_sub(HORIZONTAL_SCROLL_STEP),
+            _ => min(self.horizontal_offset + HORIZONTAL_SCROLL_STEP, max_offset),
         }
     }
 
+    fn toggle_wrap(
+        &mut self,
+        state: &mut ResponseRendererState,
+        context: Context<'_, ResponseRendererState>,
+    ) {
+        self.wrap = match self.wrap {
+            ResponseWrap::NoWrap => ResponseWrap::SoftWrap,
+            ResponseWrap::SoftWrap => ResponseWrap::NoWrap,
+        };
+        self.horizontal_offset = 0;
+        state.wrap_label.set(get_wrap_label(self.wrap));
+
+        let mut options = get_options();
+        options.response_wrap = Some(self.wrap);
+        if let Err(error) = save_options(options) {
+            let error_message = format!("Error saving the response wrap mode: {}", error);
+            self.send_error_message(&error_message, context);
+
+            return;
+        }
+
+        self.scroll_response(state, self.response_offset);
+        self.apply_filter_highlights(state);
+    }
+
     fn apply_response_filter(
         &mut self,
         filter: String,
@@ -448,6 +727,31 @@ impl ResponseRenderer {
         elements: Elements<'_, '_>,
     ) {
         info!("apply_response_filter");
+
+        // NOTE: An invalid regex keeps the last matches while it is being typed
+        let filter_regex = match filter.is_empty() {
+            true => None,
+            false => match self.filter_options.get_regex(&filter) {
+                Ok(filter_regex) => Some(filter_regex),
+                Err(error) => {
+                    state.filter_error.set(get_regex_error(&error));
+                    state.has_filter_error.set(true);
+
+                    return;
+                }
+            },
+        };
+
+        state.has_filter_error.set(false);
+        self.filter_regex = filter_regex;
+
+        // NOTE: Keep the position of Ctrl N/P when the same filter is applied
+        // again after scrolling
+        let navigation_cursor = match filter == self.text_filter.filter {
+            true => self.text_filter.search_navigation_cursor,
+            false => 0,
+        };
+
         loop {
             if state.filter_indexes.len() == 0 {
                 break;
@@ -458,24 +762,32 @@ impl ResponseRenderer {
         state.filter_total.set(0);
         state.filter_nav_index.set(0);
 
-        if filter.is_empty() {
+        let Some(filter_regex) = &self.filter_regex else {
             self.text_filter = self.get_text_filter(state);
+            self.update_filter_position(state);
             clear_highlights(state);
 
             return;
-        }
+        };
 
-        self.response_lines
-            .iter()
-            .enumerate()
-            .for_each(|(idx, line)| {
-                if line.contains(&filter) {
-                    state.filter_indexes.push(idx);
-                }
-            });
+        let filter_indexes = self
+            .response_lines
+            .find_lines(|line| !get_match_ranges(filter_regex, line).is_empty());
+
+        match filter_indexes {
+            Ok(filter_indexes) => filter_indexes
+                .into_iter()
+                .for_each(|index| state.filter_indexes.push(index)),
+            Err(error) => error!("Could not filter the response: {error}"),
+        }
 
         state.filter_total.set(state.filter_indexes.len());
 
+        let last_index = state.filter_indexes.len().saturating_sub(1);
+        state
+            .filter_nav_index
+            .set(navigation_cursor.min(last_index));
+
         if state.filter_indexes.len() > 0 {
             self.text_filter = self.get_text_filter(state);
 
@@ -489,6 +801,42 @@ impl ResponseRenderer {
 
             clear_highlights(state);
         }
+
+        self.update_filter_position(state);
+    }
+
+    /// Shows which match Ctrl N/P went to, e.g. `3/42`
+    fn update_filter_position(&self, state: &mut ResponseRendererState) {
+        let position = match self.text_filter.total {
+            0 => String::from("0/0"),
+            total => format!("{}/{total}", self.text_filter.search_navigation_cursor + 1),
+        };
+
+        state.filter_position.set(position);
+    }
+
+    fn toggle_filter_option(
+        &mut self,
+        state: &mut ResponseRendererState,
+        elements: Elements<'_, '_>,
+        context: Context<'_, ResponseRendererState>,
+        option: char,
+    ) {
+        match option {
+            'r' => self.filter_options.regex = !self.filter_options.regex,
+            'i' => self.filter_options.ignore_case = !self.filter_options.ignore_case,
+            'w' => self.filter_options.whole_word = !self.filter_options.whole_word,
+            _ => return,
+        }
+
+        state.filter_modes.set(self.filter_options.get_label());
+
+        // NOTE: The matches change with the options, start from the first one
+        self.text_filter.search_navigation_cursor = 0;
+        let filter = state.filter.to_ref().to_string();
+        self.apply_response_filter(filter, state, context, elements);
+
+        state.has_search_matches.set(self.text_filter.total > 0);
     }
 
     fn do_filter(
@@ -510,18 +858,18 @@ impl ResponseRenderer {
     }
 
     fn apply_filter_highlights(&mut self, state: &mut ResponseRendererState) {
-        if let Some(size) = self.size {
-            let rows = size.height;
-            let range_end = (self.response_offset + rows).saturating_sub(1);
-            let match_range = (self.response_offset, range_end);
+        let Some(filter_regex) = &self.filter_regex else {
+            return;
+        };
 
-            highlight_matches(
-                state,
-                match_range,
-                &mut self.text_filter.indexes,
-                &self.text_filter.filter,
-            );
-        }
+        highlight_matches(
+            state,
+            &self.viewable_rows,
+            &self.screen_lines,
+            self.response_offset,
+            &self.text_filter.indexes,
+            filter_regex,
+        );
     }
 
     fn get_text_filter(&self, state: &mut ResponseRendererState) -> TextFilter {
@@ -553,6 +901,46 @@ impl ResponseRenderer {
         self.apply_filter_highlights(state);
     }
 
+    fn show_response_tree(&self, context: Context<'_, ResponseRendererState>) {
+        if let Ok(message) = serde_json::to_string(&DashboardMessages::ShowResponseTree) {
+            let Ok(ids) = self.component_ids.try_borrow() else {
+                return;
+            };
+
+            let _ = send_message("dashboard", message, &ids, context.emitter);
+        }
+    }
+
+    fn show_response_diff(&self, context: Context<'_, ResponseRendererState>) {
+        if let Ok(message) = serde_json::to_string(&DashboardMessages::ShowResponseDiff) {
+            let Ok(ids) = self.component_ids.try_borrow() else {
+                return;
+            };
+
+            let _ = send_message("dashboard", message, &ids, context.emitter);
+        }
+    }
+
+    fn show_response_validation(&self, context: Context<'_, ResponseRendererState>) {
+        if let Ok(message) = serde_json::to_string(&DashboardMessages::ShowResponseValidation) {
+            let Ok(ids) = self.component_ids.try_borrow() else {
+                return;
+            };
+
+            let _ = send_message("dashboard", message, &ids, context.emitter);
+        }
+    }
+
+    fn approve_snapshot(&self, context: Context<'_, ResponseRendererState>) {
+        if let Ok(message) = serde_json::to_string(&DashboardMessages::ApproveSnapshot) {
+            let Ok(ids) = self.component_ids.try_borrow() else {
+                return;
+            };
+
+            let _ = send_message("dashboard", message, &ids, context.emitter);
+        }
+    }
+
     fn back_to_request(&self, context: Context<'_, ResponseRendererState>) {
         if let Ok(message) = serde_json::to_string(&DashboardMessages::BackToRequest) {
             let Ok(ids) = self.component_ids.try_borrow() else {
@@ -623,8 +1011,29 @@ pub struct ResponseRendererState {
     pub filter_total: Value<usize>,
     pub filter_nav_index: Value<usize>,
     pub has_search_matches: Value<bool>,
+
+    /// The current match of the total, e.g. `3/42`
+    pub filter_position: Value<String>,
+
+    /// Literal, or the enabled regex, ignore case and whole word modes
+    pub filter_modes: Value<String>,
+    pub filter_error: Value<String>,
+    pub has_filter_error: Value<bool>,
     pub transient_event_value: Value<String>,
 
+    /// Pretty, Raw or Query
+    pub response_view: Value<String>,
+
+    /// Soft Wrap or No Wrap
+    pub wrap_label: Value<String>,
+
+    /// The jq expression or JSON path run on the response
+    pub query: Value<String>,
+
+    /// How many values the query found, or why it failed
+    pub query_status: Value<String>,
+    pub has_query_status: Value<bool>,
+
     #[state_ignore]
     pub filter_input_focused: bool,
 }
@@ -657,6 +1066,15 @@ impl ResponseRendererState {
             filter_indexes: List::from_iter(vec![]),
             filter_total: 0.into(),
             filter_nav_index: 0.into(),
+            filter_position: "0/0".to_string().into(),
+            filter_modes: "Literal".to_string().into(),
+            filter_error: "".to_string().into(),
+            has_filter_error: false.into(),
+            response_view: "Raw".to_string().into(),
+            wrap_label: get_wrap_label(get_response_wrap()).into(),
+            query: "".to_string().into(),
+            query_status: "".to_string().into(),
+            has_query_status: false.into(),
         }
     }
 }
@@ -696,6 +1114,17 @@ impl Component for ResponseRenderer {
                 info!("Set focus back to response_renderer");
             }
 
+            "response_query__input_update" => {
+                state.query.set(value.to_string());
+                context.publish("response_query_change", |state| &state.query);
+
+                self.reload_response(state, elements, context);
+            }
+
+            "response_query__input_escape" => {
+                context.set_focus("id", "response_renderer");
+            }
+
             _ => {
                 context.publish(ident, |state| &state.transient_event_value);
             }
@@ -750,6 +1179,12 @@ impl Component for ResponseRenderer {
                 anathema::component::MouseState::ScrollDown => {
                     direction = Some(ScrollDirection::Down);
                 }
+                anathema::component::MouseState::ScrollLeft => {
+                    direction = Some(ScrollDirection::Left);
+                }
+                anathema::component::MouseState::ScrollRight => {
+                    direction = Some(ScrollDirection::Right);
+                }
                 _ => {}
             });
 
@@ -757,10 +1192,7 @@ impl Component for ResponseRenderer {
             return;
         };
 
-        match dir {
-            ScrollDirection::Up => self.scroll(state, elements, context, ScrollDirection::Up),
-            ScrollDirection::Down => self.scroll(state, elements, context, ScrollDirection::Down),
-        }
+        self.scroll(state, elements, context, dir);
     }
 
     fn on_key(
@@ -773,6 +1205,8 @@ impl Component for ResponseRenderer {
         #[allow(clippy::single_match)]
         match event.code {
             KeyCode::Esc => self.back_to_request(context),
+            KeyCode::Left => self.scroll(state, elements, context, ScrollDirection::Left),
+            KeyCode::Right => self.scroll(state, elements, context, ScrollDirection::Right),
 
             KeyCode::Char(char) => match event.ctrl {
                 true => match char {
@@ -785,6 +1219,15 @@ impl Component for ResponseRenderer {
 
                 false => match char {
                     'b' => self.back_to_request(context),
+                    'p' => self.toggle_pretty(state, elements, context),
+                    'x' => self.toggle_hex(state, elements, context),
+                    'q' => context.set_focus("id", "response_query_input"),
+                    's' => self.toggle_wrap(state, context),
+                    'r' | 'i' | 'w' => self.toggle_filter_option(state, elements, context, char),
+                    't' => self.show_response_tree(context),
+                    'd' => self.show_response_diff(context),
+                    'e' => self.show_response_validation(context),
+                    'a' => self.approve_snapshot(context),
                     'f' => {
                         context.set_focus("id", "response_body_input");
                         state.filter_input_focused = true;
@@ -850,17 +1293,29 @@ impl Component for ResponseRenderer {
                     self.update_app_theme(state);
                 }
 
-                ResponseRendererMessages::ResponseUpdate(extension) => {
-                    // TODO: Try to delete this file if the program closes/quits/crashes
-                    let reader_result = get_file_reader("/tmp/tome_response.txt");
-                    if reader_result.is_err() {
-                        println!("Error getting reader for response...");
-                        return;
+                ResponseRendererMessages::ResponseUpdate {
+                    extension,
+                    binary,
+                    files,
+                } => {
+                    self.binary = binary;
+                    self.unformatted = false;
+                    self.response_files = Some(files);
+
+                    match self.open_response(&extension, state) {
+                        Ok(response_lines) => self.response_lines = response_lines,
+                        Err(error) => {
+                            let error_message =
+                                format!("There was an error reading the response: {}", error);
+                            self.send_error_message(&error_message, context);
+
+                            return;
+                        }
                     }
 
-                    let response_reader = reader_result.unwrap();
-                    self.response_reader = Some(response_reader);
-                    self.render_response(extension, state, 0, context);
+                    self.response_json = None;
+                    self.horizontal_offset = 0;
+                    self.render_response(extension, state, 0);
                 }
 
                 ResponseRendererMessages::SyntaxPreview(theme) => {
@@ -917,81 +1372,120 @@ fn clear_highlights(state: &mut ResponseRendererState) {
 
 fn highlight_matches(
     state: &mut ResponseRendererState,
-    match_range: (usize, usize),
-    matches: &mut [usize],
-    filter: &str,
+    viewable_rows: &[(usize, usize)],
+    screen_lines: &[String],
+    first_line: usize,
+    matches: &[usize],
+    filter_regex: &Regex,
 ) {
     info!("Highlighting");
     clear_highlights(state);
 
-    let response = state.viewable_response.to_ref();
-    let response_lines = response.lines().collect::<Vec<&str>>();
     let mut lines = state.lines.to_mut();
 
-    matches.iter_mut().for_each(|match_index| {
-        let view_index = match_index.saturating_sub(match_range.0);
-
-        if *match_index < match_range.0 || *match_index > match_range.1 {
-            return;
-        }
+    viewable_rows
+        .iter()
+        .enumerate()
+        .for_each(|(row_index, (line_index, first_column))| {
+            if !matches.contains(line_index) {
+                return;
+            }
 
-        info!("Checking match_index: {match_index} for range: {match_range:?}");
-        info!("Getting view_index: {view_index}, match_index: {match_index}");
+            let response_line = line_index
+                .checked_sub(first_line)
+                .and_then(|index| screen_lines.get(index));
+            let Some(response_line) = response_line else {
+                return;
+            };
 
-        if let Some(matching_line) = response_lines.get(view_index) {
-            let mut matched_display_line = lines.get_mut(view_index);
+            let Some(display_line_value) = lines.get_mut(row_index) else {
+                return;
+            };
 
-            if let Some(ref mut display_line_value) = matched_display_line {
-                //info!("display line: {:?}", display_line_value.to_ref().spans.);
-                let r = display_line_value
-                    .to_ref()
-                    .spans
-                    .to_ref()
-                    .iter()
-                    .map(|span| {
-                        let s = span.to_ref().text.to_ref().to_string();
-                        info!("actual span: {s}");
+            let mut display_line = display_line_value.to_mut();
+            let mut spans = display_line.spans.to_mut();
+
+            // NOTE: Matches are found in the whole line, the row only shows
+            // the columns from first_column on
+            get_match_ranges(filter_regex, response_line)
+                .into_iter()
+                .flatten()
+                .filter_map(|column| column.checked_sub(*first_column))
+                .for_each(|span_ndx| {
+                    if let Some(span) = spans.get_mut(span_ndx) {
+                        let mut s = span.to_mut();
+                        let og_bg = Some(*s.background.to_ref());
+                        s.original_background.set(og_bg);
+                        s.background.set(Hex::from((255, 255, 0)));
+
+                        let og_fg = Some(*s.foreground.to_ref());
+                        s.original_foreground.set(og_fg);
+                        s.foreground.set(Hex::from((0, 0, 0)));
+                    }
+                });
+        });
+}
 
-                        s
-                    })
-                    .collect::<String>();
-                info!("span: {:?}", r);
+/// The rows a response line is shown in with the column each row starts at.
+/// Soft wrapped lines are split at the width, other lines show the columns
+/// from the horizontal offset.
+fn get_line_rows(
+    line: &str,
+    width: usize,
+    wrap: ResponseWrap,
+    horizontal_offset: usize,
+) -> Vec<(usize, String)> {
+    let chars: Vec<char> = line.chars().collect();
+
+    match wrap {
+        ResponseWrap::NoWrap => {
+            let row = chars.iter().skip(horizontal_offset).take(width).collect();
+
+            vec![(horizontal_offset, row)]
+        }
 
-                let mut display_line = (*display_line_value).to_mut();
+        ResponseWrap::SoftWrap if chars.is_empty() => vec![(0, String::new())],
 
-                let mut spans = display_line.spans.to_mut();
+        ResponseWrap::SoftWrap => chars
+            .chunks(width)
+            .enumerate()
+            .map(|(index, row)| (index * width, row.iter().collect()))
+            .collect(),
+    }
+}
 
-                info!("Applying highlighting to line: {matching_line}");
-                matching_line.match_indices(filter).for_each(|(index, _)| {
-                    let last_ndx = index + filter.len();
-                    for span_ndx in index..last_ndx {
-                        if let Some(span) = spans.get_mut(span_ndx) {
-                            info!("span.to_ref().text: {:?}", span.to_ref().text.to_ref());
+fn get_wrap_label(wrap: ResponseWrap) -> String {
+    match wrap {
+        ResponseWrap::NoWrap => String::from("No Wrap"),
+        ResponseWrap::SoftWrap => String::from("Soft Wrap"),
+    }
+}
 
-                            let mut s = span.to_mut();
-                            let og_bg = Some(*s.background.to_ref());
-                            s.original_background.set(og_bg);
-                            s.background.set(Hex::from((255, 255, 0)));
+fn open_lines(path: &Path) -> anyhow::Result<ResponseLines> {
+    Ok(ResponseLines::File(IndexedLines::open(path)?))
+}
 
-                            let og_fg = Some(*s.foreground.to_ref());
-                            s.original_foreground.set(og_fg);
-                            s.foreground.set(Hex::from((0, 0, 0)));
-                        }
-                    }
-                });
-            };
-        };
-    });
+/// The response is formatted once, later toggles open the formatted file
+fn format_response(format: PrettyFormat, files: &ResponseFiles) -> anyhow::Result<()> {
+    write_once(&files.pretty, |pretty_response| {
+        pretty_print(format, File::open(&files.text)?, pretty_response)
+    })
 }
 
-fn get_file_reader(file_path: &str) -> anyhow::Result<BufReader<File>> {
-    let file = File::open(file_path)?;
-    Ok(BufReader::new(file))
+fn format_hex_response(files: &ResponseFiles) -> anyhow::Result<()> {
+    write_once(&files.hex, |hex_response| {
+        write_hex_dump(BufReader::new(File::open(&files.raw)?), hex_response)
+    })
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ResponseRendererMessages {
-    ResponseUpdate(String),
+    /// A new response was saved, binary responses are saved as a hex dump
+    ResponseUpdate {
+        extension: String,
+        binary: bool,
+        files: ResponseFiles,
+    },
     SyntaxPreview(Option<String>),
     ThemeUpdate,
 }
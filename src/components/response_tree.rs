@@ -0,0 +1,481 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::{self, File},
+    io::BufReader,
+    path::PathBuf,
+    rc::Rc,
+};
+
+use anathema::{
+    component::{Component, ComponentId, KeyCode, KeyEvent},
+    geometry::Size,
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{CommonVal, List, State, Value},
+    widgets::Elements,
+};
+use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    json_tree::{JsonTree, TreeRow},
+    response_files::MAX_LOADED_BODY_BYTES,
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::{dashboard::DashboardMessages, send_message};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ResponseTreeMessages {
+    /// Parses the text of the last response and shows it with only the root
+    /// expanded
+    Load(PathBuf),
+}
+
+#[derive(Debug, State)]
+pub struct TreeLine {
+    text: Value<String>,
+    row_color: Value<String>,
+    row_fg_color: Value<String>,
+}
+
+#[derive(Default, State)]
+pub struct ResponseTreeState {
+    app_theme: Value<AppTheme>,
+    lines: Value<List<TreeLine>>,
+    selected_path: Value<String>,
+    position: Value<String>,
+    transient_event_value: Value<String>,
+}
+
+impl ResponseTreeState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        ResponseTreeState {
+            app_theme: app_theme.into(),
+            lines: List::empty(),
+            selected_path: String::from("$").into(),
+            position: String::new().into(),
+            transient_event_value: String::new().into(),
+        }
+    }
+}
+
+pub struct ResponseTree {
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+    tree: Option<JsonTree>,
+    size: Option<Size>,
+
+    /// Index of the selected row
+    cursor: usize,
+
+    /// Index of the first visible row
+    offset: usize,
+}
+
+impl ResponseTree {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "response_tree",
+            template("templates/response_tree"),
+            ResponseTree {
+                component_ids: ids.clone(),
+                tree: None,
+                size: None,
+                cursor: 0,
+                offset: 0,
+            },
+            ResponseTreeState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("response_tree"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut ResponseTreeState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn update_size(&mut self, context: Context<'_, ResponseTreeState>) {
+        let size = context.viewport.size();
+
+        let app_titles = 2; // top/bottom menus of dashboard
+        let url_method_inputs = 3; // height of url and method inputs with borders
+        let response_borders = 2; // borders around the tree
+        let response_metrics = 2; // timing and size lines under the tree
+
+        let total_height_offset =
+            app_titles + url_method_inputs + response_borders + response_metrics;
+
+        self.size = Some(Size {
+            width: size.width.saturating_sub(2),
+            height: size.height.saturating_sub(total_height_offset),
+        });
+    }
+
+    fn load(
+        &mut self,
+        response: PathBuf,
+        state: &mut ResponseTreeState,
+        context: Context<'_, ResponseTreeState>,
+    ) {
+        self.cursor = 0;
+        self.offset = 0;
+
+        let tree = read_tree(response);
+
+        match tree {
+            Ok(tree) => self.tree = Some(tree),
+            Err(error) => {
+                self.tree = None;
+
+                let message = format!("The response can not be shown as a tree: {error}");
+                self.send_dashboard_message(DashboardMessages::ShowError(message), context);
+            }
+        }
+
+        self.render(state);
+    }
+
+    fn render(&mut self, state: &mut ResponseTreeState) {
+        while state.lines.len() > 0 {
+            state.lines.pop_front();
+        }
+
+        let Some(tree) = &self.tree else {
+            state.selected_path.set(String::new());
+            state.position.set(String::new());
+
+            return;
+        };
+
+        let height = self.size.map_or(1, |size| size.height.max(1));
+        let width = self.size.map_or(usize::MAX, |size| size.width);
+
+        let rows = tree.rows();
+        self.cursor = self.cursor.min(rows.len().saturating_sub(1));
+        if self.cursor < self.offset {
+            self.offset = self.cursor;
+        } else if self.cursor >= self.offset + height {
+            self.offset = self.cursor + 1 - height;
+        }
+
+        let app_theme = state.app_theme.to_ref();
+        let foreground = app_theme.foreground.to_ref().clone();
+        let background = app_theme.background.to_ref().clone();
+
+        let visible_lines: Vec<TreeLine> = rows
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(height)
+            .map(|(index, row)| {
+                let (row_fg_color, row_color) = match index == self.cursor {
+                    true => (background.clone(), foreground.clone()),
+                    false => (foreground.clone(), background.clone()),
+                };
+
+                TreeLine {
+                    text: truncate_line(row.get_line(), width).into(),
+                    row_color: row_color.into(),
+                    row_fg_color: row_fg_color.into(),
+                }
+            })
+            .collect();
+        drop(app_theme);
+
+        visible_lines
+            .into_iter()
+            .for_each(|line| state.lines.push(line));
+
+        if let Some(row) = rows.get(self.cursor) {
+            state.selected_path.set(row.path.clone());
+        }
+
+        state
+            .position
+            .set(format!("{}/{}", self.cursor + 1, rows.len()));
+    }
+
+    fn move_cursor(&mut self, rows: isize, state: &mut ResponseTreeState) {
+        self.cursor = self.cursor.saturating_add_signed(rows);
+        self.render(state);
+    }
+
+    fn get_page_size(&self) -> isize {
+        self.size.map_or(1, |size| size.height.max(1)) as isize
+    }
+
+    /// Expands the selected node, or selects its first child when it is
+    /// already expanded
+    fn expand(&mut self, state: &mut ResponseTreeState) {
+        let Some(tree) = self.tree.as_mut() else {
+            return;
+        };
+
+        let selected = tree
+            .rows()
+            .get(self.cursor)
+            .filter(|row| row.is_container())
+            .map(|row| (row.expanded, row.path.clone()));
+
+        match selected {
+            Some((true, _)) => self.cursor += 1,
+            Some((false, path)) => tree.expand(&path),
+            None => {}
+        }
+
+        self.render(state);
+    }
+
+    /// Collapses the selected node, or selects its parent when it is
+    /// collapsed or is not an object or array
+    fn collapse(&mut self, state: &mut ResponseTreeState) {
+        let Some(tree) = self.tree.as_mut() else {
+            return;
+        };
+
+        let rows = tree.rows();
+        let Some(row) = rows.get(self.cursor) else {
+            return;
+        };
+
+        let expanded_path = row.expanded.then(|| row.path.clone());
+        let parent = get_parent_index(&rows, self.cursor);
+
+        match (expanded_path, parent) {
+            (Some(path), _) => tree.collapse(&path),
+            (None, Some(parent)) => self.cursor = parent,
+            (None, None) => {}
+        }
+
+        self.render(state);
+    }
+
+    fn toggle(&mut self, state: &mut ResponseTreeState) {
+        let Some(tree) = self.tree.as_mut() else {
+            return;
+        };
+
+        let path = tree
+            .rows()
+            .get(self.cursor)
+            .filter(|row| row.is_container())
+            .map(|row| row.path.clone());
+
+        if let Some(path) = path {
+            tree.toggle(&path);
+        }
+
+        self.render(state);
+    }
+
+    fn expand_all(&mut self, state: &mut ResponseTreeState) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.expand_all();
+        }
+
+        self.render(state);
+    }
+
+    fn collapse_all(&mut self, state: &mut ResponseTreeState) {
+        if let Some(tree) = self.tree.as_mut() {
+            tree.collapse_all();
+        }
+
+        self.cursor = 0;
+        self.render(state);
+    }
+
+    fn copy_value(&self, context: Context<'_, ResponseTreeState>) {
+        let Some(tree) = &self.tree else {
+            return;
+        };
+
+        if let Some(row) = tree.rows().get(self.cursor) {
+            self.copy(row.get_copy_value(), "Value copied to clipboard", context);
+        }
+    }
+
+    fn copy_path(&self, context: Context<'_, ResponseTreeState>) {
+        let Some(tree) = &self.tree else {
+            return;
+        };
+
+        if let Some(row) = tree.rows().get(self.cursor) {
+            self.copy(row.path.clone(), "JSON path copied to clipboard", context);
+        }
+    }
+
+    fn copy(&self, text: String, message: &str, context: Context<'_, ResponseTreeState>) {
+        let Ok(mut clipboard) = Clipboard::new() else {
+            let message = String::from("Error accessing your clipboard");
+            self.send_dashboard_message(DashboardMessages::ShowError(message), context);
+
+            return;
+        };
+
+        let dashboard_message = match clipboard.set().text(text) {
+            Ok(_) => {
+                DashboardMessages::ShowSucces((String::from("Clipboard"), message.to_string()))
+            }
+            Err(error) => DashboardMessages::ShowError(error.to_string()),
+        };
+
+        self.send_dashboard_message(dashboard_message, context);
+    }
+
+    fn send_dashboard_message(
+        &self,
+        dashboard_message: DashboardMessages,
+        context: Context<'_, ResponseTreeState>,
+    ) {
+        let Ok(message) = serde_json::to_string(&dashboard_message) else {
+            return;
+        };
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message("dashboard", message, &ids, context.emitter);
+    }
+}
+
+impl Component for ResponseTree {
+    type State = ResponseTreeState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn resize(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn receive(
+        &mut self,
+        ident: &str,
+        _: CommonVal<'_>,
+        _: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        context.publish(ident, |state| &state.transient_event_value);
+    }
+
+    fn on_key(
+        &mut self,
+        event: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        match event.code {
+            KeyCode::Char(char) => match event.ctrl {
+                true => match char {
+                    'd' => self.move_cursor(self.get_page_size(), state),
+                    'u' => self.move_cursor(-self.get_page_size(), state),
+                    _ => {}
+                },
+
+                false => match char {
+                    'j' => self.move_cursor(1, state),
+                    'k' => self.move_cursor(-1, state),
+                    'l' => self.expand(state),
+                    'h' => self.collapse(state),
+                    ' ' => self.toggle(state),
+                    'e' => self.expand_all(state),
+                    'c' => self.collapse_all(state),
+                    'y' => self.copy_value(context),
+                    'p' => self.copy_path(context),
+                    't' => {
+                        self.send_dashboard_message(DashboardMessages::ShowResponseBody, context)
+                    }
+                    'b' => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+                    _ => {}
+                },
+            },
+
+            KeyCode::Down => self.move_cursor(1, state),
+            KeyCode::Up => self.move_cursor(-1, state),
+            KeyCode::Right => self.expand(state),
+            KeyCode::Left => self.collapse(state),
+            KeyCode::Enter => self.toggle(state),
+            KeyCode::Esc => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        let Ok(response_tree_message) = serde_json::from_str::<ResponseTreeMessages>(&message)
+        else {
+            return;
+        };
+
+        match response_tree_message {
+            ResponseTreeMessages::Load(response) => self.load(response, state, context),
+        }
+    }
+}
+
+/// The whole tree is kept in memory, larger responses are only shown as text
+fn read_tree(response: PathBuf) -> anyhow::Result<JsonTree> {
+    if fs::metadata(&response)?.len() > MAX_LOADED_BODY_BYTES {
+        anyhow::bail!("the response is too large");
+    }
+
+    JsonTree::parse(BufReader::new(File::open(response)?))
+}
+
+/// The closest row above the row at `index` that is less indented
+fn get_parent_index(rows: &[TreeRow<'_>], index: usize) -> Option<usize> {
+    let depth = rows.get(index)?.depth;
+
+    rows[..index].iter().rposition(|row| row.depth < depth)
+}
+
+fn truncate_line(line: String, width: usize) -> String {
+    if line.chars().count() <= width {
+        return line;
+    }
+
+    let truncated: String = line.chars().take(width.saturating_sub(3)).collect();
+
+    format!("{truncated}...")
+}
@@ -0,0 +1,370 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anathema::{
+    component::{Component, ComponentId, KeyCode, KeyEvent},
+    geometry::Size,
+    prelude::{Context, TuiBackend},
+    runtime::RuntimeBuilder,
+    state::{CommonVal, List, State, Value},
+    widgets::Elements,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::GlobalEventHandler,
+    json_schema::{validate_response, Violation},
+    templates::template,
+    theme::{get_app_theme, AppTheme},
+};
+
+use super::{dashboard::DashboardMessages, send_message};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ResponseValidationMessages {
+    /// Validates the last response with the schema of the endpoint
+    Load {
+        schema: String,
+        body: Option<String>,
+    },
+}
+
+#[derive(Debug, State)]
+pub struct ViolationLine {
+    pointer: Value<String>,
+    message: Value<String>,
+}
+
+#[derive(Default, State)]
+pub struct ResponseValidationState {
+    app_theme: Value<AppTheme>,
+    lines: Value<List<ViolationLine>>,
+
+    /// How many violations were found, or why the response could not be
+    /// validated
+    summary: Value<String>,
+    schema: Value<String>,
+    position: Value<String>,
+    transient_event_value: Value<String>,
+}
+
+impl ResponseValidationState {
+    pub fn new() -> Self {
+        let app_theme = get_app_theme();
+
+        ResponseValidationState {
+            app_theme: app_theme.into(),
+            lines: List::empty(),
+            summary: String::new().into(),
+            schema: String::new().into(),
+            position: String::new().into(),
+            transient_event_value: String::new().into(),
+        }
+    }
+}
+
+pub struct ResponseValidation {
+    component_ids: Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+    size: Option<Size>,
+    schema: String,
+
+    /// The body of the last response, None before the first request
+    body: Option<String>,
+    violations: Vec<Violation>,
+
+    /// Index of the first visible violation
+    offset: usize,
+}
+
+impl ResponseValidation {
+    pub fn register(
+        ids: &Rc<RefCell<HashMap<String, ComponentId<String>>>>,
+        builder: &mut RuntimeBuilder<TuiBackend, GlobalEventHandler>,
+    ) -> anyhow::Result<()> {
+        let id = builder.register_component(
+            "response_validation",
+            template("templates/response_validation"),
+            ResponseValidation {
+                component_ids: ids.clone(),
+                size: None,
+                schema: String::new(),
+                body: None,
+                violations: vec![],
+                offset: 0,
+            },
+            ResponseValidationState::new(),
+        )?;
+
+        let mut ids_ref = ids.borrow_mut();
+        ids_ref.insert(String::from("response_validation"), id);
+
+        Ok(())
+    }
+
+    fn update_app_theme(&self, state: &mut ResponseValidationState) {
+        let app_theme = get_app_theme();
+        state.app_theme.set(app_theme);
+    }
+
+    fn update_size(&mut self, context: Context<'_, ResponseValidationState>) {
+        let size = context.viewport.size();
+
+        let app_titles = 2; // top/bottom menus of dashboard
+        let url_method_inputs = 3; // height of url and method inputs with borders
+        let response_borders = 2; // borders around the violations
+        let response_metrics = 2; // timing and size lines under the violations
+
+        let total_height_offset =
+            app_titles + url_method_inputs + response_borders + response_metrics;
+
+        self.size = Some(Size {
+            width: size.width.saturating_sub(2),
+            height: size.height.saturating_sub(total_height_offset),
+        });
+    }
+
+    fn load(
+        &mut self,
+        schema: String,
+        body: Option<String>,
+        state: &mut ResponseValidationState,
+        context: Context<'_, ResponseValidationState>,
+    ) {
+        self.schema = schema;
+        self.body = body;
+        self.offset = 0;
+        state.schema.set(self.schema.clone());
+
+        self.validate(state);
+        self.render(state);
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message(
+            "response_schema_input",
+            self.schema.clone(),
+            &ids,
+            context.emitter,
+        );
+    }
+
+    fn update_schema(&mut self, schema: String, state: &mut ResponseValidationState) {
+        self.schema = schema;
+        self.offset = 0;
+        state.schema.set(self.schema.clone());
+
+        self.validate(state);
+        self.render(state);
+    }
+
+    fn validate(&mut self, state: &mut ResponseValidationState) {
+        self.violations.clear();
+
+        let summary = match &self.body {
+            None => String::from("Send a request to validate its response"),
+            Some(_) if self.schema.trim().is_empty() => {
+                String::from("Press S to set the JSON Schema of the endpoint")
+            }
+            Some(body) => match validate_response(&self.schema, body) {
+                Ok(violations) => {
+                    self.violations = violations;
+                    get_summary(self.violations.len())
+                }
+                Err(error) => error.to_string(),
+            },
+        };
+
+        state.summary.set(summary);
+    }
+
+    fn render(&mut self, state: &mut ResponseValidationState) {
+        while state.lines.len() > 0 {
+            state.lines.pop_front();
+        }
+
+        let height = self.size.map_or(1, |size| size.height.max(1));
+        let width = self.size.map_or(usize::MAX, |size| size.width);
+
+        self.offset = self
+            .offset
+            .min(self.violations.len().saturating_sub(height));
+
+        self.violations
+            .iter()
+            .skip(self.offset)
+            .take(height)
+            .for_each(|violation| {
+                let pointer = match violation.pointer.is_empty() {
+                    true => String::from("(root)"),
+                    false => violation.pointer.clone(),
+                };
+                let message_width = width.saturating_sub(pointer.chars().count() + 2);
+
+                state.lines.push(ViolationLine {
+                    pointer: pointer.into(),
+                    message: truncate_line(&violation.message, message_width).into(),
+                });
+            });
+
+        let last_line = (self.offset + height).min(self.violations.len());
+        state.position.set(format!(
+            "{}-{last_line}/{}",
+            (self.offset + 1).min(last_line),
+            self.violations.len()
+        ));
+    }
+
+    fn scroll(&mut self, rows: isize, state: &mut ResponseValidationState) {
+        self.offset = self.offset.saturating_add_signed(rows);
+        self.render(state);
+    }
+
+    fn get_page_size(&self) -> isize {
+        self.size.map_or(1, |size| size.height.max(1) as isize)
+    }
+
+    fn send_dashboard_message(
+        &self,
+        dashboard_message: DashboardMessages,
+        context: Context<'_, ResponseValidationState>,
+    ) {
+        let Ok(message) = serde_json::to_string(&dashboard_message) else {
+            return;
+        };
+
+        let Ok(ids) = self.component_ids.try_borrow() else {
+            return;
+        };
+
+        let _ = send_message("dashboard", message, &ids, context.emitter);
+    }
+}
+
+impl Component for ResponseValidation {
+    type State = ResponseValidationState;
+    type Message = String;
+
+    fn accept_focus(&self) -> bool {
+        true
+    }
+
+    fn on_focus(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_app_theme(state);
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn resize(
+        &mut self,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        self.update_size(context);
+        self.render(state);
+    }
+
+    fn receive(
+        &mut self,
+        ident: &str,
+        value: CommonVal<'_>,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        match ident {
+            "response_schema__input_update" => {
+                self.update_schema(value.to_string(), state);
+                context.publish("response_schema_change", |state| &state.schema);
+            }
+
+            "response_schema__input_escape" => {
+                context.set_focus("id", "response_validation");
+            }
+
+            _ => {
+                context.publish(ident, |state| &state.transient_event_value);
+            }
+        }
+    }
+
+    fn on_key(
+        &mut self,
+        event: KeyEvent,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        mut context: Context<'_, Self::State>,
+    ) {
+        let page_size = self.get_page_size();
+
+        match event.code {
+            KeyCode::Char(char) => match event.ctrl {
+                true => match char {
+                    'd' => self.scroll(page_size, state),
+                    'u' => self.scroll(-page_size, state),
+                    _ => {}
+                },
+
+                false => match char {
+                    'j' => self.scroll(1, state),
+                    'k' => self.scroll(-1, state),
+                    's' => context.set_focus("id", "response_schema_input"),
+                    'r' => {
+                        self.send_dashboard_message(DashboardMessages::ShowResponseBody, context)
+                    }
+                    'b' => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+                    _ => {}
+                },
+            },
+
+            KeyCode::Down => self.scroll(1, state),
+            KeyCode::Up => self.scroll(-1, state),
+            KeyCode::Esc => self.send_dashboard_message(DashboardMessages::BackToRequest, context),
+
+            _ => {}
+        }
+    }
+
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        context: Context<'_, Self::State>,
+    ) {
+        let Ok(validation_message) = serde_json::from_str::<ResponseValidationMessages>(&message)
+        else {
+            return;
+        };
+
+        match validation_message {
+            ResponseValidationMessages::Load { schema, body } => {
+                self.load(schema, body, state, context)
+            }
+        }
+    }
+}
+
+fn get_summary(count: usize) -> String {
+    match count {
+        0 => String::from("The response matches the schema"),
+        1 => String::from("1 violation"),
+        count => format!("{count} violations"),
+    }
+}
+
+fn truncate_line(line: &str, width: usize) -> String {
+    match line.chars().count() > width {
+        true => {
+            let truncated: String = line.chars().take(width.saturating_sub(3)).collect();
+            format!("{truncated}...")
+        }
+        false => line.to_string(),
+    }
+}
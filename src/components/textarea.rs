@@ -65,7 +65,6 @@ pub struct CursorPosition {
 }
 
 pub struct TextArea {
-    #[allow(unused)]
     pub input_for: Option<String>,
 
     #[allow(unused)]
@@ -144,14 +143,15 @@ impl TextArea {
 
     fn send_to_listeners(&self, code: KeyCode, state: &mut TextAreaState, emitter: Emitter) {
         if let KeyCode::Char(_) = code {
-            // TODO: Fix the outgoing message so it is not 100% coupled to only response body
-            // editing, use InputUpdate instead of InputChange, like in edit_input.rs
             if let Ok(ids) = self.component_ids.try_borrow() {
-                let input_value = state.input.clone();
-                let input_change_message =
-                    DashboardMessages::TextArea(TextAreaMessages::InputChange(input_value));
-
-                if let Ok(serialized_message) = serde_json::to_string(&input_change_message) {
+                let input_update_message =
+                    DashboardMessages::TextArea(TextAreaMessages::InputUpdate(TextAreaUpdate {
+                        id: self.input_for.clone().unwrap_or_default(),
+                        value: state.input.clone(),
+                        cursor_prefix: state.cursor_prefix.to_ref().to_string(),
+                    }));
+
+                if let Ok(serialized_message) = serde_json::to_string(&input_update_message) {
                     for listener in &self.listeners {
                         let msg = serialized_message.clone();
 
@@ -164,9 +164,17 @@ impl TextArea {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TextAreaUpdate {
+    pub id: String,
+    pub value: String,
+    /// Text before the cursor, used for completions
+    pub cursor_prefix: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum TextAreaMessages {
-    InputChange(String),
+    InputUpdate(TextAreaUpdate),
     SetInput(String),
 }
 
@@ -178,6 +186,27 @@ impl Component for TextArea {
         true
     }
 
+    fn message(
+        &mut self,
+        message: Self::Message,
+        state: &mut Self::State,
+        _: Elements<'_, '_>,
+        _: Context<'_, Self::State>,
+    ) {
+        #[allow(clippy::single_match)]
+        match serde_json::from_str::<TextAreaMessages>(&message) {
+            Ok(TextAreaMessages::SetInput(input)) => {
+                state.input = input;
+                state.cursor_pos = CursorPosition::default();
+
+                render_display(state);
+                update_cursor_after_move(state);
+            }
+
+            _ => {}
+        }
+    }
+
     fn on_blur(
         &mut self,
         state: &mut Self::State,
@@ -0,0 +1,102 @@
+use std::{collections::HashMap, env};
+
+use chrono::{SecondsFormat, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use uuid::Uuid;
+
+fn random_string(length: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+/// Evaluates a dynamic variable like `$uuid` or `$env.HOME`, returns None when
+/// the name is not a dynamic variable or has no value
+pub fn get_dynamic_variable(name: &str) -> Option<String> {
+    let name = name.strip_prefix('$')?;
+
+    if let Some(env_name) = name.strip_prefix("env.") {
+        return env::var(env_name).ok();
+    }
+
+    match name {
+        "uuid" | "guid" => Some(Uuid::new_v4().to_string()),
+        "timestamp" => Some(Utc::now().timestamp().to_string()),
+        "isoTimestamp" => Some(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+        "randomInt" => Some(rand::thread_rng().gen_range(0..=1000).to_string()),
+        "randomString" => Some(random_string(16)),
+        "randomEmail" => Some(format!("{}@example.com", random_string(12).to_lowercase())),
+        _ => None,
+    }
+}
+
+/// Evaluates the dynamic variables used in the inputs and adds them to the
+/// variables, each one is evaluated once so it has the same value everywhere
+/// in a request
+pub fn add_dynamic_variables(inputs: &[&str], variables: &mut HashMap<String, String>) {
+    for input in inputs {
+        let mut rest = *input;
+
+        while let Some(start) = rest.find("{{$") {
+            rest = &rest[start + 2..];
+
+            let Some(end) = rest.find("}}") else {
+                break;
+            };
+
+            // Cache the value used by `{{$uuid:-default}}` and `{{$base64.$uuid}}` too
+            let name = rest[..end].split(":-").next().unwrap_or_default().trim();
+            let name = name.strip_prefix("$base64.").unwrap_or(name);
+            rest = &rest[end + 2..];
+
+            if variables.contains_key(name) {
+                continue;
+            }
+
+            if let Some(value) = get_dynamic_variable(name) {
+                variables.insert(name.to_string(), value);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_get_dynamic_variable() {
+    let uuid = get_dynamic_variable("$uuid").unwrap();
+    assert!(Uuid::parse_str(&uuid).is_ok());
+
+    let timestamp = get_dynamic_variable("$timestamp").unwrap();
+    assert!(timestamp.parse::<i64>().is_ok());
+
+    let random_int = get_dynamic_variable("$randomInt").unwrap();
+    assert!(random_int.parse::<u32>().unwrap() <= 1000);
+
+    let email = get_dynamic_variable("$randomEmail").unwrap();
+    assert!(email.ends_with("@example.com"));
+
+    let path = get_dynamic_variable("$env.PATH");
+    assert_eq!(path, env::var("PATH").ok());
+
+    assert_eq!(get_dynamic_variable("$unknown"), None);
+    assert_eq!(get_dynamic_variable("user"), None);
+}
+
+#[test]
+fn test_add_dynamic_variables() {
+    let mut variables = HashMap::<String, String>::new();
+    variables.insert("$timestamp".to_string(), "1700000000".to_string());
+
+    add_dynamic_variables(
+        &[
+            "/users/{{$uuid}}?at={{$timestamp}}",
+            "{\"id\": \"{{$uuid}}\", \"name\": \"{{name}}\"}",
+        ],
+        &mut variables,
+    );
+
+    assert_eq!(variables.len(), 2);
+    assert!(variables.contains_key("$uuid"));
+    assert_eq!(variables.get("$timestamp"), Some(&"1700000000".to_string()));
+}
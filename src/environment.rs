@@ -0,0 +1,223 @@
+use std::{collections::HashMap, env, fs, sync::OnceLock};
+
+use anyhow::{anyhow, bail};
+
+/// Variables set with `--var key=value`, they override every other variable
+static CLI_VARIABLES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+pub fn set_cli_variables(variables: HashMap<String, String>) {
+    let _ = CLI_VARIABLES.set(variables);
+}
+
+pub fn get_cli_variables() -> HashMap<String, String> {
+    CLI_VARIABLES.get().cloned().unwrap_or_default()
+}
+
+/// Parses a `--var key=value` argument
+pub fn parse_cli_variable(argument: &str) -> Result<(String, String), String> {
+    match argument.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => {
+            Ok((key.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("{argument} is not a key=value pair")),
+    }
+}
+
+/// Only process environment variables with this prefix are request variables,
+/// other ones are read with `{{$env.NAME}}`
+const VARIABLE_PREFIX: &str = "TOME_VAR_";
+
+/// The process environment variables that start with the variable prefix,
+/// named without it. Variables with names or values that are not valid
+/// unicode are skipped.
+pub fn get_environment_variables() -> HashMap<String, String> {
+    get_prefixed_variables(
+        env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?))),
+    )
+}
+
+fn get_prefixed_variables(
+    variables: impl Iterator<Item = (String, String)>,
+) -> HashMap<String, String> {
+    variables
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(VARIABLE_PREFIX)?;
+
+            Some((name.to_string(), value)).filter(|(name, _)| !name.is_empty())
+        })
+        .collect()
+}
+
+/// Loads the `.env` files in order, keys in later files override earlier ones
+pub fn load_dotenv_files(paths: &[String]) -> anyhow::Result<HashMap<String, String>> {
+    let mut variables = HashMap::new();
+
+    for path in paths {
+        let contents = fs::read_to_string(path)
+            .map_err(|error| anyhow!("Could not read the .env file at {path}: {error}"))?;
+        let dotenv = parse_dotenv(&contents)
+            .map_err(|error| anyhow!("Could not parse the .env file at {path}: {error}"))?;
+
+        variables.extend(dotenv);
+    }
+
+    Ok(variables)
+}
+
+/// Parses the `KEY=value` lines of a `.env` file. Lines can start with
+/// `export`, `#` starts a comment, single quoted values are literal and
+/// double quoted values support escapes and can span multiple lines.
+pub fn parse_dotenv(contents: &str) -> anyhow::Result<HashMap<String, String>> {
+    let mut variables = HashMap::new();
+    let mut lines = contents.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("Line {} is not a KEY=value pair", index + 1);
+        };
+
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            bail!("Line {} has an invalid key", index + 1);
+        }
+
+        let value = value.trim_start();
+        let value = match value.chars().next() {
+            Some('"') => {
+                let mut quoted = value[1..].to_string();
+                while find_closing_quote(&quoted).is_none() {
+                    let Some((_, next_line)) = lines.next() else {
+                        bail!("The value on line {} is never closed", index + 1);
+                    };
+
+                    quoted.push('\n');
+                    quoted.push_str(next_line);
+                }
+
+                let end = find_closing_quote(&quoted).unwrap_or(quoted.len());
+                unescape(&quoted[..end])
+            }
+
+            Some('\'') => match value[1..].find('\'') {
+                Some(end) => value[1..=end].to_string(),
+                None => bail!("The value on line {} is never closed", index + 1),
+            },
+
+            _ => match value.find(" #") {
+                Some(comment) => value[..comment].trim_end().to_string(),
+                None => value.trim_end().to_string(),
+            },
+        };
+
+        variables.insert(key.to_string(), value);
+    }
+
+    Ok(variables)
+}
+
+/// The index of the first `"` that is not escaped
+fn find_closing_quote(value: &str) -> Option<usize> {
+    let mut escaped = false;
+
+    for (index, char) in value.char_indices() {
+        match char {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return Some(index),
+            _ => escaped = false,
+        }
+    }
+
+    None
+}
+
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some(char) => unescaped.push(char),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+#[test]
+fn test_parse_dotenv() {
+    let contents = r#"
+# Local secrets
+API_KEY=abc123
+export TOKEN = xyz # the dev token
+EMPTY=
+SINGLE='literal \n #value'
+DOUBLE="line one\nline \"two\""
+MULTI="first
+second"
+URL=https://example.com/#anchor
+"#;
+
+    let variables = parse_dotenv(contents).unwrap();
+
+    assert_eq!(variables.len(), 7);
+    assert_eq!(variables["API_KEY"], "abc123");
+    assert_eq!(variables["TOKEN"], "xyz");
+    assert_eq!(variables["EMPTY"], "");
+    assert_eq!(variables["SINGLE"], "literal \\n #value");
+    assert_eq!(variables["DOUBLE"], "line one\nline \"two\"");
+    assert_eq!(variables["MULTI"], "first\nsecond");
+    assert_eq!(variables["URL"], "https://example.com/#anchor");
+
+    assert!(parse_dotenv("NOT A PAIR").is_err());
+    assert!(parse_dotenv("KEY=\"never closed").is_err());
+}
+
+#[test]
+fn test_parse_cli_variable() {
+    assert_eq!(
+        parse_cli_variable("baseUrl=http://localhost:8080?a=b"),
+        Ok((
+            "baseUrl".to_string(),
+            "http://localhost:8080?a=b".to_string()
+        ))
+    );
+    assert_eq!(
+        parse_cli_variable("empty="),
+        Ok(("empty".to_string(), "".to_string()))
+    );
+    assert!(parse_cli_variable("novalue").is_err());
+    assert!(parse_cli_variable("=value").is_err());
+}
+
+#[test]
+fn test_get_prefixed_variables() {
+    let variables = get_prefixed_variables(
+        [
+            ("TOME_VAR_baseUrl", "http://localhost"),
+            ("TOME_VAR_", "no name"),
+            ("HOME", "/home/user"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string())),
+    );
+
+    assert_eq!(variables.len(), 1);
+    assert_eq!(variables["baseUrl"], "http://localhost");
+}
@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+
+/// How the text of the response filter is matched
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FilterOptions {
+    pub regex: bool,
+    pub ignore_case: bool,
+    pub whole_word: bool,
+}
+
+impl FilterOptions {
+    /// Literal filters are escaped so every mode is matched with a regex
+    pub fn get_regex(&self, filter: &str) -> Result<Regex, regex::Error> {
+        let pattern = match self.regex {
+            true => filter.to_string(),
+            false => regex::escape(filter),
+        };
+
+        let pattern = match self.whole_word {
+            true => format!(r"\b(?:{pattern})\b"),
+            false => pattern,
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(self.ignore_case)
+            .build()
+    }
+
+    /// The enabled modes, e.g. `Regex, Ignore Case`
+    pub fn get_label(&self) -> String {
+        let modes = [
+            (self.regex, "Regex"),
+            (self.ignore_case, "Ignore Case"),
+            (self.whole_word, "Whole Word"),
+        ];
+
+        let label = modes
+            .iter()
+            .filter(|(enabled, _)| *enabled)
+            .map(|(_, mode)| *mode)
+            .collect::<Vec<&str>>()
+            .join(", ");
+
+        match label.is_empty() {
+            true => String::from("Literal"),
+            false => label,
+        }
+    }
+}
+
+/// The character ranges of the matches in the line, empty matches are skipped
+/// so patterns like `a*` don't match every line
+pub fn get_match_ranges(filter_regex: &Regex, line: &str) -> Vec<Range<usize>> {
+    filter_regex
+        .find_iter(line)
+        .filter(|found| !found.is_empty())
+        .map(|found| {
+            let start = line[..found.start()].chars().count();
+            let end = start + found.as_str().chars().count();
+
+            start..end
+        })
+        .collect()
+}
+
+/// The last line of a regex error, without the pattern and the caret under it
+pub fn get_regex_error(error: &regex::Error) -> String {
+    let message = error.to_string();
+    let reason = message.lines().last().unwrap_or_default();
+
+    format!("Invalid regex: {}", reason.trim_start_matches("error: "))
+}
+
+#[test]
+fn test_filter_options_regex() {
+    let line = "Total: 42, total_count: 7";
+    let matches = |options: FilterOptions, filter: &str| {
+        get_match_ranges(&options.get_regex(filter).unwrap(), line)
+    };
+
+    assert_eq!(matches(FilterOptions::default(), "total"), vec![11..16]);
+    assert!(matches(FilterOptions::default(), ".").is_empty());
+
+    let ignore_case = FilterOptions {
+        ignore_case: true,
+        ..Default::default()
+    };
+    assert_eq!(matches(ignore_case, "total"), vec![0..5, 11..16]);
+
+    let whole_word = FilterOptions {
+        ignore_case: true,
+        whole_word: true,
+        ..Default::default()
+    };
+    assert_eq!(matches(whole_word, "total"), vec![0..5]);
+
+    let regex = FilterOptions {
+        regex: true,
+        ..Default::default()
+    };
+    assert_eq!(matches(regex, r"\d+"), vec![7..9, 24..25]);
+    assert!(matches(regex, "x*").is_empty());
+}
+
+#[test]
+fn test_filter_options_label() {
+    assert_eq!(FilterOptions::default().get_label(), "Literal");
+
+    let options = FilterOptions {
+        regex: true,
+        whole_word: true,
+        ..Default::default()
+    };
+    assert_eq!(options.get_label(), "Regex, Whole Word");
+}
+
+#[test]
+fn test_get_regex_error() {
+    let options = FilterOptions {
+        regex: true,
+        ..Default::default()
+    };
+    let error = options.get_regex("(total").unwrap_err();
+
+    assert_eq!(get_regex_error(&error), "Invalid regex: unclosed group");
+}
+
+#[test]
+fn test_get_match_ranges_chars() {
+    let options = FilterOptions::default();
+    let filter_regex = options.get_regex("ok").unwrap();
+
+    assert_eq!(get_match_ranges(&filter_regex, "\"é\": \"ok\""), vec![6..8]);
+}
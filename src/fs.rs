@@ -7,7 +7,10 @@ use std::{
 use anathema::prelude::Context;
 use directories::{ProjectDirs, UserDirs};
 
-use crate::components::dashboard::{DashboardComponent, DashboardState};
+use crate::{
+    binary::{get_file_extension, ResponseEncoding},
+    components::dashboard::{DashboardComponent, DashboardState},
+};
 
 pub fn get_project_directory<'a>(app: &'a str, path: &'a str) -> anyhow::Result<PathBuf> {
     let requested_path = ProjectDirs::from("com", "s9tpepper", app)
@@ -25,6 +28,20 @@ pub fn get_app_dir(path: &str) -> anyhow::Result<PathBuf> {
     get_project_directory("Tome", path)
 }
 
+/// A folder for files that can be deleted at any time, like the bodies of
+/// the responses
+pub fn get_cache_dir(path: &str) -> anyhow::Result<PathBuf> {
+    let requested_path = ProjectDirs::from("com", "s9tpepper", "Tome")
+        .map(|project_dirs| project_dirs.cache_dir().join(path));
+
+    let path = requested_path.ok_or(anyhow::Error::msg("Could not build requested path"))?;
+    if !path.exists() {
+        fs::create_dir_all(&path)?;
+    }
+
+    Ok(path)
+}
+
 pub fn get_documents_dir() -> anyhow::Result<PathBuf> {
     let user_dirs = UserDirs::new();
     let dirs = user_dirs.ok_or(Err(anyhow::Error::msg("Could not get user directories")));
@@ -42,12 +59,32 @@ pub fn get_documents_dir() -> anyhow::Result<PathBuf> {
     }
 }
 
+/// Saves the response as it arrived, with the file extension of its content
+/// type
 pub fn save_response(dashboard: &DashboardComponent, state: &mut DashboardState) {
     let dir = get_documents_dir();
 
     match dir {
         Ok(mut docs_dir) => {
-            let response = state.response.to_ref().to_string();
+            let Some(response_files) = &dashboard.response_files else {
+                dashboard.show_error("Send a request before saving its response", state);
+                return;
+            };
+
+            let content_type = get_response_content_type(state);
+            let encoding =
+                match ResponseEncoding::detect_file(content_type.as_deref(), &response_files.raw) {
+                    Ok(encoding) => encoding,
+                    Err(error) => {
+                        dashboard
+                            .show_error(&format!("Could not read the response: {error}"), state);
+                        return;
+                    }
+                };
+            let extension = get_file_extension(
+                content_type.as_deref(),
+                encoding == ResponseEncoding::Binary,
+            );
 
             let endpoint_name = state.endpoint.to_ref().name.to_ref().to_string();
             let endpoint_name = endpoint_name.replace("/", "_");
@@ -56,7 +93,7 @@ pub fn save_response(dashboard: &DashboardComponent, state: &mut DashboardState)
             let duration = timestamp
                 .duration_since(UNIX_EPOCH)
                 .unwrap_or(Duration::from_secs(1));
-            let name = format!("{endpoint_name}_{}.txt", duration.as_secs());
+            let name = format!("{endpoint_name}_{}.{extension}", duration.as_secs());
 
             docs_dir.push(state.project.to_ref().name.to_ref().to_string());
             let _ = fs::create_dir_all(&docs_dir);
@@ -76,7 +113,7 @@ pub fn save_response(dashboard: &DashboardComponent, state: &mut DashboardState)
 
                     let save_path = docs_dir.clone();
 
-                    match fs::write(docs_dir, response) {
+                    match fs::copy(&response_files.raw, docs_dir) {
                         Ok(_) => {
                             dashboard.show_message(
                                 "Response Saved",
@@ -96,3 +133,13 @@ pub fn save_response(dashboard: &DashboardComponent, state: &mut DashboardState)
         Err(error) => dashboard.show_error(&error.to_string(), state),
     }
 }
+
+fn get_response_content_type(state: &DashboardState) -> Option<String> {
+    let headers = state.response_headers.to_ref();
+
+    headers
+        .iter()
+        .map(|header| header.to_ref())
+        .find(|header| header.name.to_ref().eq_ignore_ascii_case("content-type"))
+        .map(|header| header.value.to_ref().to_string())
+}
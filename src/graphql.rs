@@ -0,0 +1,608 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::fs::get_app_dir;
+
+pub const INTROSPECTION_QUERY: &str = r#"query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      name
+      fields(includeDeprecated: true) {
+        name
+        args {
+          name
+          type { ...TypeRef }
+        }
+        type { ...TypeRef }
+      }
+    }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+        }
+      }
+    }
+  }
+}"#;
+
+const OPERATION_KEYWORDS: [&str; 4] = ["query", "mutation", "subscription", "fragment"];
+
+/// The parts of an introspected schema needed for query completion
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphQLSchema {
+    pub query_type: Option<String>,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    pub types: HashMap<String, Vec<GraphQLField>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphQLField {
+    pub name: String,
+    /// Display type, like `[User!]!`
+    pub r#type: String,
+    /// Named type used to look up the fields of a selection set
+    pub type_name: String,
+    pub args: Vec<GraphQLArgument>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphQLArgument {
+    pub name: String,
+    pub r#type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionResponse {
+    data: Option<IntrospectionData>,
+    errors: Option<Vec<IntrospectionError>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionData {
+    #[serde(rename = "__schema")]
+    schema: IntrospectionSchema,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IntrospectionSchema {
+    query_type: Option<NamedType>,
+    mutation_type: Option<NamedType>,
+    subscription_type: Option<NamedType>,
+    types: Vec<IntrospectionType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedType {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionType {
+    name: Option<String>,
+    fields: Option<Vec<IntrospectionField>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionField {
+    name: String,
+    #[serde(default)]
+    args: Vec<IntrospectionArgument>,
+    r#type: TypeRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntrospectionArgument {
+    name: String,
+    r#type: TypeRef,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypeRef {
+    kind: String,
+    name: Option<String>,
+    of_type: Option<Box<TypeRef>>,
+}
+
+impl TypeRef {
+    fn named_type(&self) -> String {
+        match (&self.name, &self.of_type) {
+            (Some(name), _) => name.clone(),
+            (None, Some(of_type)) => of_type.named_type(),
+            (None, None) => String::new(),
+        }
+    }
+
+    fn display(&self) -> String {
+        let inner = self
+            .of_type
+            .as_ref()
+            .map(|of_type| of_type.display())
+            .unwrap_or_default();
+
+        match self.kind.as_str() {
+            "NON_NULL" => format!("{inner}!"),
+            "LIST" => format!("[{inner}]"),
+            _ => self.name.clone().unwrap_or_default(),
+        }
+    }
+}
+
+pub fn is_graphql_mode(body_mode: &str) -> bool {
+    body_mode.eq_ignore_ascii_case("graphql")
+}
+
+/// Wraps the query and the JSON variables into the `{"query", "variables"}`
+/// envelope GraphQL servers expect
+pub fn build_graphql_body(query: &str, variables: &str) -> anyhow::Result<String> {
+    // NOTE: Older endpoints and Postman imports stored the whole envelope as
+    // the body, those are sent as they are
+    if let Ok(Value::Object(envelope)) = serde_json::from_str::<Value>(query) {
+        if envelope.contains_key("query") {
+            return Ok(query.to_string());
+        }
+    }
+
+    let mut envelope = Map::new();
+    envelope.insert("query".to_string(), Value::String(query.to_string()));
+
+    if !variables.trim().is_empty() {
+        let variables: Value = serde_json::from_str(variables)
+            .map_err(|error| anyhow!("GraphQL variables are not valid JSON: {error}"))?;
+
+        if !variables.is_object() && !variables.is_null() {
+            bail!("GraphQL variables must be a JSON object");
+        }
+
+        envelope.insert("variables".to_string(), variables);
+    }
+
+    Ok(serde_json::to_string(&Value::Object(envelope))?)
+}
+
+/// Parses the response of the introspection query
+pub fn parse_introspection(response: &str) -> anyhow::Result<GraphQLSchema> {
+    let response: IntrospectionResponse = serde_json::from_str(response)
+        .map_err(|error| anyhow!("Introspection response is not valid JSON: {error}"))?;
+
+    let Some(data) = response.data else {
+        let message = response
+            .errors
+            .and_then(|errors| errors.into_iter().next())
+            .map(|error| error.message)
+            .unwrap_or("Introspection response has no schema".to_string());
+
+        bail!("Schema introspection failed: {message}");
+    };
+
+    let schema = data.schema;
+    let types = schema
+        .types
+        .into_iter()
+        .filter_map(|introspection_type| {
+            let name = introspection_type.name?;
+            let fields = introspection_type
+                .fields?
+                .into_iter()
+                .map(|field| GraphQLField {
+                    name: field.name,
+                    r#type: field.r#type.display(),
+                    type_name: field.r#type.named_type(),
+                    args: field
+                        .args
+                        .into_iter()
+                        .map(|arg| GraphQLArgument {
+                            name: arg.name,
+                            r#type: arg.r#type.display(),
+                        })
+                        .collect(),
+                })
+                .collect();
+
+            Some((name, fields))
+        })
+        .collect();
+
+    Ok(GraphQLSchema {
+        query_type: schema.query_type.and_then(|named| named.name),
+        mutation_type: schema.mutation_type.and_then(|named| named.name),
+        subscription_type: schema.subscription_type.and_then(|named| named.name),
+        types,
+    })
+}
+
+/// Caches the introspection response for an endpoint url
+pub fn save_schema(url: &str, response: &str) -> anyhow::Result<PathBuf> {
+    let mut path = get_app_dir("graphql_schemas")?;
+    path.push(get_schema_file_name(url));
+
+    fs::write(&path, response)?;
+
+    Ok(path)
+}
+
+/// Loads the cached schema for an endpoint url
+pub fn load_schema(url: &str) -> anyhow::Result<GraphQLSchema> {
+    let mut path = get_app_dir("graphql_schemas")?;
+    path.push(get_schema_file_name(url));
+
+    let response = fs::read_to_string(&path)
+        .map_err(|_| anyhow!("No cached GraphQL schema for {url}, introspect it first"))?;
+
+    parse_introspection(&response)
+}
+
+fn get_schema_file_name(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let without_query = without_scheme.split(['?', '#']).next().unwrap_or_default();
+
+    let name: String = without_query
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+
+    format!("{name}.json")
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Name(&'a str),
+    Punctuation(char),
+    Value,
+}
+
+fn tokenize(query: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut chars = query.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        match c {
+            '#' => while chars.next_if(|(_, c)| *c != '\n').is_some() {},
+
+            '"' => {
+                let mut escaped = false;
+                for (_, c) in chars.by_ref() {
+                    match c {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => break,
+                        _ => escaped = false,
+                    }
+                }
+
+                tokens.push(Token::Value);
+            }
+
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = start + c.len_utf8();
+                while let Some((index, c)) =
+                    chars.next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+                {
+                    end = index + c.len_utf8();
+                }
+
+                tokens.push(Token::Name(&query[start..end]));
+            }
+
+            c if c.is_ascii_digit() || c == '-' => {
+                while chars
+                    .next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '.')
+                    .is_some()
+                {}
+
+                tokens.push(Token::Value);
+            }
+
+            c if c.is_whitespace() || c == ',' => {
+                if c == ',' {
+                    tokens.push(Token::Punctuation(','));
+                }
+            }
+
+            c => tokens.push(Token::Punctuation(c)),
+        }
+    }
+
+    tokens
+}
+
+/// Suggests fields, or arguments inside parentheses, for the text before the
+/// cursor, formatted as `name: Type`
+pub fn complete(schema: &GraphQLSchema, before_cursor: &str) -> Vec<String> {
+    let partial_start = before_cursor
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+        .last()
+        .map_or(before_cursor.len(), |(index, _)| index);
+
+    let (text, partial) = before_cursor.split_at(partial_start);
+    let partial = partial.to_lowercase();
+
+    // NOTE: A variable name like $id or a directive like @skip is not a field
+    if text.ends_with('$') || text.ends_with('@') {
+        return vec![];
+    }
+
+    let mut selection_types: Vec<Option<String>> = vec![];
+    let mut root_type: Option<String> = None;
+    let mut last_field: Option<String> = None;
+    let mut type_condition: Option<String> = None;
+    let mut arguments_for: Option<GraphQLField> = None;
+    let mut parens_depth = 0;
+    let mut previous: Option<&Token> = None;
+
+    let tokens = tokenize(text);
+    for token in &tokens {
+        let current_type = selection_types.last().cloned().flatten();
+
+        match token {
+            // NOTE: Names inside parentheses are arguments, variables or enum
+            // values, never fields
+            Token::Name(_) if parens_depth > 0 => {}
+
+            Token::Name(name) if matches!(previous, Some(Token::Name("on"))) => {
+                type_condition = Some(name.to_string());
+            }
+
+            Token::Name(name) if selection_types.is_empty() => match *name {
+                "query" => root_type = schema.query_type.clone(),
+                "mutation" => root_type = schema.mutation_type.clone(),
+                "subscription" => root_type = schema.subscription_type.clone(),
+                _ => {}
+            },
+
+            Token::Name(name) if *name != "on" => {
+                last_field = Some(name.to_string());
+            }
+
+            Token::Punctuation('{') => {
+                let selection_type = match (type_condition.take(), selection_types.is_empty()) {
+                    (Some(type_condition), _) => Some(type_condition),
+                    (None, true) => root_type.take().or(schema.query_type.clone()),
+                    (None, false) => last_field
+                        .as_deref()
+                        .and_then(|field| find_field(schema, current_type.as_deref(), field))
+                        .map(|field| field.type_name.clone()),
+                };
+
+                selection_types.push(selection_type);
+                last_field = None;
+            }
+
+            Token::Punctuation('}') => {
+                selection_types.pop();
+                last_field = None;
+            }
+
+            Token::Punctuation('(') => {
+                parens_depth += 1;
+
+                if parens_depth == 1 && !selection_types.is_empty() {
+                    arguments_for = last_field
+                        .as_deref()
+                        .and_then(|field| find_field(schema, current_type.as_deref(), field))
+                        .cloned();
+                }
+            }
+
+            Token::Punctuation(')') => {
+                parens_depth -= 1;
+
+                if parens_depth <= 0 {
+                    parens_depth = 0;
+                    arguments_for = None;
+                }
+            }
+
+            _ => {}
+        }
+
+        previous = Some(token);
+    }
+
+    if parens_depth > 0 {
+        // NOTE: Only suggest argument names, not argument values
+        if matches!(previous, Some(Token::Punctuation(':'))) {
+            return vec![];
+        }
+
+        return arguments_for
+            .map(|field| {
+                field
+                    .args
+                    .iter()
+                    .filter(|arg| arg.name.to_lowercase().starts_with(&partial))
+                    .map(|arg| format!("{}: {}", arg.name, arg.r#type))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    match selection_types.last() {
+        Some(Some(type_name)) => schema
+            .types
+            .get(type_name)
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter(|field| field.name.to_lowercase().starts_with(&partial))
+                    .map(|field| format!("{}: {}", field.name, field.r#type))
+                    .collect()
+            })
+            .unwrap_or_default(),
+
+        Some(None) => vec![],
+
+        None => OPERATION_KEYWORDS
+            .iter()
+            .filter(|keyword| !partial.is_empty() && keyword.starts_with(&partial))
+            .map(|keyword| keyword.to_string())
+            .collect(),
+    }
+}
+
+fn find_field<'a>(
+    schema: &'a GraphQLSchema,
+    type_name: Option<&str>,
+    field_name: &str,
+) -> Option<&'a GraphQLField> {
+    schema
+        .types
+        .get(type_name?)?
+        .iter()
+        .find(|field| field.name == field_name)
+}
+
+#[cfg(test)]
+fn get_test_schema() -> GraphQLSchema {
+    let response = r#"{"data":{"__schema":{
+        "queryType":{"name":"Query"},
+        "mutationType":{"name":"Mutation"},
+        "subscriptionType":null,
+        "types":[
+            {"name":"Query","fields":[
+                {"name":"user","args":[
+                    {"name":"id","type":{"kind":"NON_NULL","name":null,"ofType":{"kind":"SCALAR","name":"ID","ofType":null}}}
+                ],"type":{"kind":"OBJECT","name":"User","ofType":null}},
+                {"name":"users","args":[
+                    {"name":"first","type":{"kind":"SCALAR","name":"Int","ofType":null}},
+                    {"name":"filter","type":{"kind":"INPUT_OBJECT","name":"UserFilter","ofType":null}}
+                ],"type":{"kind":"NON_NULL","name":null,"ofType":{"kind":"LIST","name":null,"ofType":{"kind":"NON_NULL","name":null,"ofType":{"kind":"OBJECT","name":"User","ofType":null}}}}}
+            ]},
+            {"name":"Mutation","fields":[
+                {"name":"deleteUser","args":[],"type":{"kind":"SCALAR","name":"Boolean","ofType":null}}
+            ]},
+            {"name":"User","fields":[
+                {"name":"id","args":[],"type":{"kind":"NON_NULL","name":null,"ofType":{"kind":"SCALAR","name":"ID","ofType":null}}},
+                {"name":"name","args":[],"type":{"kind":"SCALAR","name":"String","ofType":null}},
+                {"name":"friends","args":[],"type":{"kind":"LIST","name":null,"ofType":{"kind":"OBJECT","name":"User","ofType":null}}}
+            ]},
+            {"name":"ID","fields":null}
+        ]
+    }}}"#;
+
+    parse_introspection(response).unwrap()
+}
+
+#[test]
+fn test_build_graphql_body() {
+    let body = build_graphql_body("query { user { id } }", "").unwrap();
+    assert_eq!(body, r#"{"query":"query { user { id } }"}"#);
+
+    let body = build_graphql_body("query($id: ID!) { user(id: $id) { id } }", r#"{"id": 1}"#);
+    assert_eq!(
+        body.unwrap(),
+        r#"{"query":"query($id: ID!) { user(id: $id) { id } }","variables":{"id":1}}"#
+    );
+
+    let envelope = r#"{"query":"{ users { id } }","variables":{}}"#;
+    assert_eq!(build_graphql_body(envelope, "").unwrap(), envelope);
+
+    assert!(build_graphql_body("{ users { id } }", "{ broken").is_err());
+    assert!(build_graphql_body("{ users { id } }", "[1, 2]").is_err());
+}
+
+#[test]
+fn test_parse_introspection() {
+    let schema = get_test_schema();
+
+    assert_eq!(schema.query_type, Some("Query".to_string()));
+    assert_eq!(schema.subscription_type, None);
+    assert!(!schema.types.contains_key("ID"));
+
+    let users = find_field(&schema, Some("Query"), "users").unwrap();
+    assert_eq!(users.r#type, "[User!]!");
+    assert_eq!(users.type_name, "User");
+    assert_eq!(users.args[0].name, "first");
+
+    let error = parse_introspection(r#"{"errors":[{"message":"Introspection is disabled"}]}"#);
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "Schema introspection failed: Introspection is disabled"
+    );
+}
+
+#[test]
+fn test_complete_fields() {
+    let schema = get_test_schema();
+
+    assert_eq!(
+        complete(&schema, "query { us"),
+        vec!["user: User", "users: [User!]!"]
+    );
+    assert_eq!(
+        complete(&schema, "{ users { id friends { na"),
+        vec!["name: String"]
+    );
+    assert_eq!(
+        complete(&schema, "{ user(id: \"1\") { friends { id } n"),
+        vec!["name: String"]
+    );
+    assert_eq!(
+        complete(&schema, "mutation { del"),
+        vec!["deleteUser: Boolean"]
+    );
+    assert_eq!(complete(&schema, "{ me: user(id: 1) { i"), vec!["id: ID!"]);
+    assert_eq!(complete(&schema, "mut"), vec!["mutation"]);
+    assert!(complete(&schema, "{ unknown { i").is_empty());
+}
+
+#[test]
+fn test_complete_arguments() {
+    let schema = get_test_schema();
+
+    assert_eq!(
+        complete(&schema, "{ users("),
+        vec!["first: Int", "filter: UserFilter"]
+    );
+    assert_eq!(
+        complete(&schema, "{ users(first: 10, fi"),
+        vec!["first: Int", "filter: UserFilter"]
+    );
+    assert!(complete(&schema, "{ users(first: ").is_empty());
+    assert!(complete(&schema, "query($id: ID!) { user(id: $").is_empty());
+}
+
+#[test]
+fn test_get_schema_file_name() {
+    assert_eq!(
+        get_schema_file_name("https://api.example.com/graphql?debug=1"),
+        "api_example_com_graphql.json"
+    );
+    assert_eq!(
+        get_schema_file_name("{{baseUrl}}/graphql/"),
+        "__baseUrl___graphql.json"
+    );
+}
@@ -0,0 +1,245 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{fs::get_app_dir, requests::metrics::ResponseMetrics};
+
+/// The oldest entries are dropped once a project has this many
+const MAX_HISTORY_ENTRIES: usize = 200;
+
+/// Larger responses are not kept with their history entry
+const MAX_SAVED_RESPONSE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// A request that was sent from the dashboard
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    /// RFC 3339 time the response arrived
+    pub timestamp: String,
+    pub endpoint: String,
+    pub method: String,
+
+    /// The url as written in the endpoint, the values of its variables are
+    /// not saved
+    pub url: String,
+    pub status: u16,
+    pub metrics: ResponseMetrics,
+
+    /// File name of the saved response body, used to compare responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_file: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn new(
+        endpoint: &str,
+        method: &str,
+        url: &str,
+        status: u16,
+        metrics: ResponseMetrics,
+    ) -> Self {
+        HistoryEntry {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            endpoint: endpoint.to_string(),
+            method: method.to_string(),
+            url: url.to_string(),
+            status,
+            metrics,
+            response_file: None,
+        }
+    }
+}
+
+/// Adds an entry to the request history of a project with a copy of the
+/// response body, the bodies of the entries that are dropped are deleted.
+/// Without a body only the entry is saved.
+pub fn add_history_entry(
+    project_name: &str,
+    mut entry: HistoryEntry,
+    body: Option<&Path>,
+) -> anyhow::Result<()> {
+    let saved_body = match body {
+        Some(body) if fs::metadata(body)?.len() <= MAX_SAVED_RESPONSE_BYTES => Some(body),
+        _ => None,
+    };
+
+    if let Some(body) = saved_body {
+        let file_name = format!("{}.txt", uuid::Uuid::new_v4());
+        let mut response_path = get_responses_dir(project_name)?;
+        response_path.push(&file_name);
+
+        fs::copy(body, response_path)?;
+        entry.response_file = Some(file_name);
+    }
+
+    let mut entries = load_history(project_name).unwrap_or_default();
+    let dropped = push_entry(&mut entries, entry);
+
+    let path = get_history_path(project_name)?;
+    fs::write(path, serde_json::to_string(&entries)?)?;
+
+    for dropped_entry in dropped {
+        if let Some(response_path) = get_history_response_path(project_name, &dropped_entry) {
+            let _ = fs::remove_file(response_path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Where the response body of an entry was saved, None when it was too
+/// large to keep or the entry is older than saved responses
+pub fn get_history_response_path(project_name: &str, entry: &HistoryEntry) -> Option<PathBuf> {
+    let file_name = entry.response_file.as_ref()?;
+    let mut path = get_responses_dir(project_name).ok()?;
+    path.push(file_name);
+
+    Some(path)
+}
+
+/// The request history of a project, oldest first
+pub fn load_history(project_name: &str) -> anyhow::Result<Vec<HistoryEntry>> {
+    let path = get_history_path(project_name)?;
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+
+    let history = fs::read_to_string(path)?;
+
+    Ok(serde_json::from_str(&history)?)
+}
+
+/// The saved responses the last response of an endpoint can be compared with,
+/// newest first. The earlier responses of the endpoint come first, then the
+/// last response of every other endpoint.
+pub fn get_compared_entries(entries: &[HistoryEntry], endpoint: &str) -> Vec<HistoryEntry> {
+    let saved: Vec<&HistoryEntry> = entries
+        .iter()
+        .rev()
+        .filter(|entry| entry.response_file.is_some())
+        .collect();
+
+    // NOTE: The newest entry of the endpoint is the response being compared
+    let mut compared: Vec<HistoryEntry> = saved
+        .iter()
+        .filter(|entry| entry.endpoint == endpoint)
+        .skip(1)
+        .map(|entry| (*entry).clone())
+        .collect();
+
+    let mut other_endpoints: Vec<&str> = vec![];
+    for entry in saved {
+        if entry.endpoint != endpoint && !other_endpoints.contains(&entry.endpoint.as_str()) {
+            other_endpoints.push(&entry.endpoint);
+            compared.push(entry.clone());
+        }
+    }
+
+    compared
+}
+
+/// Adds the entry and returns the oldest entries that no longer fit
+fn push_entry(entries: &mut Vec<HistoryEntry>, entry: HistoryEntry) -> Vec<HistoryEntry> {
+    entries.push(entry);
+
+    let excess = entries.len().saturating_sub(MAX_HISTORY_ENTRIES);
+    entries.drain(..excess).collect()
+}
+
+fn get_history_path(project_name: &str) -> anyhow::Result<PathBuf> {
+    let mut path = get_app_dir("history")?;
+    path.push(get_history_file_name(project_name));
+
+    Ok(path)
+}
+
+/// The response bodies of each project are kept in their own folder next to
+/// the history files
+fn get_responses_dir(project_name: &str) -> anyhow::Result<PathBuf> {
+    let mut path = get_app_dir("history")?;
+    path.push(format!("{}_responses", get_safe_name(project_name)));
+    fs::create_dir_all(&path)?;
+
+    Ok(path)
+}
+
+fn get_history_file_name(project_name: &str) -> String {
+    format!("{}.json", get_safe_name(project_name))
+}
+
+/// A project or endpoint name that can be used as a file name
+pub fn get_safe_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect()
+}
+
+#[test]
+fn test_push_entry() {
+    let entry = |status| {
+        HistoryEntry::new(
+            "Get User",
+            "GET",
+            "http://localhost",
+            status,
+            ResponseMetrics::default(),
+        )
+    };
+
+    let mut entries: Vec<HistoryEntry> = (0..MAX_HISTORY_ENTRIES).map(|_| entry(200)).collect();
+    let dropped = push_entry(&mut entries, entry(404));
+
+    assert_eq!(entries.len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(entries.last().map(|entry| entry.status), Some(404));
+}
+
+#[test]
+fn test_get_history_file_name() {
+    assert_eq!(get_history_file_name("My API/v2"), "My_API_v2.json");
+}
+
+#[test]
+fn test_get_compared_entries() {
+    let entry = |endpoint: &str, status, saved: bool| {
+        let mut entry = HistoryEntry::new(
+            endpoint,
+            "GET",
+            "http://localhost",
+            status,
+            ResponseMetrics::default(),
+        );
+        entry.response_file = saved.then(|| format!("{endpoint}-{status}.txt"));
+
+        entry
+    };
+
+    let entries = vec![
+        entry("Get User", 200, true),
+        entry("List Users", 200, true),
+        entry("Get User", 404, false),
+        entry("List Users", 500, true),
+        entry("Get User", 201, true),
+        entry("Get User", 202, true),
+    ];
+
+    let compared: Vec<(String, u16)> = get_compared_entries(&entries, "Get User")
+        .into_iter()
+        .map(|entry| (entry.endpoint, entry.status))
+        .collect();
+
+    assert_eq!(
+        compared,
+        vec![
+            (String::from("Get User"), 201),
+            (String::from("Get User"), 200),
+            (String::from("List Users"), 500),
+        ]
+    );
+}
@@ -0,0 +1,1221 @@
+use std::{cmp::Ordering, fmt, ops::Range};
+
+use anyhow::bail;
+use serde_json::{Map, Value};
+
+/// A query for JSON responses, either a jq expression like
+/// `.data[] | select(.active) | .id` or a JSON path like `$.data[*].id`
+pub enum JsonQuery {
+    Jq(Expr),
+    JsonPath(Vec<PathSegment>),
+}
+
+impl JsonQuery {
+    pub fn parse(query: &str) -> anyhow::Result<Self> {
+        let query = query.trim();
+
+        match query.starts_with('$') {
+            true => Ok(JsonQuery::JsonPath(parse_json_path(query)?)),
+            false => Ok(JsonQuery::Jq(parse_jq(query)?)),
+        }
+    }
+
+    /// Every value the query outputs for the input, in order
+    pub fn run(&self, input: &Value) -> anyhow::Result<Vec<Value>> {
+        match self {
+            JsonQuery::Jq(expr) => expr.eval(input),
+            JsonQuery::JsonPath(segments) => {
+                Ok(select_nodes(segments, input).into_iter().cloned().collect())
+            }
+        }
+    }
+}
+
+/// The nodes of the input a JSON path selects, without copying them so they
+/// can be compared by reference
+pub fn select_json_path<'value>(
+    path: &str,
+    input: &'value Value,
+) -> anyhow::Result<Vec<&'value Value>> {
+    let segments = parse_json_path(path.trim())?;
+
+    Ok(select_nodes(&segments, input))
+}
+
+fn select_nodes<'value>(segments: &[PathSegment], input: &'value Value) -> Vec<&'value Value> {
+    let mut nodes = vec![input];
+    for segment in segments {
+        nodes = segment.select(nodes);
+    }
+
+    nodes
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `.`
+    Identity,
+    /// `..`, the input and everything in it
+    Recurse,
+    Literal(Value),
+    /// `.name`, `.["name"]` or `.[0]`
+    Index(Box<Expr>, Box<Expr>),
+    /// `.[1:3]`
+    Slice(Box<Expr>, Option<i64>, Option<i64>),
+    /// `.[]`
+    Iterate(Box<Expr>),
+    /// `expr?`, errors output nothing
+    Optional(Box<Expr>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Binary(Box<Expr>, BinaryOp, Box<Expr>),
+    /// `[expr]`, collects the outputs of the expression
+    Array(Option<Box<Expr>>),
+    /// `{name: expr}`
+    Object(Vec<(Expr, Expr)>),
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn eval(&self, input: &Value) -> anyhow::Result<Vec<Value>> {
+        let mut outputs = vec![];
+
+        match self {
+            Expr::Identity => outputs.push(input.clone()),
+            Expr::Recurse => push_descendants(input, &mut outputs),
+            Expr::Literal(value) => outputs.push(value.clone()),
+
+            Expr::Index(target, key) => {
+                for target in target.eval(input)? {
+                    for key in key.eval(input)? {
+                        outputs.push(index(&target, &key)?);
+                    }
+                }
+            }
+
+            Expr::Slice(target, start, end) => {
+                for target in target.eval(input)? {
+                    outputs.push(slice(&target, *start, *end)?);
+                }
+            }
+
+            Expr::Iterate(target) => {
+                for target in target.eval(input)? {
+                    outputs.extend(iterate(&target)?);
+                }
+            }
+
+            Expr::Optional(expr) => outputs = expr.eval(input).unwrap_or_default(),
+
+            Expr::Pipe(left, right) => {
+                for value in left.eval(input)? {
+                    outputs.extend(right.eval(&value)?);
+                }
+            }
+
+            Expr::Comma(left, right) => {
+                outputs.extend(left.eval(input)?);
+                outputs.extend(right.eval(input)?);
+            }
+
+            Expr::Binary(left, op, right) => {
+                for right in right.eval(input)? {
+                    for left in left.eval(input)? {
+                        outputs.push(apply_binary(&left, *op, &right));
+                    }
+                }
+            }
+
+            Expr::Array(None) => outputs.push(Value::Array(vec![])),
+            Expr::Array(Some(expr)) => outputs.push(Value::Array(expr.eval(input)?)),
+
+            Expr::Object(entries) => {
+                let mut objects = vec![Map::new()];
+                for (key, value) in entries {
+                    let keys = key.eval(input)?;
+                    let values = value.eval(input)?;
+
+                    let mut next_objects = vec![];
+                    for object in &objects {
+                        for key in &keys {
+                            let Value::String(key) = key else {
+                                bail!("Object keys must be strings, not {}", get_type_name(key));
+                            };
+
+                            for value in &values {
+                                let mut object = object.clone();
+                                object.insert(key.clone(), value.clone());
+                                next_objects.push(object);
+                            }
+                        }
+                    }
+
+                    objects = next_objects;
+                }
+
+                outputs.extend(objects.into_iter().map(Value::Object));
+            }
+
+            Expr::Call(name, args) => outputs = call(name, args, input)?,
+        }
+
+        Ok(outputs)
+    }
+}
+
+fn call(name: &str, args: &[Expr], input: &Value) -> anyhow::Result<Vec<Value>> {
+    let output = match (name, args) {
+        ("select", [condition]) => {
+            let selected = condition.eval(input)?.iter().any(is_truthy);
+
+            return Ok(match selected {
+                true => vec![input.clone()],
+                false => vec![],
+            });
+        }
+
+        ("map", [expr]) => {
+            let mut items = vec![];
+            for item in iterate(input)? {
+                items.extend(expr.eval(&item)?);
+            }
+
+            Value::Array(items)
+        }
+
+        ("has", [key]) => {
+            let mut outputs = vec![];
+            for key in key.eval(input)? {
+                let has_key = match (input, &key) {
+                    (Value::Object(map), Value::String(key)) => map.contains_key(key),
+                    (Value::Array(items), Value::Number(index)) => index
+                        .as_f64()
+                        .is_some_and(|index| index >= 0.0 && (index as usize) < items.len()),
+                    (input, key) => bail!(
+                        "Cannot check whether {} has a {} key",
+                        get_type_name(input),
+                        get_type_name(key)
+                    ),
+                };
+
+                outputs.push(Value::Bool(has_key));
+            }
+
+            return Ok(outputs);
+        }
+
+        ("length", []) => match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => bail!("boolean has no length"),
+            Value::Number(number) => get_number(number.as_f64().unwrap_or_default().abs()),
+            Value::String(text) => Value::from(text.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        },
+
+        ("keys", []) => match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+
+                Value::from_iter(keys.into_iter().cloned())
+            }
+            Value::Array(items) => Value::from_iter(0..items.len()),
+            input => bail!("{} has no keys", get_type_name(input)),
+        },
+
+        ("type", []) => Value::from(get_type_name(input)),
+        ("not", []) => Value::Bool(!is_truthy(input)),
+        ("first", []) => index(input, &Value::from(0))?,
+        ("last", []) => index(input, &Value::from(-1))?,
+        ("empty", []) => return Ok(vec![]),
+
+        (name, args) => bail!("{name}/{} is not a supported function", args.len()),
+    };
+
+    Ok(vec![output])
+}
+
+fn index(value: &Value, key: &Value) -> anyhow::Result<Value> {
+    match (value, key) {
+        (Value::Null, Value::String(_) | Value::Number(_)) => Ok(Value::Null),
+        (Value::Object(map), Value::String(key)) => Ok(map.get(key).cloned().unwrap_or_default()),
+        (Value::Array(items), Value::Number(index)) => {
+            let index = index.as_f64().unwrap_or_default().floor() as i64;
+
+            Ok(get_array_index(items.len(), index)
+                .and_then(|index| items.get(index))
+                .cloned()
+                .unwrap_or_default())
+        }
+        (value, key) => bail!("Cannot index {} with {key}", get_type_name(value)),
+    }
+}
+
+/// Negative indexes count from the end of the array
+fn get_array_index(length: usize, index: i64) -> Option<usize> {
+    match index < 0 {
+        true => length.checked_sub(index.unsigned_abs() as usize),
+        false => Some(index as usize),
+    }
+}
+
+fn slice(value: &Value, start: Option<i64>, end: Option<i64>) -> anyhow::Result<Value> {
+    match value {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => Ok(Value::from(
+            &items[get_slice_range(items.len(), start, end)],
+        )),
+        Value::String(text) => {
+            let chars: Vec<char> = text.chars().collect();
+            let range = get_slice_range(chars.len(), start, end);
+
+            Ok(Value::from(chars[range].iter().collect::<String>()))
+        }
+        value => bail!("Cannot slice {}", get_type_name(value)),
+    }
+}
+
+/// Slice indexes past either end are clamped to the sequence
+fn get_slice_range(length: usize, start: Option<i64>, end: Option<i64>) -> Range<usize> {
+    let clamp = |index: i64| get_array_index(length, index).unwrap_or(0).min(length);
+    let start = start.map_or(0, clamp);
+    let end = end.map_or(length, clamp);
+
+    start..end.max(start)
+}
+
+fn iterate(value: &Value) -> anyhow::Result<Vec<Value>> {
+    match value {
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(map) => Ok(map.values().cloned().collect()),
+        value => bail!("Cannot iterate over {}", get_type_name(value)),
+    }
+}
+
+fn push_descendants(value: &Value, outputs: &mut Vec<Value>) {
+    outputs.push(value.clone());
+
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| push_descendants(item, outputs)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| push_descendants(value, outputs)),
+        _ => {}
+    }
+}
+
+fn apply_binary(left: &Value, op: BinaryOp, right: &Value) -> Value {
+    let result = match op {
+        BinaryOp::Eq => compare(left, right) == Ordering::Equal,
+        BinaryOp::Ne => compare(left, right) != Ordering::Equal,
+        BinaryOp::Lt => compare(left, right) == Ordering::Less,
+        BinaryOp::Le => compare(left, right) != Ordering::Greater,
+        BinaryOp::Gt => compare(left, right) == Ordering::Greater,
+        BinaryOp::Ge => compare(left, right) != Ordering::Less,
+        BinaryOp::And => is_truthy(left) && is_truthy(right),
+        BinaryOp::Or => is_truthy(left) || is_truthy(right),
+    };
+
+    Value::Bool(result)
+}
+
+/// Values of different types are ordered like jq orders them:
+/// null, false, true, numbers, strings, arrays, objects
+fn compare(left: &Value, right: &Value) -> Ordering {
+    let get_rank = |value: &Value| match value {
+        Value::Null => 0,
+        Value::Bool(false) => 1,
+        Value::Bool(true) => 2,
+        Value::Number(_) => 3,
+        Value::String(_) => 4,
+        Value::Array(_) => 5,
+        Value::Object(_) => 6,
+    };
+
+    match (left, right) {
+        (Value::Number(left), Value::Number(right)) => {
+            let left = left.as_f64().unwrap_or_default();
+            let right = right.as_f64().unwrap_or_default();
+
+            left.partial_cmp(&right).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(left), Value::String(right)) => left.cmp(right),
+        (Value::Array(left), Value::Array(right)) => left
+            .iter()
+            .zip(right)
+            .map(|(left, right)| compare(left, right))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(left.len().cmp(&right.len())),
+        (Value::Object(left), Value::Object(right)) => {
+            let is_equal = left.len() == right.len()
+                && left.iter().all(|(key, left)| {
+                    right
+                        .get(key)
+                        .is_some_and(|right| compare(left, right).is_eq())
+                });
+
+            match is_equal {
+                true => Ordering::Equal,
+                false => left.len().cmp(&right.len()).then(Ordering::Less),
+            }
+        }
+        (left, right) => get_rank(left).cmp(&get_rank(right)),
+    }
+}
+
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn get_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Whole numbers are kept as integers so they are not shown as `1.0`
+fn get_number(number: f64) -> Value {
+    match number.fract() == 0.0 && number.abs() < 9007199254740992.0 {
+        true => Value::from(number as i64),
+        false => Value::from(number),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    DotDot,
+    Field(String),
+    Ident(String),
+    Str(String),
+    Num(f64),
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Colon,
+    Comma,
+    Pipe,
+    Question,
+    Op(BinaryOp),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Token::Dot => ".",
+            Token::DotDot => "..",
+            Token::Field(name) => return write!(f, ".{name}"),
+            Token::Ident(name) => name,
+            Token::Str(text) => return write!(f, "{}", Value::from(text.as_str())),
+            Token::Num(number) => return write!(f, "{number}"),
+            Token::LBracket => "[",
+            Token::RBracket => "]",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::Colon => ":",
+            Token::Comma => ",",
+            Token::Pipe => "|",
+            Token::Question => "?",
+            Token::Op(BinaryOp::Eq) => "==",
+            Token::Op(BinaryOp::Ne) => "!=",
+            Token::Op(BinaryOp::Lt) => "<",
+            Token::Op(BinaryOp::Le) => "<=",
+            Token::Op(BinaryOp::Gt) => ">",
+            Token::Op(BinaryOp::Ge) => ">=",
+            Token::Op(BinaryOp::And) => "and",
+            Token::Op(BinaryOp::Or) => "or",
+        };
+
+        write!(f, "{text}")
+    }
+}
+
+fn tokenize(query: &str) -> anyhow::Result<Vec<Token>> {
+    let chars: Vec<char> = query.chars().collect();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    let is_ident_start = |c: char| c.is_ascii_alphabetic() || c == '_';
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    while let Some(&c) = chars.get(position) {
+        let next = chars.get(position + 1).copied();
+        position += 1;
+
+        let token = match (c, next) {
+            (c, _) if c.is_whitespace() => continue,
+
+            ('.', Some('.')) => {
+                position += 1;
+                Token::DotDot
+            }
+
+            ('.', Some(next)) if is_ident_start(next) => {
+                let start = position;
+                while chars.get(position).is_some_and(|&c| is_ident(c)) {
+                    position += 1;
+                }
+
+                Token::Field(chars[start..position].iter().collect())
+            }
+
+            ('.', _) => Token::Dot,
+
+            ('"', _) => {
+                let start = position - 1;
+                let mut escaped = false;
+                loop {
+                    let Some(&c) = chars.get(position) else {
+                        bail!("The query has a string that is not closed");
+                    };
+
+                    position += 1;
+                    match (c, escaped) {
+                        ('"', false) => break,
+                        ('\\', false) => escaped = true,
+                        _ => escaped = false,
+                    }
+                }
+
+                let literal: String = chars[start..position].iter().collect();
+                Token::Str(serde_json::from_str(&literal)?)
+            }
+
+            (c, next)
+                if c.is_ascii_digit() || (c == '-' && next.is_some_and(|c| c.is_ascii_digit())) =>
+            {
+                let start = position - 1;
+                while chars
+                    .get(position)
+                    .is_some_and(|&c| c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E')
+                {
+                    position += 1;
+                }
+
+                let number: String = chars[start..position].iter().collect();
+                match number.parse() {
+                    Ok(number) => Token::Num(number),
+                    Err(_) => bail!("{number} is not a number"),
+                }
+            }
+
+            (c, _) if is_ident_start(c) => {
+                let start = position - 1;
+                while chars.get(position).is_some_and(|&c| is_ident(c)) {
+                    position += 1;
+                }
+
+                Token::Ident(chars[start..position].iter().collect())
+            }
+
+            ('=', Some('=')) | ('!', Some('=')) | ('<', Some('=')) | ('>', Some('=')) => {
+                position += 1;
+
+                Token::Op(match c {
+                    '=' => BinaryOp::Eq,
+                    '!' => BinaryOp::Ne,
+                    '<' => BinaryOp::Le,
+                    _ => BinaryOp::Ge,
+                })
+            }
+
+            ('<', _) => Token::Op(BinaryOp::Lt),
+            ('>', _) => Token::Op(BinaryOp::Gt),
+            ('[', _) => Token::LBracket,
+            (']', _) => Token::RBracket,
+            ('(', _) => Token::LParen,
+            (')', _) => Token::RParen,
+            ('{', _) => Token::LBrace,
+            ('}', _) => Token::RBrace,
+            (':', _) => Token::Colon,
+            (',', _) => Token::Comma,
+            ('|', _) => Token::Pipe,
+            ('?', _) => Token::Question,
+
+            (c, _) => bail!("Unexpected '{c}' in the query"),
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn parse_jq(query: &str) -> anyhow::Result<Expr> {
+    let mut parser = Parser {
+        tokens: tokenize(query)?,
+        position: 0,
+    };
+
+    if parser.tokens.is_empty() {
+        return Ok(Expr::Identity);
+    }
+
+    let expr = parser.parse_pipe()?;
+    if let Some(token) = parser.peek() {
+        bail!("Unexpected '{token}' in the query");
+    }
+
+    Ok(expr)
+}
+
+/// Parses jq expressions, from the lowest precedence `|` down to paths
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_next(&self) -> Option<&Token> {
+        self.tokens.get(self.position + 1)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let is_match = self.peek() == Some(token);
+        if is_match {
+            self.position += 1;
+        }
+
+        is_match
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.eat(&Token::Ident(keyword.to_string()))
+    }
+
+    fn expect(&mut self, token: Token) -> anyhow::Result<()> {
+        match self.next() {
+            Some(next) if next == token => Ok(()),
+            Some(next) => bail!("Expected '{token}' but found '{next}' in the query"),
+            None => bail!("Expected '{token}' at the end of the query"),
+        }
+    }
+
+    fn parse_pipe(&mut self) -> anyhow::Result<Expr> {
+        let left = self.parse_comma()?;
+
+        match self.eat(&Token::Pipe) {
+            true => Ok(Expr::Pipe(Box::new(left), Box::new(self.parse_pipe()?))),
+            false => Ok(left),
+        }
+    }
+
+    fn parse_comma(&mut self) -> anyhow::Result<Expr> {
+        let mut left = self.parse_or()?;
+        while self.eat(&Token::Comma) {
+            left = Expr::Comma(Box::new(left), Box::new(self.parse_or()?));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_or(&mut self) -> anyhow::Result<Expr> {
+        let mut left = self.parse_and()?;
+        while self.eat_keyword("or") {
+            let right = self.parse_and()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::Or, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<Expr> {
+        let mut left = self.parse_comparison()?;
+        while self.eat_keyword("and") {
+            let right = self.parse_comparison()?;
+            left = Expr::Binary(Box::new(left), BinaryOp::And, Box::new(right));
+        }
+
+        Ok(left)
+    }
+
+    fn parse_comparison(&mut self) -> anyhow::Result<Expr> {
+        let left = self.parse_postfix()?;
+
+        let Some(Token::Op(op)) = self.peek().cloned() else {
+            return Ok(left);
+        };
+
+        self.position += 1;
+        let right = self.parse_postfix()?;
+
+        Ok(Expr::Binary(Box::new(left), op, Box::new(right)))
+    }
+
+    fn parse_postfix(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.parse_primary()?;
+
+        loop {
+            expr = match (self.peek(), self.peek_next()) {
+                (Some(Token::Field(name)), _) => {
+                    let key = Expr::Literal(Value::from(name.as_str()));
+                    self.position += 1;
+
+                    Expr::Index(Box::new(expr), Box::new(key))
+                }
+
+                (Some(Token::Dot), Some(Token::Str(name))) => {
+                    let key = Expr::Literal(Value::from(name.as_str()));
+                    self.position += 2;
+
+                    Expr::Index(Box::new(expr), Box::new(key))
+                }
+
+                (Some(Token::Dot), Some(Token::LBracket)) => {
+                    self.position += 2;
+                    self.parse_brackets(expr)?
+                }
+
+                (Some(Token::LBracket), _) => {
+                    self.position += 1;
+                    self.parse_brackets(expr)?
+                }
+
+                (Some(Token::Question), _) => {
+                    self.position += 1;
+                    Expr::Optional(Box::new(expr))
+                }
+
+                _ => return Ok(expr),
+            };
+        }
+    }
+
+    /// `[]`, `[index]` or `[start:end]` after the opening bracket
+    fn parse_brackets(&mut self, target: Expr) -> anyhow::Result<Expr> {
+        let target = Box::new(target);
+
+        if self.eat(&Token::RBracket) {
+            return Ok(Expr::Iterate(target));
+        }
+
+        let is_slice = matches!(
+            (self.peek(), self.peek_next()),
+            (Some(Token::Colon), _) | (Some(Token::Num(_)), Some(Token::Colon))
+        );
+
+        if !is_slice {
+            let key = self.parse_pipe()?;
+            self.expect(Token::RBracket)?;
+
+            return Ok(Expr::Index(target, Box::new(key)));
+        }
+
+        let start = self.parse_slice_index();
+        self.expect(Token::Colon)?;
+        let end = self.parse_slice_index();
+        self.expect(Token::RBracket)?;
+
+        Ok(Expr::Slice(target, start, end))
+    }
+
+    fn parse_slice_index(&mut self) -> Option<i64> {
+        let Some(Token::Num(index)) = self.peek().cloned() else {
+            return None;
+        };
+
+        self.position += 1;
+
+        Some(index as i64)
+    }
+
+    fn parse_primary(&mut self) -> anyhow::Result<Expr> {
+        let Some(token) = self.next() else {
+            bail!("The query ended unexpectedly");
+        };
+
+        let expr = match token {
+            Token::Dot => match self.peek().cloned() {
+                Some(Token::Str(name)) => {
+                    self.position += 1;
+
+                    let key = Expr::Literal(Value::from(name));
+                    Expr::Index(Box::new(Expr::Identity), Box::new(key))
+                }
+                _ => Expr::Identity,
+            },
+
+            Token::Field(name) => {
+                let key = Expr::Literal(Value::from(name));
+                Expr::Index(Box::new(Expr::Identity), Box::new(key))
+            }
+
+            Token::DotDot => Expr::Recurse,
+            Token::Num(number) => Expr::Literal(get_number(number)),
+            Token::Str(text) => Expr::Literal(Value::from(text)),
+
+            Token::Ident(name) => match name.as_str() {
+                "true" => Expr::Literal(Value::Bool(true)),
+                "false" => Expr::Literal(Value::Bool(false)),
+                "null" => Expr::Literal(Value::Null),
+                _ if self.eat(&Token::LParen) => {
+                    let arg = self.parse_pipe()?;
+                    self.expect(Token::RParen)?;
+
+                    Expr::Call(name, vec![arg])
+                }
+                _ => Expr::Call(name, vec![]),
+            },
+
+            Token::LParen => {
+                let expr = self.parse_pipe()?;
+                self.expect(Token::RParen)?;
+
+                expr
+            }
+
+            Token::LBracket => match self.eat(&Token::RBracket) {
+                true => Expr::Array(None),
+                false => {
+                    let expr = self.parse_pipe()?;
+                    self.expect(Token::RBracket)?;
+
+                    Expr::Array(Some(Box::new(expr)))
+                }
+            },
+
+            Token::LBrace => self.parse_object()?,
+
+            token => bail!("Unexpected '{token}' in the query"),
+        };
+
+        Ok(expr)
+    }
+
+    /// `{name, "key": .value, (.key): .value}` after the opening brace
+    fn parse_object(&mut self) -> anyhow::Result<Expr> {
+        let mut entries = vec![];
+
+        while !self.eat(&Token::RBrace) {
+            let (key, shorthand) = match self.next() {
+                Some(Token::Ident(name)) | Some(Token::Str(name)) => {
+                    let key = Expr::Literal(Value::from(name));
+                    let shorthand = Expr::Index(Box::new(Expr::Identity), Box::new(key.clone()));
+
+                    (key, Some(shorthand))
+                }
+                Some(Token::LParen) => {
+                    let key = self.parse_pipe()?;
+                    self.expect(Token::RParen)?;
+
+                    (key, None)
+                }
+                Some(token) => bail!("Unexpected '{token}' in an object key"),
+                None => bail!("The query has an object that is not closed"),
+            };
+
+            let value = match (self.eat(&Token::Colon), shorthand) {
+                (true, _) => self.parse_or()?,
+                (false, Some(shorthand)) => shorthand,
+                (false, None) => bail!("Object keys in parentheses need a value"),
+            };
+
+            entries.push((key, value));
+
+            if !self.eat(&Token::Comma) {
+                self.expect(Token::RBrace)?;
+                break;
+            }
+        }
+
+        Ok(Expr::Object(entries))
+    }
+}
+
+/// A step of a JSON path, `..` steps select from every descendant
+pub struct PathSegment {
+    descendant: bool,
+    selectors: Vec<PathSelector>,
+}
+
+enum PathSelector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+    Slice(Option<i64>, Option<i64>),
+
+    /// `[?(@.price < 10)]`, the filter is a jq expression run on each child
+    Filter(Expr),
+}
+
+impl PathSegment {
+    fn select<'value>(&self, nodes: Vec<&'value Value>) -> Vec<&'value Value> {
+        let mut candidates = vec![];
+        for node in nodes {
+            match self.descendant {
+                true => push_descendant_nodes(node, &mut candidates),
+                false => candidates.push(node),
+            }
+        }
+
+        let mut selected = vec![];
+        for node in candidates {
+            for selector in &self.selectors {
+                selector.select(node, &mut selected);
+            }
+        }
+
+        selected
+    }
+}
+
+impl PathSelector {
+    fn select<'value>(&self, node: &'value Value, selected: &mut Vec<&'value Value>) {
+        let children: Vec<&Value> = match node {
+            Value::Array(items) => items.iter().collect(),
+            Value::Object(map) => map.values().collect(),
+            _ => vec![],
+        };
+
+        match (self, node) {
+            (PathSelector::Name(name), Value::Object(map)) => selected.extend(map.get(name)),
+
+            (PathSelector::Index(index), Value::Array(items)) => selected
+                .extend(get_array_index(items.len(), *index).and_then(|index| items.get(index))),
+
+            (PathSelector::Wildcard, _) => selected.extend(children),
+
+            (PathSelector::Slice(start, end), Value::Array(items)) => {
+                selected.extend(items[get_slice_range(items.len(), *start, *end)].iter())
+            }
+
+            (PathSelector::Filter(filter), _) => {
+                selected.extend(children.into_iter().filter(|child| {
+                    filter
+                        .eval(child)
+                        .is_ok_and(|outputs| outputs.iter().any(is_truthy))
+                }))
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn push_descendant_nodes<'value>(value: &'value Value, nodes: &mut Vec<&'value Value>) {
+    nodes.push(value);
+
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| push_descendant_nodes(item, nodes)),
+        Value::Object(map) => map
+            .values()
+            .for_each(|value| push_descendant_nodes(value, nodes)),
+        _ => {}
+    }
+}
+
+fn parse_json_path(path: &str) -> anyhow::Result<Vec<PathSegment>> {
+    let Some(mut rest) = path.strip_prefix('$') else {
+        bail!("JSON paths start with $");
+    };
+
+    let mut segments = vec![];
+    while !rest.is_empty() {
+        let descendant = rest.starts_with("..");
+        rest = match descendant {
+            true => &rest[2..],
+            false => rest.strip_prefix('.').unwrap_or(rest),
+        };
+
+        let selectors = match rest.strip_prefix('[') {
+            Some(brackets) => {
+                let end = find_closing_bracket(brackets)?;
+                let selectors = parse_path_brackets(&brackets[..end])?;
+                rest = &brackets[end + 1..];
+
+                selectors
+            }
+
+            None => {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let name = &rest[..end];
+                rest = &rest[end..];
+
+                match name {
+                    "" => bail!("The JSON path has an empty name"),
+                    "*" => vec![PathSelector::Wildcard],
+                    name => vec![PathSelector::Name(name.to_string())],
+                }
+            }
+        };
+
+        segments.push(PathSegment {
+            descendant,
+            selectors,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// The index of the `]` that closes the brackets, skipping nested brackets
+/// and quoted strings
+fn find_closing_bracket(input: &str) -> anyhow::Result<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (index, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(_), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(open), c) if c == open && !escaped => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, ']') if depth == 0 => return Ok(index),
+            (None, ']') => depth -= 1,
+            _ => {}
+        }
+
+        escaped = false;
+    }
+
+    bail!("The JSON path has a [ that is not closed")
+}
+
+fn parse_path_brackets(content: &str) -> anyhow::Result<Vec<PathSelector>> {
+    let content = content.trim();
+
+    if let Some(filter) = content.strip_prefix('?') {
+        return Ok(vec![PathSelector::Filter(parse_jq(&get_jq_filter(
+            filter,
+        ))?)]);
+    }
+
+    split_selectors(content)
+        .into_iter()
+        .map(|selector| {
+            let selector = selector.trim();
+
+            if selector == "*" {
+                return Ok(PathSelector::Wildcard);
+            }
+
+            if let Some(name) = get_quoted(selector) {
+                return Ok(PathSelector::Name(name));
+            }
+
+            if let Some((start, end)) = selector.split_once(':') {
+                let parse = |index: &str| index.trim().parse::<i64>().ok();
+                return Ok(PathSelector::Slice(parse(start), parse(end)));
+            }
+
+            match selector.parse() {
+                Ok(index) => Ok(PathSelector::Index(index)),
+                Err(_) => bail!("{selector} is not a JSON path selector"),
+            }
+        })
+        .collect()
+}
+
+/// Splits `'a','b'` on the commas that are not quoted
+fn split_selectors(content: &str) -> Vec<String> {
+    let mut selectors = vec![String::new()];
+    let mut quote = None;
+
+    for c in content.chars() {
+        match (quote, c) {
+            (None, ',') => {
+                selectors.push(String::new());
+                continue;
+            }
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            _ => {}
+        }
+
+        if let Some(selector) = selectors.last_mut() {
+            selector.push(c);
+        }
+    }
+
+    selectors
+}
+
+fn get_quoted(selector: &str) -> Option<String> {
+    let quote = selector
+        .chars()
+        .next()
+        .filter(|c| *c == '\'' || *c == '"')?;
+    let name = selector.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    Some(name.replace(&format!("\\{quote}"), &quote.to_string()))
+}
+
+/// Turns a JSON path filter into a jq expression, `@` becomes `.`, `&&`
+/// and `||` become `and` and `or`, single quoted strings become JSON strings
+fn get_jq_filter(filter: &str) -> String {
+    let chars: Vec<char> = filter.chars().collect();
+    let mut jq = String::new();
+    let mut position = 0;
+
+    while let Some(&c) = chars.get(position) {
+        let next = chars.get(position + 1).copied();
+        position += 1;
+
+        match (c, next) {
+            ('@', Some('.')) => {}
+            ('@', _) => jq.push('.'),
+            ('&', Some('&')) => {
+                position += 1;
+                jq.push_str(" and ");
+            }
+            ('|', Some('|')) => {
+                position += 1;
+                jq.push_str(" or ");
+            }
+            ('\'', _) => {
+                let start = position;
+                while chars.get(position).is_some_and(|&c| c != '\'') {
+                    position += 1;
+                }
+
+                let text: String = chars[start..position.min(chars.len())].iter().collect();
+                jq.push_str(&Value::from(text).to_string());
+                position += 1;
+            }
+            (c, _) => jq.push(c),
+        }
+    }
+
+    jq
+}
+
+#[cfg(test)]
+fn run_query(query: &str, input: &str) -> anyhow::Result<Vec<Value>> {
+    let input: Value = serde_json::from_str(input)?;
+
+    JsonQuery::parse(query)?.run(&input)
+}
+
+#[cfg(test)]
+const USERS: &str = r#"{
+    "data": [
+        {"id": 1, "name": "Ada", "active": true, "tags": ["admin"]},
+        {"id": 2, "name": "Linus", "active": false, "tags": []},
+        {"id": 3, "name": "Grace", "active": true, "tags": ["ops", "dev"]}
+    ],
+    "next page": null
+}"#;
+
+#[test]
+fn test_jq_paths() {
+    let ids = run_query(".data[] | select(.active) | .id", USERS).unwrap();
+    assert_eq!(ids, vec![Value::from(1), Value::from(3)]);
+
+    let name = run_query(".data[-1].name", USERS).unwrap();
+    assert_eq!(name, vec![Value::from("Grace")]);
+
+    let next = run_query(r#"."next page", .["data"][0].tags[0]"#, USERS).unwrap();
+    assert_eq!(next, vec![Value::Null, Value::from("admin")]);
+
+    let sliced = run_query(".data[1:] | map(.id)", USERS).unwrap();
+    assert_eq!(sliced, vec![serde_json::json!([2, 3])]);
+
+    let everything = run_query(".", USERS).unwrap();
+    assert_eq!(everything.len(), 1);
+}
+
+#[test]
+fn test_jq_construction() {
+    let users = run_query(
+        "[.data[] | select(.id >= 2 and (.tags | length) > 0) | {name, tag_count: (.tags | length)}]",
+        USERS,
+    )
+    .unwrap();
+    assert_eq!(
+        users,
+        vec![serde_json::json!([{"name": "Grace", "tag_count": 2}])]
+    );
+
+    let names = run_query("[.. | .name? | select(. != null)]", USERS).unwrap();
+    assert_eq!(names, vec![serde_json::json!(["Ada", "Linus", "Grace"])]);
+
+    let keys = run_query(".data[0] | keys", USERS).unwrap();
+    assert_eq!(
+        keys,
+        vec![serde_json::json!(["active", "id", "name", "tags"])]
+    );
+}
+
+#[test]
+fn test_jq_errors() {
+    assert!(JsonQuery::parse(".data[").is_err());
+    assert!(JsonQuery::parse(".data | ").is_err());
+    assert!(JsonQuery::parse(r#".name == "open"#).is_err());
+    assert!(run_query(".data.id", USERS).is_err());
+    assert!(run_query(".data | frobnicate", USERS).is_err());
+    assert!(run_query(".data.id?", USERS).unwrap().is_empty());
+}
+
+#[test]
+fn test_json_path() {
+    let names = run_query("$.data[*].name", USERS).unwrap();
+    assert_eq!(
+        names,
+        vec![
+            Value::from("Ada"),
+            Value::from("Linus"),
+            Value::from("Grace")
+        ]
+    );
+
+    let active = run_query("$.data[?(@.active && @.id > 1)].name", USERS).unwrap();
+    assert_eq!(active, vec![Value::from("Grace")]);
+
+    let tags = run_query("$..tags[0]", USERS).unwrap();
+    assert_eq!(tags, vec![Value::from("admin"), Value::from("ops")]);
+
+    let named = run_query("$.data[?(@.name == 'Linus')].id", USERS).unwrap();
+    assert_eq!(named, vec![Value::from(2)]);
+
+    let next = run_query("$['next page']", USERS).unwrap();
+    assert_eq!(next, vec![Value::Null]);
+
+    let first_two = run_query("$.data[:2].id", USERS).unwrap();
+    assert_eq!(first_two, vec![Value::from(1), Value::from(2)]);
+
+    assert!(JsonQuery::parse("$.data[0").is_err());
+}
@@ -0,0 +1,668 @@
+use std::{collections::HashSet, fmt::Display, fs};
+
+use anyhow::bail;
+use regex::Regex;
+use serde_json::{Map, Value};
+
+/// $refs that lead to other $refs without going deeper into the response
+/// more times than this are a loop
+const MAX_REF_DEPTH: usize = 64;
+
+/// A value of the response that does not match the schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// JSON pointer of the value, e.g. `/data/0/id`
+    pub pointer: String,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.pointer.is_empty() {
+            true => write!(f, "(root): {}", self.message),
+            false => write!(f, "{}: {}", self.pointer, self.message),
+        }
+    }
+}
+
+/// A JSON Schema and the document it was found in, $refs are resolved
+/// against the document so component schemas of OpenAPI documents can
+/// refer to each other
+#[derive(Debug)]
+pub struct JsonSchema {
+    document: Value,
+
+    /// JSON pointer of the schema in the document
+    pointer: String,
+}
+
+impl JsonSchema {
+    /// Reads the schema of an endpoint, one of:
+    ///
+    /// - an inline schema, e.g. `{"type": "object", "required": ["id"]}`
+    /// - the path of a schema file, e.g. `schemas/user.json`
+    /// - a schema inside a JSON document, e.g. `openapi.json#/components/schemas/User`
+    pub fn load(source: &str) -> anyhow::Result<Self> {
+        let source = source.trim();
+
+        if source.starts_with('{') {
+            let document = serde_json::from_str(source)
+                .map_err(|error| anyhow::anyhow!("The inline schema is not valid JSON: {error}"))?;
+
+            return Ok(JsonSchema {
+                document,
+                pointer: String::new(),
+            });
+        }
+
+        let (path, pointer) = source.split_once('#').unwrap_or((source, ""));
+        let contents = fs::read_to_string(path)
+            .map_err(|error| anyhow::anyhow!("Could not read the schema {path}: {error}"))?;
+        let document: Value = serde_json::from_str(&contents)
+            .map_err(|error| anyhow::anyhow!("{path} is not a JSON document: {error}"))?;
+
+        if document.pointer(pointer).is_none() {
+            bail!("{path} has no schema at #{pointer}");
+        }
+
+        Ok(JsonSchema {
+            document,
+            pointer: pointer.to_string(),
+        })
+    }
+
+    /// Every value of the instance that does not match the schema, in the
+    /// order they are found
+    pub fn validate(&self, instance: &Value) -> anyhow::Result<Vec<Violation>> {
+        let Some(schema) = self.document.pointer(&self.pointer) else {
+            bail!("There is no schema at #{}", self.pointer);
+        };
+
+        let mut validator = Validator {
+            document: &self.document,
+            violations: vec![],
+        };
+        validator.validate(schema, instance, "", 0)?;
+
+        Ok(validator.violations)
+    }
+}
+
+/// Validates a response body with the schema of its endpoint
+pub fn validate_response(schema_source: &str, body: &str) -> anyhow::Result<Vec<Violation>> {
+    let schema = JsonSchema::load(schema_source)?;
+    let Ok(instance) = serde_json::from_str::<Value>(body) else {
+        bail!("The response is not valid JSON");
+    };
+
+    schema.validate(&instance)
+}
+
+/// The violations of a response as assertion failures, a schema that can
+/// not be used is one failure. Endpoints without a schema have none.
+pub fn get_schema_failures(schema_source: &str, body: &str) -> Vec<String> {
+    if schema_source.trim().is_empty() {
+        return vec![];
+    }
+
+    match validate_response(schema_source, body) {
+        Ok(violations) => violations
+            .iter()
+            .map(|violation| violation.to_string())
+            .collect(),
+
+        Err(error) => vec![error.to_string()],
+    }
+}
+
+struct Validator<'document> {
+    document: &'document Value,
+    violations: Vec<Violation>,
+}
+
+impl<'document> Validator<'document> {
+    fn push(&mut self, pointer: &str, message: impl Into<String>) {
+        self.violations.push(Violation {
+            pointer: pointer.to_string(),
+            message: message.into(),
+        });
+    }
+
+    /// Whether the instance matches the schema, without keeping the
+    /// violations, for `anyOf`, `oneOf`, `not` and `if`
+    fn matches(
+        &self,
+        schema: &'document Value,
+        instance: &Value,
+        pointer: &str,
+        ref_depth: usize,
+    ) -> anyhow::Result<bool> {
+        let mut validator = Validator {
+            document: self.document,
+            violations: vec![],
+        };
+        validator.validate(schema, instance, pointer, ref_depth)?;
+
+        Ok(validator.violations.is_empty())
+    }
+
+    fn resolve(&self, reference: &str) -> anyhow::Result<&'document Value> {
+        let Some(pointer) = reference.strip_prefix('#') else {
+            bail!("Only $refs inside the schema document are supported, found {reference}");
+        };
+
+        match self.document.pointer(pointer) {
+            Some(schema) => Ok(schema),
+            None => bail!("The schema $ref {reference} does not exist"),
+        }
+    }
+
+    fn validate(
+        &mut self,
+        schema: &'document Value,
+        instance: &Value,
+        pointer: &str,
+        ref_depth: usize,
+    ) -> anyhow::Result<()> {
+        let schema = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => {
+                self.push(pointer, "no value is allowed here");
+                return Ok(());
+            }
+            Value::Object(schema) => schema,
+            _ => bail!("The schema for {pointer} is not an object"),
+        };
+
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            if ref_depth >= MAX_REF_DEPTH {
+                bail!("The schema $ref {reference} refers back to itself");
+            }
+
+            let target = self.resolve(reference)?;
+            self.validate(target, instance, pointer, ref_depth + 1)?;
+        }
+
+        // NOTE: OpenAPI 3.0 schemas allow null with `nullable` instead of a
+        // `null` type
+        let is_nullable = schema.get("nullable") == Some(&Value::Bool(true));
+        if instance.is_null() && is_nullable {
+            return Ok(());
+        }
+
+        if let Some(types) = schema.get("type") {
+            self.validate_type(types, instance, pointer);
+        }
+
+        let enum_values = schema.get("enum").and_then(Value::as_array);
+        if let Some(values) = enum_values.filter(|values| !values.contains(instance)) {
+            let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+            self.push(pointer, format!("must be one of {}", values.join(", ")));
+        }
+
+        if let Some(value) = schema.get("const").filter(|value| *value != instance) {
+            self.push(pointer, format!("must be {value}"));
+        }
+
+        match instance {
+            Value::Number(number) => {
+                if let Some(number) = number.as_f64() {
+                    self.validate_number(schema, number, pointer);
+                }
+            }
+            Value::String(text) => self.validate_string(schema, text, pointer)?,
+            Value::Array(items) => self.validate_array(schema, items, pointer)?,
+            Value::Object(properties) => self.validate_object(schema, properties, pointer)?,
+            _ => {}
+        }
+
+        self.validate_combinators(schema, instance, pointer, ref_depth)
+    }
+
+    fn validate_type(&mut self, types: &Value, instance: &Value, pointer: &str) {
+        let types: Vec<&str> = match types {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => return,
+        };
+
+        let instance_type = get_type_name(instance);
+        let is_match = types.iter().any(|name| match *name {
+            "number" => instance.is_number(),
+            "integer" => is_integer(instance),
+            name => name == instance_type,
+        });
+
+        if !is_match {
+            self.push(
+                pointer,
+                format!("expected {}, found {instance_type}", types.join(" or ")),
+            );
+        }
+    }
+
+    fn validate_number(&mut self, schema: &Map<String, Value>, number: f64, pointer: &str) {
+        let limit = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+
+        if let Some(minimum) = limit("minimum").filter(|minimum| number < *minimum) {
+            self.push(pointer, format!("must be at least {minimum}"));
+        }
+
+        if let Some(maximum) = limit("maximum").filter(|maximum| number > *maximum) {
+            self.push(pointer, format!("must be at most {maximum}"));
+        }
+
+        if let Some(minimum) = limit("exclusiveMinimum").filter(|minimum| number <= *minimum) {
+            self.push(pointer, format!("must be greater than {minimum}"));
+        }
+
+        if let Some(maximum) = limit("exclusiveMaximum").filter(|maximum| number >= *maximum) {
+            self.push(pointer, format!("must be less than {maximum}"));
+        }
+
+        if let Some(divisor) = limit("multipleOf") {
+            let quotient = number / divisor;
+            if divisor > 0.0 && (quotient - quotient.round()).abs() > f64::EPSILON * quotient.abs()
+            {
+                self.push(pointer, format!("must be a multiple of {divisor}"));
+            }
+        }
+    }
+
+    fn validate_string(
+        &mut self,
+        schema: &Map<String, Value>,
+        text: &str,
+        pointer: &str,
+    ) -> anyhow::Result<()> {
+        let length = text.chars().count() as u64;
+
+        if let Some(min_length) = schema
+            .get("minLength")
+            .and_then(Value::as_u64)
+            .filter(|min_length| length < *min_length)
+        {
+            self.push(pointer, format!("must be at least {min_length} characters"));
+        }
+
+        if let Some(max_length) = schema
+            .get("maxLength")
+            .and_then(Value::as_u64)
+            .filter(|max_length| length > *max_length)
+        {
+            self.push(pointer, format!("must be at most {max_length} characters"));
+        }
+
+        if let Some(Value::String(pattern)) = schema.get("pattern") {
+            let Ok(regex) = Regex::new(pattern) else {
+                bail!("The schema pattern {pattern} is not a valid regex");
+            };
+
+            if !regex.is_match(text) {
+                self.push(pointer, format!("does not match the pattern {pattern}"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_array(
+        &mut self,
+        schema: &'document Map<String, Value>,
+        items: &[Value],
+        pointer: &str,
+    ) -> anyhow::Result<()> {
+        let item_pointer = |index: usize| format!("{pointer}/{index}");
+
+        // NOTE: `prefixItems` is the 2020-12 tuple keyword, older drafts use
+        // an array of schemas in `items` and `additionalItems` for the rest
+        let (prefix_items, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix_items)), rest) => (prefix_items.as_slice(), rest),
+            (None, Some(Value::Array(prefix_items))) => {
+                (prefix_items.as_slice(), schema.get("additionalItems"))
+            }
+            (_, rest) => (&[][..], rest),
+        };
+
+        for (index, item) in items.iter().enumerate() {
+            let item_schema = prefix_items.get(index).or(rest);
+            if let Some(item_schema) = item_schema {
+                self.validate(item_schema, item, &item_pointer(index), 0)?;
+            }
+        }
+
+        let count = items.len() as u64;
+        if let Some(min_items) = schema
+            .get("minItems")
+            .and_then(Value::as_u64)
+            .filter(|min_items| count < *min_items)
+        {
+            self.push(pointer, format!("must have at least {min_items} items"));
+        }
+
+        if let Some(max_items) = schema
+            .get("maxItems")
+            .and_then(Value::as_u64)
+            .filter(|max_items| count > *max_items)
+        {
+            self.push(pointer, format!("must have at most {max_items} items"));
+        }
+
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let unique: HashSet<String> = items.iter().map(|item| item.to_string()).collect();
+            if unique.len() < items.len() {
+                self.push(pointer, "items must be unique");
+            }
+        }
+
+        if let Some(contains) = schema.get("contains") {
+            let mut found = false;
+            for (index, item) in items.iter().enumerate() {
+                if self.matches(contains, item, &item_pointer(index), 0)? {
+                    found = true;
+                    break;
+                }
+            }
+
+            if !found {
+                self.push(pointer, "no item matches the contains schema");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_object(
+        &mut self,
+        schema: &'document Map<String, Value>,
+        properties: &Map<String, Value>,
+        pointer: &str,
+    ) -> anyhow::Result<()> {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !properties.contains_key(name) {
+                    self.push(pointer, format!("missing required property {name}"));
+                }
+            }
+        }
+
+        let property_schemas = schema.get("properties").and_then(Value::as_object);
+        let pattern_schemas = match schema.get("patternProperties").and_then(Value::as_object) {
+            Some(pattern_schemas) => pattern_schemas
+                .iter()
+                .map(|(pattern, pattern_schema)| match Regex::new(pattern) {
+                    Ok(regex) => Ok((regex, pattern_schema)),
+                    Err(_) => bail!("The schema pattern {pattern} is not a valid regex"),
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?,
+            None => vec![],
+        };
+
+        for (name, value) in properties {
+            let property_pointer = format!("{pointer}/{}", escape_pointer_token(name));
+            let mut is_known = false;
+
+            if let Some(property_schema) = property_schemas.and_then(|schemas| schemas.get(name)) {
+                is_known = true;
+                self.validate(property_schema, value, &property_pointer, 0)?;
+            }
+
+            for (regex, pattern_schema) in &pattern_schemas {
+                if regex.is_match(name) {
+                    is_known = true;
+                    self.validate(pattern_schema, value, &property_pointer, 0)?;
+                }
+            }
+
+            match schema.get("additionalProperties") {
+                _ if is_known => {}
+                Some(Value::Bool(false)) => {
+                    self.push(&property_pointer, format!("property {name} is not allowed"));
+                }
+                Some(additional_schema) => {
+                    self.validate(additional_schema, value, &property_pointer, 0)?;
+                }
+                None => {}
+            }
+        }
+
+        let count = properties.len() as u64;
+        if let Some(min_properties) = schema
+            .get("minProperties")
+            .and_then(Value::as_u64)
+            .filter(|min_properties| count < *min_properties)
+        {
+            self.push(
+                pointer,
+                format!("must have at least {min_properties} properties"),
+            );
+        }
+
+        if let Some(max_properties) = schema
+            .get("maxProperties")
+            .and_then(Value::as_u64)
+            .filter(|max_properties| count > *max_properties)
+        {
+            self.push(
+                pointer,
+                format!("must have at most {max_properties} properties"),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn validate_combinators(
+        &mut self,
+        schema: &'document Map<String, Value>,
+        instance: &Value,
+        pointer: &str,
+        ref_depth: usize,
+    ) -> anyhow::Result<()> {
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for all_of_schema in schemas {
+                self.validate(all_of_schema, instance, pointer, ref_depth)?;
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            let mut any_match = false;
+            for any_of_schema in schemas {
+                if self.matches(any_of_schema, instance, pointer, ref_depth)? {
+                    any_match = true;
+                    break;
+                }
+            }
+
+            if !any_match {
+                self.push(pointer, "does not match any of the anyOf schemas");
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let mut match_count = 0;
+            for one_of_schema in schemas {
+                if self.matches(one_of_schema, instance, pointer, ref_depth)? {
+                    match_count += 1;
+                }
+            }
+
+            if match_count != 1 {
+                let message = format!("matches {match_count} of the oneOf schemas instead of 1");
+                self.push(pointer, message);
+            }
+        }
+
+        if let Some(not_schema) = schema.get("not") {
+            let is_match = self.matches(not_schema, instance, pointer, ref_depth)?;
+            if is_match {
+                self.push(pointer, "must not match the not schema");
+            }
+        }
+
+        if let Some(if_schema) = schema.get("if") {
+            let branch = match self.matches(if_schema, instance, pointer, ref_depth)? {
+                true => schema.get("then"),
+                false => schema.get("else"),
+            };
+
+            if let Some(branch) = branch {
+                self.validate(branch, instance, pointer, ref_depth)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn get_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) if is_integer(value) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Numbers like `1.0` are integers too
+fn is_integer(value: &Value) -> bool {
+    match value {
+        Value::Number(number) => {
+            number.is_i64()
+                || number.is_u64()
+                || number.as_f64().is_some_and(|number| number.fract() == 0.0)
+        }
+        _ => false,
+    }
+}
+
+/// Escapes `~` and `/` in a key of a JSON pointer
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+fn get_violations(schema: &str, instance: &str) -> Vec<String> {
+    let schema = JsonSchema::load(schema).unwrap();
+    let instance = serde_json::from_str(instance).unwrap();
+
+    schema
+        .validate(&instance)
+        .unwrap()
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect()
+}
+
+#[test]
+fn test_validate_object() {
+    let schema = r#"{
+        "type": "object",
+        "required": ["id", "name"],
+        "properties": {
+            "id": {"type": "integer", "minimum": 1},
+            "name": {"type": "string", "minLength": 2},
+            "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+        },
+        "additionalProperties": false
+    }"#;
+
+    assert!(get_violations(schema, r#"{"id": 1, "name": "Ann"}"#).is_empty());
+    assert_eq!(
+        get_violations(schema, r#"{"id": 0, "tags": ["a", 2, "a"], "a/b": true}"#),
+        vec![
+            "(root): missing required property name",
+            "/id: must be at least 1",
+            "/tags/1: expected string, found integer",
+            "/tags: items must be unique",
+            "/a~1b: property a/b is not allowed",
+        ]
+    );
+}
+
+#[test]
+fn test_validate_refs_and_combinators() {
+    let schema = r##"{
+        "$defs": {
+            "status": {"enum": ["active", "disabled"]},
+            "user": {
+                "type": "object",
+                "properties": {
+                    "status": {"$ref": "#/$defs/status"},
+                    "email": {"type": ["string", "null"], "pattern": "@"},
+                    "manager": {"$ref": "#/$defs/user"}
+                }
+            }
+        },
+        "type": "array",
+        "items": {"$ref": "#/$defs/user"},
+        "minItems": 1
+    }"##;
+
+    assert_eq!(
+        get_violations(
+            schema,
+            r#"[{"status": "active", "email": null, "manager": {"status": "gone", "email": "x"}}]"#
+        ),
+        vec![
+            r#"/0/manager/status: must be one of "active", "disabled""#,
+            "/0/manager/email: does not match the pattern @",
+        ]
+    );
+    assert_eq!(
+        get_violations(schema, "[]"),
+        vec!["(root): must have at least 1 items"]
+    );
+
+    let one_of = r#"{"oneOf": [{"type": "integer"}, {"type": "number"}]}"#;
+    assert!(get_violations(one_of, "1.5").is_empty());
+    assert_eq!(
+        get_violations(one_of, "2"),
+        vec!["(root): matches 2 of the oneOf schemas instead of 1"]
+    );
+
+    let looped = JsonSchema::load(r##"{"$ref": "#"}"##).unwrap();
+    assert!(looped.validate(&Value::Null).is_err());
+}
+
+#[test]
+fn test_validate_openapi_component() {
+    let document = r##"{
+        "openapi": "3.0.0",
+        "components": {
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string"},
+                        "owner": {"$ref": "#/components/schemas/Owner"}
+                    }
+                },
+                "Owner": {"type": "object", "nullable": true, "required": ["id"]}
+            }
+        }
+    }"##;
+
+    let mut path = std::env::temp_dir();
+    path.push(format!("tome_schema_{}.json", uuid::Uuid::new_v4()));
+    fs::write(&path, document).unwrap();
+
+    let source = format!("{}#/components/schemas/Pet", path.display());
+    assert!(get_violations(&source, r#"{"name": "Rex", "owner": null}"#).is_empty());
+    assert_eq!(
+        get_violations(&source, r#"{"owner": {}}"#),
+        vec![
+            "(root): missing required property name",
+            "/owner: missing required property id",
+        ]
+    );
+
+    let missing = format!("{}#/components/schemas/Cat", path.display());
+    assert!(JsonSchema::load(&missing).is_err());
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,261 @@
+use std::{collections::HashSet, io::Read};
+
+use serde_json::Value;
+
+const INDENT: &str = "  ";
+
+/// A parsed JSON document and which of its objects and arrays are expanded
+pub struct JsonTree {
+    root: Value,
+
+    /// JSON paths of the expanded nodes
+    expanded: HashSet<String>,
+}
+
+/// A visible node of the tree
+pub struct TreeRow<'tree> {
+    pub path: String,
+    pub depth: usize,
+
+    /// The quoted key in an object or the index in an array, None for the root
+    pub key: Option<String>,
+    pub value: &'tree Value,
+    pub expanded: bool,
+}
+
+impl JsonTree {
+    /// Parses the document with only the root expanded
+    pub fn parse(reader: impl Read) -> anyhow::Result<Self> {
+        let root: Value = serde_json::from_reader(reader)?;
+
+        Ok(JsonTree {
+            root,
+            expanded: HashSet::from([String::from("$")]),
+        })
+    }
+
+    /// The nodes that are not inside a collapsed node, in document order
+    pub fn rows(&self) -> Vec<TreeRow<'_>> {
+        let mut rows = vec![];
+        self.push_rows(&mut rows, String::from("$"), 0, None, &self.root);
+
+        rows
+    }
+
+    fn push_rows<'tree>(
+        &'tree self,
+        rows: &mut Vec<TreeRow<'tree>>,
+        path: String,
+        depth: usize,
+        key: Option<String>,
+        value: &'tree Value,
+    ) {
+        let expanded = is_container(value) && self.expanded.contains(&path);
+
+        rows.push(TreeRow {
+            path: path.clone(),
+            depth,
+            key,
+            value,
+            expanded,
+        });
+
+        if !expanded {
+            return;
+        }
+
+        match value {
+            Value::Object(map) => map.iter().for_each(|(key, child)| {
+                let child_path = get_key_path(&path, key);
+                let key = Value::from(key.as_str()).to_string();
+                self.push_rows(rows, child_path, depth + 1, Some(key), child);
+            }),
+
+            Value::Array(items) => items.iter().enumerate().for_each(|(index, child)| {
+                let child_path = format!("{path}[{index}]");
+                self.push_rows(rows, child_path, depth + 1, Some(index.to_string()), child);
+            }),
+
+            _ => {}
+        }
+    }
+
+    pub fn expand(&mut self, path: &str) {
+        self.expanded.insert(path.to_string());
+    }
+
+    pub fn collapse(&mut self, path: &str) {
+        self.expanded.remove(path);
+    }
+
+    pub fn toggle(&mut self, path: &str) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_string());
+        }
+    }
+
+    pub fn expand_all(&mut self) {
+        let mut paths = vec![];
+        get_container_paths(&self.root, String::from("$"), &mut paths);
+
+        self.expanded.extend(paths);
+    }
+
+    /// Collapses every node but the root
+    pub fn collapse_all(&mut self) {
+        self.expanded.retain(|path| path == "$");
+    }
+}
+
+impl TreeRow<'_> {
+    pub fn is_container(&self) -> bool {
+        is_container(self.value)
+    }
+
+    /// The row as it is shown in the tree view, e.g. `▾ "data": {} 3 keys`
+    pub fn get_line(&self) -> String {
+        let marker = match (self.is_container(), self.expanded) {
+            (true, true) => "▾ ",
+            (true, false) => "▸ ",
+            (false, _) => "  ",
+        };
+
+        let key = match &self.key {
+            Some(key) => format!("{key}: "),
+            None => String::new(),
+        };
+
+        format!(
+            "{}{marker}{key}{}",
+            INDENT.repeat(self.depth),
+            get_summary(self.value)
+        )
+    }
+
+    /// The value to copy, strings are copied without their quotes
+    pub fn get_copy_value(&self) -> String {
+        match self.value {
+            Value::String(text) => text.clone(),
+            value => serde_json::to_string_pretty(value).unwrap_or_default(),
+        }
+    }
+}
+
+fn is_container(value: &Value) -> bool {
+    matches!(value, Value::Object(_) | Value::Array(_))
+}
+
+/// Objects and arrays show how many children they have, other values are
+/// shown as JSON
+fn get_summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => match map.len() {
+            1 => String::from("{} 1 key"),
+            count => format!("{{}} {count} keys"),
+        },
+        Value::Array(items) => match items.len() {
+            1 => String::from("[] 1 item"),
+            count => format!("[] {count} items"),
+        },
+        value => value.to_string(),
+    }
+}
+
+/// Keys that are not identifiers use the bracket notation, e.g. `$["user id"]`
+pub fn get_key_path(parent: &str, key: &str) -> String {
+    let is_identifier_char = |(index, c): (usize, char)| {
+        c == '_' || c.is_ascii_alphabetic() || (index > 0 && c.is_ascii_digit())
+    };
+
+    match !key.is_empty() && key.chars().enumerate().all(is_identifier_char) {
+        true => format!("{parent}.{key}"),
+        false => format!("{parent}[{}]", Value::from(key)),
+    }
+}
+
+fn get_container_paths(value: &Value, path: String, paths: &mut Vec<String>) {
+    match value {
+        Value::Object(map) => map.iter().for_each(|(key, child)| {
+            get_container_paths(child, get_key_path(&path, key), paths);
+        }),
+
+        Value::Array(items) => items.iter().enumerate().for_each(|(index, child)| {
+            get_container_paths(child, format!("{path}[{index}]"), paths);
+        }),
+
+        _ => return,
+    }
+
+    paths.push(path);
+}
+
+#[test]
+fn test_json_tree_rows() {
+    let body = r#"{"data": {"users": [{"id": 1}, {"id": 2}], "next page": null}, "ok": true}"#;
+    let mut tree = JsonTree::parse(body.as_bytes()).unwrap();
+
+    let lines = |tree: &JsonTree| {
+        tree.rows()
+            .iter()
+            .map(|row| row.get_line())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(
+        lines(&tree),
+        vec!["▾ {} 2 keys", "  ▸ \"data\": {} 2 keys", "    \"ok\": true"]
+    );
+
+    tree.toggle("$.data");
+    tree.expand("$.data.users");
+    assert_eq!(
+        lines(&tree),
+        vec![
+            "▾ {} 2 keys",
+            "  ▾ \"data\": {} 2 keys",
+            "    ▾ \"users\": [] 2 items",
+            "      ▸ 0: {} 1 key",
+            "      ▸ 1: {} 1 key",
+            "      \"next page\": null",
+            "    \"ok\": true",
+        ]
+    );
+
+    tree.collapse_all();
+    assert_eq!(tree.rows().len(), 3);
+
+    tree.expand_all();
+    let rows = tree.rows();
+    let paths: Vec<&str> = rows.iter().map(|row| row.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "$",
+            "$.data",
+            "$.data.users",
+            "$.data.users[0]",
+            "$.data.users[0].id",
+            "$.data.users[1]",
+            "$.data.users[1].id",
+            "$.data[\"next page\"]",
+            "$.ok",
+        ]
+    );
+}
+
+#[test]
+fn test_json_tree_copy_value() {
+    let tree = JsonTree::parse(r#"{"name": "tome", "tags": [1]}"#.as_bytes()).unwrap();
+    let rows = tree.rows();
+
+    assert_eq!(rows[1].get_copy_value(), "tome");
+    assert_eq!(rows[2].get_copy_value(), "[\n  1\n]");
+}
+
+#[test]
+fn test_get_key_path() {
+    assert_eq!(get_key_path("$", "user_id"), "$.user_id");
+    assert_eq!(get_key_path("$", "2fa"), "$[\"2fa\"]");
+    assert_eq!(get_key_path("$.a", "x-y"), "$.a[\"x-y\"]");
+    assert_eq!(get_key_path("$", ""), "$[\"\"]");
+}
@@ -1,52 +1,116 @@
-use std::env;
-
 use clap::{Parser, Subcommand};
 
 mod app;
 mod app_themes;
+mod benchmark;
+mod binary;
 mod code_gen;
 mod compatibility;
 mod components;
+mod dynamic_variables;
+mod environment;
+mod filter_options;
 mod fs;
+mod graphql;
+mod history;
+mod json_query;
+mod json_schema;
+mod json_tree;
 mod messages;
 mod options;
+mod path_params;
+mod pretty;
 mod projects;
+mod query_params;
 mod requests;
+mod response_diff;
+mod response_files;
+mod response_lines;
+mod runner;
+mod scripting;
+mod snapshots;
 mod templates;
+mod templating;
 mod theme;
 mod themes;
+mod tls;
+mod vault;
 
-use crate::app::app;
+use crate::{
+    app::app,
+    environment::{parse_cli_variable, set_cli_variables},
+    response_files::ResponseSession,
+    runner::run_collection_from_cli,
+};
 
 #[derive(Debug, Subcommand)]
 enum Cmds {
     /// Does stuff
     Test,
+
+    /// Runs endpoints of a project once for every row of a data file
+    Run {
+        /// The name of the project
+        project: String,
+
+        /// A CSV file with a header row or a JSON array of objects, each row
+        /// is one iteration and its columns are variables
+        #[arg(long, value_name = "FILE")]
+        data: Option<String>,
+
+        /// An endpoint to run, repeat it to run several endpoints in order.
+        /// Every endpoint of the project runs when it is not set
+        #[arg(long = "endpoint", value_name = "NAME")]
+        endpoints: Vec<String>,
+
+        /// Approves the responses that do not match the snapshot of their
+        /// endpoint, and saves a snapshot for endpoints that have none
+        #[arg(long)]
+        update_snapshots: bool,
+    },
 }
 
 #[derive(Debug, Parser)]
 struct Cli {
+    /// Sets a variable for this run, overriding every other variable
+    #[arg(long = "var", global = true, value_name = "KEY=VALUE", value_parser = parse_cli_variable)]
+    vars: Vec<(String, String)>,
+
     #[command(subcommand)]
-    commands: Cmds,
+    commands: Option<Cmds>,
 }
 
 #[quit::main]
 fn main() -> anyhow::Result<()> {
-    let args = env::args().collect::<Vec<_>>();
+    let cli = Cli::parse();
 
-    match args.len() {
-        1 => {
-            app()?;
+    match cli.commands {
+        Some(Cmds::Test) => Ok(()),
+
+        Some(Cmds::Run {
+            project,
+            data,
+            endpoints,
+            update_snapshots,
+        }) => {
+            set_cli_variables(cli.vars.into_iter().collect());
+
+            let passed =
+                run_collection_from_cli(&project, data.as_deref(), &endpoints, update_snapshots)?;
+            if !passed {
+                quit::with_code(1);
+            }
 
             Ok(())
         }
 
-        _ => {
-            // let cli = Cli::parse();
+        None => {
+            set_cli_variables(cli.vars.into_iter().collect());
 
-            // match cli.commands {
-            //     Cmds::Test => Ok::<(), anyhow::Error>(()),
-            // };
+            // NOTE: The responses are removed when the session is dropped, also
+            // when quitting unwinds out of the app
+            let _response_session = ResponseSession::start();
+            app()?;
 
             Ok(())
         }
@@ -15,6 +15,10 @@ pub enum ConfirmAction {
 
     ConfirmDeleteHeader(ConfirmDetails<Header>),
     ConfirmationDeleteHeader(ConfirmationAnswer<Header>),
+
+    /// Replaces the request body with the file at this path
+    ConfirmReplaceRequestBody(ConfirmDetails<String>),
+    ConfirmationReplaceRequestBody(ConfirmationAnswer<String>),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
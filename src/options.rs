@@ -27,11 +27,23 @@ pub enum ButtonStyle {
     Rounded,
 }
 
+/// How response lines longer than the response view are shown
+#[derive(Clone, Copy, Default, Debug, Deserialize, Serialize, PartialEq)]
+pub enum ResponseWrap {
+    /// Lines are cut at the edge of the view and scrolled horizontally
+    #[default]
+    NoWrap,
+
+    /// Lines are reflowed to the width of the view
+    SoftWrap,
+}
+
 #[derive(Default, Debug, Deserialize, Serialize)]
 pub struct Options {
     pub syntax_theme: String,
     pub app_theme_name: String,
     pub button_style: Option<ButtonStyle>,
+    pub response_wrap: Option<ResponseWrap>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -87,6 +99,10 @@ pub fn get_button_caps() -> (&'static str, &'static str) {
     }
 }
 
+pub fn get_response_wrap() -> ResponseWrap {
+    get_options().response_wrap.unwrap_or_default()
+}
+
 pub fn get_options() -> Options {
     match get_app_dir("options") {
         Ok(mut options_dir) => {
@@ -0,0 +1,183 @@
+use anyhow::bail;
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::projects::PathParam;
+
+/// Characters escaped when a path param value is sent, a value can not add
+/// segments to the path so `/` is escaped too
+const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// The name of a path param segment written as `:name` or `{name}`, project
+/// variables written as `{{name}}` are not path params
+fn get_param_name(segment: &str) -> Option<&str> {
+    let name = match segment.strip_prefix(':') {
+        Some(name) => name,
+        None => segment.strip_prefix('{')?.strip_suffix('}')?,
+    };
+
+    is_param_name(name).then_some(name)
+}
+
+fn get_path(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or_default()
+}
+
+/// The names of the path params in the url, in the order they appear
+pub fn parse_path_params(url: &str) -> Vec<String> {
+    let mut names: Vec<String> = vec![];
+
+    get_path(url)
+        .split('/')
+        .filter_map(get_param_name)
+        .for_each(|name| {
+            if !names.iter().any(|existing| existing == name) {
+                names.push(name.to_string());
+            }
+        });
+
+    names
+}
+
+/// The rows of the path params table, one for each path param in the url
+/// with the value saved for it on the endpoint
+pub fn get_path_params_table(url: &str, values: &[PathParam]) -> Vec<PathParam> {
+    parse_path_params(url)
+        .into_iter()
+        .map(|name| {
+            let value = values
+                .iter()
+                .find(|param| param.name == name)
+                .map(|param| param.value.clone())
+                .unwrap_or_default();
+
+            PathParam { name, value }
+        })
+        .collect()
+}
+
+/// Renames the segments of a path param, keeping the style it was written in
+pub fn rename_path_param(url: &str, name: &str, new_name: &str) -> String {
+    let path = get_path(url);
+    let rest = &url[path.len()..];
+
+    let path = path
+        .split('/')
+        .map(|segment| match get_param_name(segment) {
+            Some(segment_name) if segment_name == name => match segment.starts_with(':') {
+                true => format!(":{new_name}"),
+                false => format!("{{{new_name}}}"),
+            },
+            _ => segment.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join("/");
+
+    format!("{path}{rest}")
+}
+
+/// Replaces the path params in a url with their encoded values, variables are
+/// replaced in each value before it is encoded
+pub fn replace_path_params(
+    url: &str,
+    values: &[PathParam],
+    replace_variables: impl Fn(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let path = get_path(url);
+    let rest = &url[path.len()..];
+
+    let mut segments: Vec<String> = vec![];
+    for segment in path.split('/') {
+        let Some(name) = get_param_name(segment) else {
+            segments.push(segment.to_string());
+            continue;
+        };
+
+        let value = values
+            .iter()
+            .find(|param| param.name == name)
+            .map(|param| param.value.as_str())
+            .unwrap_or_default();
+
+        if value.is_empty() {
+            bail!("The path param {name} does not have a value");
+        }
+
+        let value = replace_variables(value)?;
+        segments.push(utf8_percent_encode(&value, PATH_SEGMENT_ENCODE_SET).to_string());
+    }
+
+    Ok(format!("{}{rest}", segments.join("/")))
+}
+
+#[cfg(test)]
+fn path_param(name: &str, value: &str) -> PathParam {
+    PathParam {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_path_params() {
+    let names = parse_path_params("{{host}}/users/:id/orders/{orderId}/:id?sort={order}");
+
+    assert_eq!(names, vec!["id".to_string(), "orderId".to_string()]);
+    assert!(parse_path_params("https://example.com:8080/users/{{userId}}").is_empty());
+}
+
+#[test]
+fn test_get_path_params_table() {
+    let rows = get_path_params_table(
+        "/users/:id/orders/{orderId}",
+        &[path_param("orderId", "7"), path_param("stale", "1")],
+    );
+
+    assert_eq!(rows, vec![path_param("id", ""), path_param("orderId", "7")]);
+}
+
+#[test]
+fn test_rename_path_param() {
+    let url = rename_path_param("/users/:id/orders/{id}?id=1", "id", "userId");
+
+    assert_eq!(url, "/users/:userId/orders/{userId}?id=1");
+}
+
+#[test]
+fn test_replace_path_params() {
+    let replace = |input: &str| Ok(input.replace("{{user}}", "jane doe"));
+    let values = [path_param("id", "{{user}}"), path_param("orderId", "a/b")];
+
+    let url = replace_path_params(
+        "{{host}}/users/:id/orders/{orderId}?q=:id",
+        &values,
+        replace,
+    )
+    .unwrap();
+    assert_eq!(url, "{{host}}/users/jane%20doe/orders/a%2Fb?q=:id");
+
+    let missing = replace_path_params("/users/:id", &[], replace);
+    assert!(missing.is_err());
+}
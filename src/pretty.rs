@@ -0,0 +1,510 @@
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+
+use anyhow::bail;
+
+const INDENT: &str = "  ";
+
+/// HTML elements that never have a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// HTML elements whose content is not markup
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PrettyFormat {
+    Json,
+    Xml,
+    Html,
+}
+
+impl PrettyFormat {
+    /// The format for a response extension from its content type
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" => Some(PrettyFormat::Json),
+            "xml" => Some(PrettyFormat::Xml),
+            "html" | "xhtml" => Some(PrettyFormat::Html),
+            ext if ext.ends_with("+json") => Some(PrettyFormat::Json),
+            ext if ext.ends_with("+xml") => Some(PrettyFormat::Xml),
+            _ => None,
+        }
+    }
+}
+
+/// Formats a response while it is read, only a token is held in memory at a
+/// time so large responses can be formatted from one file into another.
+/// Fails when a JSON response is not valid, the writer can then be discarded.
+pub fn pretty_print(
+    format: PrettyFormat,
+    reader: impl Read,
+    writer: impl Write,
+) -> anyhow::Result<()> {
+    let reader = BufReader::new(reader);
+    let mut writer = BufWriter::new(writer);
+
+    match format {
+        PrettyFormat::Json => pretty_print_json(reader, &mut writer)?,
+        PrettyFormat::Xml => MarkupPrinter::new(&mut writer, false).print(reader)?,
+        PrettyFormat::Html => MarkupPrinter::new(&mut writer, true).print(reader)?,
+    }
+
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn write_line_break(writer: &mut impl Write, depth: usize) -> anyhow::Result<()> {
+    writer.write_all(b"\n")?;
+    writer.write_all(INDENT.repeat(depth).as_bytes())?;
+
+    Ok(())
+}
+
+fn pretty_print_json(reader: impl BufRead, writer: &mut impl Write) -> anyhow::Result<()> {
+    let mut brackets: Vec<u8> = vec![];
+    let mut in_string = false;
+    let mut escaped = false;
+
+    // NOTE: The line break after an opening bracket waits for the next byte,
+    // empty objects and arrays stay on one line
+    let mut open_bracket = false;
+
+    for byte in reader.bytes() {
+        let byte = byte?;
+
+        if in_string {
+            writer.write_all(&[byte])?;
+
+            match (escaped, byte) {
+                (true, _) => escaped = false,
+                (false, b'\\') => escaped = true,
+                (false, b'"') => in_string = false,
+                _ => {}
+            }
+
+            continue;
+        }
+
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+
+        let is_closing = matches!(byte, b'}' | b']');
+        if open_bracket && !is_closing {
+            write_line_break(writer, brackets.len())?;
+        }
+
+        match byte {
+            b'{' | b'[' => {
+                writer.write_all(&[byte])?;
+                brackets.push(byte);
+            }
+
+            b'}' | b']' => {
+                let opening = match byte {
+                    b'}' => b'{',
+                    _ => b'[',
+                };
+
+                if brackets.pop() != Some(opening) {
+                    bail!("The response is not valid JSON");
+                }
+
+                if !open_bracket {
+                    write_line_break(writer, brackets.len())?;
+                }
+
+                writer.write_all(&[byte])?;
+            }
+
+            b',' if !brackets.is_empty() => {
+                writer.write_all(b",")?;
+                write_line_break(writer, brackets.len())?;
+            }
+
+            b':' => writer.write_all(b": ")?,
+
+            b'"' => {
+                writer.write_all(b"\"")?;
+                in_string = true;
+            }
+
+            b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' | b'a'..=b'z' => {
+                writer.write_all(&[byte])?;
+            }
+
+            _ => bail!("The response is not valid JSON"),
+        }
+
+        open_bracket = matches!(byte, b'{' | b'[');
+    }
+
+    if in_string || !brackets.is_empty() {
+        bail!("The response is not valid JSON");
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+enum MarkupToken {
+    Text(String),
+    OpeningTag(String),
+    ClosingTag(String),
+
+    /// Self-closing and void elements, comments, declarations and
+    /// processing instructions
+    Standalone(String),
+}
+
+/// An opening tag that may be closed on the same line, with its text
+struct PendingElement {
+    tag: String,
+    text: Option<String>,
+}
+
+struct MarkupPrinter<'a, W: Write> {
+    writer: &'a mut W,
+    html: bool,
+    depth: usize,
+    pending: Option<PendingElement>,
+    lines_written: usize,
+}
+
+impl<'a, W: Write> MarkupPrinter<'a, W> {
+    fn new(writer: &'a mut W, html: bool) -> Self {
+        MarkupPrinter {
+            writer,
+            html,
+            depth: 0,
+            pending: None,
+            lines_written: 0,
+        }
+    }
+
+    fn print(mut self, mut reader: impl BufRead) -> anyhow::Result<()> {
+        let mut raw_text_element: Option<String> = None;
+
+        loop {
+            if let Some(element) = raw_text_element.take() {
+                let (text, closing_tag) = read_raw_text(&mut reader, &element)?;
+                self.handle(MarkupToken::Text(text))?;
+
+                match closing_tag {
+                    Some(tag) => self.handle(MarkupToken::ClosingTag(tag))?,
+                    None => break,
+                }
+
+                continue;
+            }
+
+            let mut text = vec![];
+            reader.read_until(b'<', &mut text)?;
+
+            let found_tag = text.last() == Some(&b'<');
+            if found_tag {
+                text.pop();
+            }
+
+            let text = String::from_utf8_lossy(&text).to_string();
+            self.handle(MarkupToken::Text(text))?;
+            if !found_tag {
+                break;
+            }
+
+            let tag = read_tag(&mut reader)?;
+            let token = self.get_tag_token(tag);
+
+            if let MarkupToken::OpeningTag(tag) = &token {
+                let name = get_tag_name(&tag[1..]).to_lowercase();
+                if self.html && RAW_TEXT_ELEMENTS.contains(&name.as_str()) {
+                    raw_text_element = Some(name);
+                }
+            }
+
+            self.handle(token)?;
+        }
+
+        self.flush_pending()?;
+
+        Ok(())
+    }
+
+    fn get_tag_token(&self, tag: String) -> MarkupToken {
+        let name = get_tag_name(&tag).to_lowercase();
+        let markup = format!("<{tag}>");
+
+        match tag.as_bytes().first() {
+            Some(b'/') => MarkupToken::ClosingTag(markup),
+            Some(b'!' | b'?') => MarkupToken::Standalone(markup),
+            _ if tag.ends_with('/') => MarkupToken::Standalone(markup),
+            _ if self.html && VOID_ELEMENTS.contains(&name.as_str()) => {
+                MarkupToken::Standalone(markup)
+            }
+            _ => MarkupToken::OpeningTag(markup),
+        }
+    }
+
+    fn handle(&mut self, token: MarkupToken) -> anyhow::Result<()> {
+        match token {
+            MarkupToken::Text(text) => {
+                let text = text.trim();
+                if text.is_empty() {
+                    return Ok(());
+                }
+
+                match &mut self.pending {
+                    Some(pending) if pending.text.is_none() && !text.contains('\n') => {
+                        pending.text = Some(text.to_string());
+                    }
+
+                    _ => {
+                        self.flush_pending()?;
+                        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                            self.write_line(line)?;
+                        }
+                    }
+                }
+            }
+
+            MarkupToken::OpeningTag(tag) => {
+                self.flush_pending()?;
+                self.pending = Some(PendingElement { tag, text: None });
+            }
+
+            MarkupToken::ClosingTag(tag) => match self.pending.take() {
+                Some(PendingElement { tag: opening, text }) => {
+                    let text = text.unwrap_or_default();
+                    self.write_line(&format!("{opening}{text}{tag}"))?;
+                }
+
+                None => {
+                    self.depth = self.depth.saturating_sub(1);
+                    self.write_line(&tag)?;
+                }
+            },
+
+            MarkupToken::Standalone(tag) => {
+                self.flush_pending()?;
+                self.write_line(&tag)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes an opening tag that was not closed on its line
+    fn flush_pending(&mut self) -> anyhow::Result<()> {
+        let Some(PendingElement { tag, text }) = self.pending.take() else {
+            return Ok(());
+        };
+
+        self.write_line(&tag)?;
+        self.depth += 1;
+
+        if let Some(text) = text {
+            self.write_line(&text)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_line(&mut self, line: &str) -> anyhow::Result<()> {
+        if self.lines_written > 0 {
+            self.writer.write_all(b"\n")?;
+        }
+
+        self.writer
+            .write_all(INDENT.repeat(self.depth).as_bytes())?;
+        self.writer.write_all(line.as_bytes())?;
+        self.lines_written += 1;
+
+        Ok(())
+    }
+}
+
+/// Reads the rest of a tag after its `<`, without the `>`. Quoted attribute
+/// values, comments and CDATA sections can contain `>`.
+fn read_tag(reader: &mut impl BufRead) -> anyhow::Result<String> {
+    let mut tag: Vec<u8> = vec![];
+
+    loop {
+        let read = reader.read_until(b'>', &mut tag)?;
+        let complete = tag.last() == Some(&b'>');
+        if complete {
+            tag.pop();
+        }
+
+        if read == 0 || !complete || is_tag_complete(&tag) {
+            break;
+        }
+
+        tag.push(b'>');
+    }
+
+    Ok(String::from_utf8_lossy(&tag).to_string())
+}
+
+/// Reads the content of a script or style element, which can contain `<`,
+/// until its closing tag. Returns the content and the closing tag.
+fn read_raw_text(
+    reader: &mut impl BufRead,
+    element: &str,
+) -> anyhow::Result<(String, Option<String>)> {
+    let mut raw_text: Vec<u8> = vec![];
+
+    loop {
+        let read = reader.read_until(b'>', &mut raw_text)?;
+        if read == 0 || raw_text.last() != Some(&b'>') {
+            return Ok((String::from_utf8_lossy(&raw_text).to_string(), None));
+        }
+
+        let Some(start) = raw_text.windows(2).rposition(|window| window == b"</") else {
+            continue;
+        };
+
+        let tag = String::from_utf8_lossy(&raw_text[start + 2..raw_text.len() - 1]);
+        if get_tag_name(&tag).eq_ignore_ascii_case(element) {
+            let closing_tag = format!("</{tag}>");
+            let text = String::from_utf8_lossy(&raw_text[..start]).to_string();
+
+            return Ok((text, Some(closing_tag)));
+        }
+    }
+}
+
+fn is_tag_complete(tag: &[u8]) -> bool {
+    if tag.starts_with(b"!--") {
+        return tag.len() >= 5 && tag.ends_with(b"--");
+    }
+
+    if tag.starts_with(b"![CDATA[") {
+        return tag.ends_with(b"]]");
+    }
+
+    let mut quote: Option<u8> = None;
+    for byte in tag {
+        quote = match (quote, byte) {
+            (None, b'"' | b'\'') => Some(*byte),
+            (Some(open), _) if open == *byte => None,
+            (quote, _) => quote,
+        };
+    }
+
+    quote.is_none()
+}
+
+/// The name of a tag without its `<`, closing tags keep their `/`
+fn get_tag_name(tag: &str) -> &str {
+    let end = tag
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| c.is_whitespace() || *c == '/' || *c == '>')
+        .map_or(tag.len(), |(index, _)| index);
+
+    &tag[..end]
+}
+
+#[cfg(test)]
+fn pretty_print_string(format: PrettyFormat, input: &str) -> anyhow::Result<String> {
+    let mut output: Vec<u8> = vec![];
+    pretty_print(format, input.as_bytes(), &mut output)?;
+
+    Ok(String::from_utf8(output)?)
+}
+
+#[test]
+fn test_pretty_print_json() {
+    let input = r#"{"name":"Tome","tags":["api", "tui"],"empty":{},"none":[],"nested":{"quote":"a \"{[,:]}\" b","n":-1.5e3,"ok":true}}"#;
+    let expected = r#"{
+  "name": "Tome",
+  "tags": [
+    "api",
+    "tui"
+  ],
+  "empty": {},
+  "none": [],
+  "nested": {
+    "quote": "a \"{[,:]}\" b",
+    "n": -1.5e3,
+    "ok": true
+  }
+}"#;
+
+    assert_eq!(
+        pretty_print_string(PrettyFormat::Json, input).unwrap(),
+        expected
+    );
+
+    assert_eq!(
+        pretty_print_string(PrettyFormat::Json, "[1,\n  2]").unwrap(),
+        "[\n  1,\n  2\n]"
+    );
+
+    assert!(pretty_print_string(PrettyFormat::Json, r#"{"a":1"#).is_err());
+    assert!(pretty_print_string(PrettyFormat::Json, r#"{"a":1]"#).is_err());
+    assert!(pretty_print_string(PrettyFormat::Json, "Not Found").is_err());
+}
+
+#[test]
+fn test_pretty_print_xml() {
+    let input = r#"<?xml version="1.0"?><users><!-- a > b --><user id="1" note="x > y"><name>Ada</name><tags/></user><![CDATA[<raw>]]></users>"#;
+    let expected = r#"<?xml version="1.0"?>
+<users>
+  <!-- a > b -->
+  <user id="1" note="x > y">
+    <name>Ada</name>
+    <tags/>
+  </user>
+  <![CDATA[<raw>]]>
+</users>"#;
+
+    assert_eq!(
+        pretty_print_string(PrettyFormat::Xml, input).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_pretty_print_html() {
+    let input = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><script>if (a < b) { go(); }</script></head><body><p>Hello <b>there</b></p><br></body></html>";
+    let expected = r#"<!DOCTYPE html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <script>if (a < b) { go(); }</script>
+  </head>
+  <body>
+    <p>
+      Hello
+      <b>there</b>
+    </p>
+    <br>
+  </body>
+</html>"#;
+
+    assert_eq!(
+        pretty_print_string(PrettyFormat::Html, input).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_pretty_format_from_extension() {
+    assert_eq!(
+        PrettyFormat::from_extension("json"),
+        Some(PrettyFormat::Json)
+    );
+    assert_eq!(
+        PrettyFormat::from_extension("vnd.api+json"),
+        Some(PrettyFormat::Json)
+    );
+    assert_eq!(
+        PrettyFormat::from_extension("html"),
+        Some(PrettyFormat::Html)
+    );
+    assert_eq!(PrettyFormat::from_extension("txt"), None);
+}
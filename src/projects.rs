@@ -1,4 +1,5 @@
 use anathema::state::{self, List, State, Value};
+use log::error;
 use serde::{Deserialize, Serialize};
 use std::{
     fs::{self, File},
@@ -7,7 +8,9 @@ use std::{
     path::PathBuf,
 };
 
-use crate::fs::get_app_dir;
+use uuid::Uuid;
+
+use crate::{fs::get_app_dir, snapshots::rename_snapshot, vault::Vault};
 
 // TODO: Fix the default project row color to the correct gray
 pub const DEFAULT_ROW_COLOR: &str = "#333333";
@@ -26,6 +29,18 @@ pub struct Project {
     pub row_color: Value<String>,
     pub row_fg_color: Value<String>,
     pub variable: Value<List<ProjectVariable>>,
+
+    #[state_ignore]
+    pub client_certificates: Vec<ClientCertificate>,
+
+    #[state_ignore]
+    pub dotenv_files: Vec<String>,
+
+    #[state_ignore]
+    pub pre_request_script: String,
+
+    #[state_ignore]
+    pub post_response_script: String,
 }
 
 #[derive(Default, Debug)]
@@ -61,6 +76,14 @@ pub struct ProjectVariable {
 
     pub row_fg_color: Value<String>,
     pub row_color: Value<String>,
+
+    #[state_ignore]
+    pub vault_id: Option<String>,
+
+    /// The private value could not be read from the vault, it is left in the
+    /// vault when the project is saved
+    #[state_ignore]
+    pub vault_locked: bool,
 }
 
 impl From<PersistedVariable> for ProjectVariable {
@@ -78,10 +101,12 @@ impl From<PersistedVariable> for ProjectVariable {
             name: persisted_variable.name.unwrap_or_default().into(),
             system: persisted_variable.system.unwrap_or_default().into(),
             disabled: persisted_variable.disabled.unwrap_or_default().into(),
+            vault_locked: is_vault_locked(&persisted_variable),
             private: persisted_variable.private.unwrap_or_default().into(),
 
             row_color: DEFAULT_ROW_COLOR.to_string().into(),
             row_fg_color: DEFAULT_ROW_COLOR.to_string().into(),
+            vault_id: persisted_variable.vault_id,
         }
     }
 }
@@ -94,6 +119,10 @@ impl Project {
             row_fg_color: DEFAULT_ROW_COLOR.to_string().into(),
             endpoints: List::empty(),
             variable: List::empty(),
+            client_certificates: vec![],
+            dotenv_files: vec![],
+            pre_request_script: String::new(),
+            post_response_script: String::new(),
         }
     }
 
@@ -101,6 +130,10 @@ impl Project {
         self.name.set(String::from(DEFAULT_PROJECT_NAME));
         self.row_color.set(DEFAULT_ROW_COLOR.to_string());
         self.row_fg_color.set(DEFAULT_ROW_COLOR.to_string());
+        self.client_certificates.clear();
+        self.dotenv_files.clear();
+        self.pre_request_script.clear();
+        self.post_response_script.clear();
 
         self.clear_endpoints();
         self.clear_variables();
@@ -112,6 +145,10 @@ impl Project {
         self.name.set(project.name.clone());
         self.update_endpoints(&project.endpoints);
         self.update_variables(&project.variable);
+        self.client_certificates = project.client_certificates.clone();
+        self.dotenv_files = project.dotenv_files.clone();
+        self.pre_request_script = project.pre_request_script.clone();
+        self.post_response_script = project.post_response_script.clone();
     }
 
     pub fn update_variables(&mut self, variables: &[PersistedVariable]) {
@@ -153,6 +190,30 @@ pub struct Endpoint {
     pub row_fg_color: Value<String>,
     pub body_mode: Value<String>,
     pub raw_type: Value<String>,
+    pub graphql_variables: Value<String>,
+    pub pre_request_script: Value<String>,
+    pub post_response_script: Value<String>,
+
+    #[state_ignore]
+    pub disabled_params: Vec<QueryParam>,
+
+    #[state_ignore]
+    pub path_params: Vec<PathParam>,
+
+    #[state_ignore]
+    pub variables: Vec<EndpointVariable>,
+
+    #[state_ignore]
+    pub response_query: String,
+
+    #[state_ignore]
+    pub diff_ignore_paths: Vec<String>,
+
+    #[state_ignore]
+    pub snapshot_ignore_headers: Vec<String>,
+
+    #[state_ignore]
+    pub response_schema: String,
 }
 
 impl Endpoint {
@@ -164,6 +225,16 @@ impl Endpoint {
             body: String::from("").into(),
             body_mode: String::from("raw").into(),
             raw_type: String::from("text").into(),
+            graphql_variables: String::from("").into(),
+            pre_request_script: String::from("").into(),
+            post_response_script: String::from("").into(),
+            disabled_params: vec![],
+            path_params: vec![],
+            variables: vec![],
+            response_query: String::new(),
+            diff_ignore_paths: vec![],
+            snapshot_ignore_headers: vec![],
+            response_schema: String::new(),
             headers: List::from_iter(get_default_headers()),
             row_color: DEFAULT_ROW_COLOR.to_string().into(),
             row_fg_color: DEFAULT_ROW_COLOR.to_string().into(),
@@ -180,6 +251,19 @@ impl Endpoint {
         self.method.set(endpoint.method.to_ref().to_string());
         self.body_mode.set(endpoint.body_mode.to_ref().to_string());
         self.raw_type.set(endpoint.raw_type.to_ref().to_string());
+        self.graphql_variables
+            .set(endpoint.graphql_variables.to_ref().to_string());
+        self.pre_request_script
+            .set(endpoint.pre_request_script.to_ref().to_string());
+        self.post_response_script
+            .set(endpoint.post_response_script.to_ref().to_string());
+        self.disabled_params = endpoint.disabled_params.clone();
+        self.path_params = endpoint.path_params.clone();
+        self.variables = endpoint.variables.clone();
+        self.response_query = endpoint.response_query.clone();
+        self.diff_ignore_paths = endpoint.diff_ignore_paths.clone();
+        self.snapshot_ignore_headers = endpoint.snapshot_ignore_headers.clone();
+        self.response_schema = endpoint.response_schema.clone();
     }
 
     fn add_headers(&mut self, headers: &Value<List<HeaderState>>) {
@@ -204,6 +288,16 @@ impl Endpoint {
         self.body.set(String::from(""));
         self.body_mode.set(String::from("raw"));
         self.raw_type.set(String::from("text"));
+        self.graphql_variables.set(String::from(""));
+        self.pre_request_script.set(String::from(""));
+        self.post_response_script.set(String::from(""));
+        self.disabled_params.clear();
+        self.path_params.clear();
+        self.variables.clear();
+        self.response_query.clear();
+        self.diff_ignore_paths.clear();
+        self.snapshot_ignore_headers.clear();
+        self.response_schema.clear();
         self.row_color.set(DEFAULT_ROW_COLOR.to_string());
         self.row_fg_color.set(DEFAULT_ROW_COLOR.to_string());
 
@@ -230,6 +324,16 @@ impl Endpoint {
             body: self.body.to_ref().to_string().into(),
             body_mode: self.body_mode.to_ref().to_string().into(),
             raw_type: self.raw_type.to_ref().to_string().into(),
+            graphql_variables: self.graphql_variables.to_ref().to_string().into(),
+            pre_request_script: self.pre_request_script.to_ref().to_string().into(),
+            post_response_script: self.post_response_script.to_ref().to_string().into(),
+            disabled_params: self.disabled_params.clone(),
+            path_params: self.path_params.clone(),
+            variables: self.variables.clone(),
+            response_query: self.response_query.clone(),
+            diff_ignore_paths: self.diff_ignore_paths.clone(),
+            snapshot_ignore_headers: self.snapshot_ignore_headers.clone(),
+            response_schema: self.response_schema.clone(),
             row_color: DEFAULT_ROW_COLOR.to_string().into(),
             row_fg_color: DEFAULT_ROW_COLOR.to_string().into(),
             headers: List::from_iter(headers),
@@ -261,6 +365,45 @@ pub struct PersistedProject {
     pub name: String,
     pub endpoints: Vec<PersistedEndpoint>,
     pub variable: Vec<PersistedVariable>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_certificates: Vec<ClientCertificate>,
+
+    /// Paths of `.env` files with variables for the project, later files
+    /// override earlier ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dotenv_files: Vec<String>,
+
+    /// Rhai script run before every request of the project, before the
+    /// endpoint's own script
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre_request_script: String,
+
+    /// Rhai script run after every response of the project, before the
+    /// endpoint's own script
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub post_response_script: String,
+}
+
+#[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum ClientCertificateFormat {
+    #[default]
+    Pkcs12,
+    Pem,
+}
+
+/// Client identity used for mutual TLS. Certificates without a host pattern
+/// apply to every request in the project.
+#[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct ClientCertificate {
+    /// Host or wildcard pattern, like `*.staging.example.com`
+    pub host: Option<String>,
+    pub format: ClientCertificateFormat,
+    pub cert_path: String,
+    /// PEM private key, defaults to the certificate file when not set
+    pub key_path: Option<String>,
+    /// Password for the PKCS#12 file or encrypted PEM key, supports `{{variables}}`
+    pub password: Option<String>,
 }
 
 #[derive(Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -282,6 +425,11 @@ pub struct PersistedVariable {
     pub name: Option<String>,
     pub system: Option<bool>,
     pub disabled: Option<bool>,
+
+    /// The id of the private value in the vault, private values are not saved
+    /// in project files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault_id: Option<String>,
 }
 
 impl From<&ProjectVariable> for PersistedVariable {
@@ -290,7 +438,7 @@ impl From<&ProjectVariable> for PersistedVariable {
             id: Some(project_variable.id.to_ref().to_string()),
             key: Some(project_variable.key.to_ref().to_string()),
             value: Some(project_variable.value.to_ref().to_string()),
-            private: Some(project_variable.private.to_ref().to_string()),
+            private: get_persisted_private(project_variable),
             r#type: Some(match *project_variable.r#type.to_ref() {
                 ProjectVariableType::String => VariableType::String,
                 ProjectVariableType::Boolean => VariableType::Boolean,
@@ -300,6 +448,7 @@ impl From<&ProjectVariable> for PersistedVariable {
             name: Some(project_variable.name.to_ref().to_string()),
             system: Some(*project_variable.system.to_ref()),
             disabled: Some(*project_variable.disabled.to_ref()),
+            vault_id: project_variable.vault_id.clone(),
         }
     }
 }
@@ -310,7 +459,7 @@ impl From<ProjectVariable> for PersistedVariable {
             id: Some(project_variable.id.to_ref().to_string()),
             key: Some(project_variable.key.to_ref().to_string()),
             value: Some(project_variable.value.to_ref().to_string()),
-            private: Some(project_variable.private.to_ref().to_string()),
+            private: get_persisted_private(project_variable),
             r#type: Some(match *project_variable.r#type.to_ref() {
                 ProjectVariableType::String => VariableType::String,
                 ProjectVariableType::Boolean => VariableType::Boolean,
@@ -320,10 +469,21 @@ impl From<ProjectVariable> for PersistedVariable {
             name: Some(project_variable.name.to_ref().to_string()),
             system: Some(*project_variable.system.to_ref()),
             disabled: Some(*project_variable.disabled.to_ref()),
+            vault_id: project_variable.vault_id.clone(),
         }
     }
 }
 
+#[derive(Debug, Default, State)]
+pub struct QueryParamState {
+    pub name: Value<String>,
+    pub value: Value<String>,
+    pub enabled: Value<bool>,
+    pub path: Value<bool>,
+    pub row_color: Value<String>,
+    pub row_fg_color: Value<String>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PersistedEndpoint {
     pub name: String,
@@ -333,6 +493,46 @@ pub struct PersistedEndpoint {
     pub body: String,
     pub body_mode: String,
     pub raw_type: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub graphql_variables: String,
+
+    /// Query params toggled off in the params table, enabled params live in the url
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_params: Vec<QueryParam>,
+
+    /// Values of the `:name` and `{name}` segments of the url
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_params: Vec<PathParam>,
+
+    /// Variables for this endpoint only, they override the project variables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variables: Vec<EndpointVariable>,
+
+    /// Rhai script run before the request is sent
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre_request_script: String,
+
+    /// Rhai script run after the response is received
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub post_response_script: String,
+
+    /// jq expression or JSON path shown instead of the whole response
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub response_query: String,
+
+    /// JSON paths left out when comparing responses, e.g. `$..updated_at`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diff_ignore_paths: Vec<String>,
+
+    /// Headers left out when comparing responses with the approved snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub snapshot_ignore_headers: Vec<String>,
+
+    /// JSON Schema the responses are validated with, inline JSON, a file or
+    /// a schema in a document, e.g. `openapi.json#/components/schemas/User`
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub response_schema: String,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -341,6 +541,24 @@ pub struct Header {
     pub value: String,
 }
 
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QueryParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PathParam {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EndpointVariable {
+    pub key: String,
+    pub value: String,
+}
+
 fn get_default_headers() -> Vec<HeaderState> {
     vec![
         HeaderState {
@@ -358,15 +576,114 @@ fn get_default_headers() -> Vec<HeaderState> {
     ]
 }
 
+/// Private values are None when they were not read from the vault
+fn is_vault_locked(variable: &PersistedVariable) -> bool {
+    variable.vault_id.is_some() && variable.private.is_none()
+}
+
+/// The private value of a variable, None when it was not read from the vault
+/// and was not changed so the vault keeps it
+fn get_persisted_private(variable: &ProjectVariable) -> Option<String> {
+    let private = variable.private.to_ref().to_string();
+
+    match variable.vault_locked && private.is_empty() {
+        true => None,
+        false => Some(private),
+    }
+}
+
 fn get_project(project_path: &PathBuf) -> anyhow::Result<PersistedProject> {
     let file = File::open(project_path)?;
     let reader = BufReader::new(file);
 
-    let persisted_project: PersistedProject = serde_json::from_reader(reader)?;
+    let mut persisted_project: PersistedProject = serde_json::from_reader(reader)?;
+    load_private_values(std::slice::from_mut(&mut persisted_project))?;
 
     Ok(persisted_project)
 }
 
+fn get_vault_ids(project: &PersistedProject) -> Vec<String> {
+    project
+        .variable
+        .iter()
+        .filter_map(|variable| variable.vault_id.clone())
+        .collect()
+}
+
+/// Reads the private values of the project variables from the vault
+fn load_private_values(projects: &mut [PersistedProject]) -> anyhow::Result<()> {
+    if projects
+        .iter()
+        .all(|project| get_vault_ids(project).is_empty())
+    {
+        return Ok(());
+    }
+
+    let vault = Vault::open()?;
+    for variable in projects
+        .iter_mut()
+        .flat_map(|project| project.variable.iter_mut())
+    {
+        if let Some(vault_id) = &variable.vault_id {
+            variable.private = Some(vault.get(vault_id)?.unwrap_or_default());
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the private values of the project variables into the vault so the
+/// project file only has their vault ids, values removed since the previous
+/// save are removed from the vault. Values that were not read from the vault
+/// keep their vault ids.
+fn store_private_values(
+    project: &PersistedProject,
+    previous_vault_ids: &[String],
+) -> anyhow::Result<PersistedProject> {
+    let mut project = project.clone();
+
+    let has_private_values = project.variable.iter().any(|variable| {
+        variable
+            .private
+            .as_ref()
+            .is_some_and(|private| !private.is_empty())
+    });
+    if !has_private_values && previous_vault_ids.is_empty() {
+        project
+            .variable
+            .iter_mut()
+            .for_each(|variable| variable.private = None);
+
+        return Ok(project);
+    }
+
+    let mut vault = Vault::open()?;
+    for variable in project.variable.iter_mut() {
+        match variable.private.take() {
+            None => {}
+
+            Some(private) if private.is_empty() => variable.vault_id = None,
+
+            Some(private) => {
+                let vault_id = variable
+                    .vault_id
+                    .get_or_insert_with(|| Uuid::new_v4().to_string());
+                vault.set(vault_id, &private)?;
+            }
+        }
+    }
+
+    let vault_ids = get_vault_ids(&project);
+    previous_vault_ids
+        .iter()
+        .filter(|vault_id| !vault_ids.contains(vault_id))
+        .for_each(|vault_id| vault.remove(vault_id));
+
+    vault.save()?;
+
+    Ok(project)
+}
+
 pub fn rename_endpoint(
     project_name: &str,
     endpoint: &PersistedEndpoint,
@@ -381,6 +698,7 @@ pub fn rename_endpoint(
     project_dir.push(format!("{}.project", project_name));
 
     let mut persisted_project = get_project(&project_dir)?;
+    rename_snapshot(project_name, &endpoint.name, new_name)?;
     delete_project(&persisted_project)?;
 
     let mut endpoints: Vec<PersistedEndpoint> = persisted_project
@@ -460,26 +778,62 @@ pub fn delete_project(project: &PersistedProject) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Sets the value of a project variable, adding the variable when it does
+/// not exist. Variables with a private value keep the new value private.
+pub fn set_project_variable(project: &mut PersistedProject, key: &str, value: &str) {
+    let existing_variable = project
+        .variable
+        .iter_mut()
+        .find(|variable| variable.key.as_deref() == Some(key));
+
+    match existing_variable {
+        Some(variable) => match variable.private.as_ref().is_some_and(|p| !p.is_empty()) {
+            true => variable.private = Some(value.to_string()),
+            false => variable.value = Some(value.to_string()),
+        },
+
+        None => project.variable.push(PersistedVariable {
+            id: None,
+            key: Some(key.to_string()),
+            value: Some(value.to_string()),
+            private: Some(String::new()),
+            r#type: Some(VariableType::String),
+            name: Some(key.to_string()),
+            system: Some(false),
+            disabled: Some(false),
+            vault_id: None,
+        }),
+    }
+}
+
 pub fn save_project(project: &PersistedProject) -> anyhow::Result<()> {
     if project.name.trim() == "" {
         return Err(anyhow::Error::msg("Project must have name"));
     }
 
-    let serialization_result = serde_json::to_string(&project);
-
-    if serialization_result.is_err() {
-        return Err(anyhow::Error::msg("Unable to serialize project"));
-    }
-
     let dir_result = get_app_dir("projects");
     if dir_result.is_err() {
         return Err(anyhow::Error::msg("Unable to access projects directory"));
     }
 
     let mut project_dir = dir_result.unwrap();
-    let serialized_project = serialization_result.unwrap();
     project_dir.push(format!("{}.project", project.name));
 
+    let previous_vault_ids: Vec<String> = fs::read_to_string(&project_dir)
+        .ok()
+        .and_then(|content| serde_json::from_str::<PersistedProject>(&content).ok())
+        .map(|previous_project| get_vault_ids(&previous_project))
+        .unwrap_or_default();
+
+    let project = store_private_values(project, &previous_vault_ids)?;
+    let serialization_result = serde_json::to_string(&project);
+
+    if serialization_result.is_err() {
+        return Err(anyhow::Error::msg("Unable to serialize project"));
+    }
+
+    let serialized_project = serialization_result.unwrap();
+
     let write_result = fs::write(project_dir, serialized_project);
     if write_result.is_err() {
         let write_error = write_result.unwrap_err();
@@ -500,11 +854,18 @@ pub fn get_projects() -> anyhow::Result<Vec<PersistedProject>> {
 
     let read_dir = fs::read_dir(project_dir)?;
 
-    Ok(read_dir
+    let mut projects = read_dir
         .flatten()
         .flat_map(|entry| fs::read_to_string(entry.path()))
         .flat_map(|content| serde_json::from_str::<PersistedProject>(&content))
-        .collect::<Vec<PersistedProject>>())
+        .collect::<Vec<PersistedProject>>();
+
+    // The projects still load without their private values when the vault is locked
+    if let Err(load_error) = load_private_values(&mut projects) {
+        error!("Could not load private variable values: {load_error}");
+    }
+
+    Ok(projects)
 }
 
 #[allow(unused)]
@@ -533,6 +894,16 @@ impl From<&Endpoint> for PersistedEndpoint {
             body: endpoint.body.to_ref().to_string(),
             body_mode: endpoint.body_mode.to_ref().to_string(),
             raw_type: endpoint.raw_type.to_ref().to_string(),
+            graphql_variables: endpoint.graphql_variables.to_ref().to_string(),
+            pre_request_script: endpoint.pre_request_script.to_ref().to_string(),
+            post_response_script: endpoint.post_response_script.to_ref().to_string(),
+            disabled_params: endpoint.disabled_params.clone(),
+            path_params: endpoint.path_params.clone(),
+            variables: endpoint.variables.clone(),
+            response_query: endpoint.response_query.clone(),
+            diff_ignore_paths: endpoint.diff_ignore_paths.clone(),
+            snapshot_ignore_headers: endpoint.snapshot_ignore_headers.clone(),
+            response_schema: endpoint.response_schema.clone(),
             headers,
         }
     }
@@ -568,7 +939,8 @@ impl From<&Project> for PersistedProject {
                 name: Some(pv.to_ref().name.to_ref().to_string()),
                 system: Some(*pv.to_ref().system.to_ref()),
                 disabled: Some(*pv.to_ref().disabled.to_ref()),
-                private: Some(pv.to_ref().private.to_ref().to_string()),
+                private: get_persisted_private(&pv.to_ref()),
+                vault_id: pv.to_ref().vault_id.clone(),
             })
             .collect();
 
@@ -576,6 +948,10 @@ impl From<&Project> for PersistedProject {
             name,
             endpoints,
             variable,
+            client_certificates: project.client_certificates.clone(),
+            dotenv_files: project.dotenv_files.clone(),
+            pre_request_script: project.pre_request_script.clone(),
+            post_response_script: project.post_response_script.clone(),
         }
     }
 }
@@ -620,6 +996,8 @@ impl From<&PersistedProject> for Project {
                 private: pv.private.clone().unwrap_or_default().into(),
                 row_color: "".to_string().into(),
                 row_fg_color: "".to_string().into(),
+                vault_id: pv.vault_id.clone(),
+                vault_locked: is_vault_locked(pv),
             })
             .collect();
 
@@ -629,6 +1007,10 @@ impl From<&PersistedProject> for Project {
             row_fg_color: DEFAULT_ROW_COLOR.to_string().into(),
             endpoints,
             variable,
+            client_certificates: persisted_project.client_certificates.clone(),
+            dotenv_files: persisted_project.dotenv_files.clone(),
+            pre_request_script: persisted_project.pre_request_script.clone(),
+            post_response_script: persisted_project.post_response_script.clone(),
         }
     }
 }
@@ -647,6 +1029,16 @@ impl From<&PersistedEndpoint> for Endpoint {
             body: persisted_endpoint.body.clone().into(),
             body_mode: persisted_endpoint.body_mode.clone().into(),
             raw_type: persisted_endpoint.raw_type.clone().into(),
+            graphql_variables: persisted_endpoint.graphql_variables.clone().into(),
+            pre_request_script: persisted_endpoint.pre_request_script.clone().into(),
+            post_response_script: persisted_endpoint.post_response_script.clone().into(),
+            disabled_params: persisted_endpoint.disabled_params.clone(),
+            path_params: persisted_endpoint.path_params.clone(),
+            variables: persisted_endpoint.variables.clone(),
+            response_query: persisted_endpoint.response_query.clone(),
+            diff_ignore_paths: persisted_endpoint.diff_ignore_paths.clone(),
+            snapshot_ignore_headers: persisted_endpoint.snapshot_ignore_headers.clone(),
+            response_schema: persisted_endpoint.response_schema.clone(),
             url: persisted_endpoint.url.clone().into(),
             method: persisted_endpoint.method.clone().into(),
             row_color: DEFAULT_ROW_COLOR.to_string().into(),
@@ -666,3 +1058,29 @@ impl From<&Header> for HeaderState {
         }
     }
 }
+
+impl From<(&QueryParam, bool)> for QueryParamState {
+    fn from((param, enabled): (&QueryParam, bool)) -> Self {
+        QueryParamState {
+            name: param.name.clone().into(),
+            value: param.value.clone().into(),
+            enabled: enabled.into(),
+            path: false.into(),
+            row_color: "".to_string().into(),
+            row_fg_color: "".to_string().into(),
+        }
+    }
+}
+
+impl From<&PathParam> for QueryParamState {
+    fn from(param: &PathParam) -> Self {
+        QueryParamState {
+            name: param.name.clone().into(),
+            value: param.value.clone().into(),
+            enabled: true.into(),
+            path: true.into(),
+            row_color: "".to_string().into(),
+            row_fg_color: "".to_string().into(),
+        }
+    }
+}
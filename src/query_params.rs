@@ -0,0 +1,275 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::{
+    path_params::replace_path_params,
+    projects::{PathParam, QueryParam},
+};
+
+/// Characters escaped when a query param is sent. Params are decoded before
+/// they are sent, so `+`, `&`, `=` and `#` in a value are its own text and
+/// are escaped. A `+` typed in the url was decoded as a space.
+const QUERY_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Characters escaped when a query param is written back to the url input,
+/// only what would otherwise break parsing the url back into params
+const QUERY_DISPLAY_SET: &AsciiSet = &CONTROLS.add(b'#').add(b'%').add(b'&').add(b'+').add(b'=');
+
+/// Splits a url into the part before the query, the query and the fragment
+pub fn split_url(url: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (url, fragment) = match url.split_once('#') {
+        Some((url, fragment)) => (url, Some(fragment)),
+        None => (url, None),
+    };
+
+    match url.split_once('?') {
+        Some((base, query)) => (base, Some(query), fragment),
+        None => (url, None, fragment),
+    }
+}
+
+pub fn parse_query_params(url: &str) -> Vec<QueryParam> {
+    let (_, query, _) = split_url(url);
+
+    query.map(parse_query).unwrap_or_default()
+}
+
+fn parse_query(query: &str) -> Vec<QueryParam> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            QueryParam {
+                name: decode(name),
+                value: decode(value),
+            }
+        })
+        .collect()
+}
+
+/// Decodes a query param the way forms are encoded, a `+` is a space
+fn decode(value: &str) -> String {
+    percent_decode_str(&value.replace('+', " "))
+        .decode_utf8_lossy()
+        .to_string()
+}
+
+/// Replaces the query of the url with the given params, keeping the fragment
+pub fn set_query_params(url: &str, params: &[QueryParam]) -> String {
+    let (base, _, fragment) = split_url(url);
+
+    let mut url = base.to_string();
+
+    if !params.is_empty() {
+        let query = params
+            .iter()
+            .map(|param| match param.value.is_empty() {
+                true => utf8_percent_encode(&param.name, QUERY_DISPLAY_SET).to_string(),
+                false => format!(
+                    "{}={}",
+                    utf8_percent_encode(&param.name, QUERY_DISPLAY_SET),
+                    utf8_percent_encode(&param.value, QUERY_DISPLAY_SET)
+                ),
+            })
+            .collect::<Vec<String>>()
+            .join("&");
+
+        url.push('?');
+        url.push_str(&query);
+    }
+
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+
+    url
+}
+
+/// The rows of the params table, the params in the url are enabled and
+/// are followed by the disabled params saved with the endpoint
+pub fn get_params_table(url: &str, disabled_params: &[QueryParam]) -> Vec<(QueryParam, bool)> {
+    parse_query_params(url)
+        .into_iter()
+        .map(|param| (param, true))
+        .chain(disabled_params.iter().map(|param| (param.clone(), false)))
+        .collect()
+}
+
+/// Writes the enabled rows of the params table back into the url, returns
+/// the new url and the disabled params
+pub fn apply_params_table(url: &str, rows: &[(QueryParam, bool)]) -> (String, Vec<QueryParam>) {
+    let (enabled, disabled): (Vec<_>, Vec<_>) = rows.iter().partition(|(_, enabled)| *enabled);
+
+    let enabled: Vec<QueryParam> = enabled
+        .into_iter()
+        .map(|(param, _)| param.clone())
+        .collect();
+    let disabled: Vec<QueryParam> = disabled
+        .into_iter()
+        .map(|(param, _)| param.clone())
+        .collect();
+
+    (set_query_params(url, &enabled), disabled)
+}
+
+/// Builds the url that is sent, variables are replaced in the base url, the
+/// path params and in each query param before they are percent-encoded
+pub fn encode_url(
+    url: &str,
+    path_params: &[PathParam],
+    replace_variables: impl Fn(&str) -> anyhow::Result<String>,
+) -> anyhow::Result<String> {
+    let (base, query, _) = split_url(url);
+
+    let base = replace_path_params(base, path_params, &replace_variables)?;
+    let base = replace_variables(&base)?;
+
+    // A variable in the base url can bring its own query string along
+    let (base, base_query) = match base.split_once('?') {
+        Some((base, base_query)) => (base.to_string(), Some(base_query.to_string())),
+        None => (base, None),
+    };
+
+    let mut pairs: Vec<String> = base_query
+        .into_iter()
+        .filter(|base_query| !base_query.is_empty())
+        .collect();
+
+    for param in query.map(parse_query).unwrap_or_default() {
+        let name = replace_variables(&param.name)?;
+        let value = replace_variables(&param.value)?;
+
+        pairs.push(match value.is_empty() {
+            true => utf8_percent_encode(&name, QUERY_ENCODE_SET).to_string(),
+            false => format!(
+                "{}={}",
+                utf8_percent_encode(&name, QUERY_ENCODE_SET),
+                utf8_percent_encode(&value, QUERY_ENCODE_SET)
+            ),
+        });
+    }
+
+    match pairs.is_empty() {
+        true => Ok(base),
+        false => Ok(format!("{base}?{}", pairs.join("&"))),
+    }
+}
+
+#[cfg(test)]
+fn param(name: &str, value: &str) -> QueryParam {
+    QueryParam {
+        name: name.to_string(),
+        value: value.to_string(),
+    }
+}
+
+#[test]
+fn test_parse_query_params() {
+    let params =
+        parse_query_params("https://example.com/users?page=2&q=tome%20tui&name=a+b%2Bc&flag#top");
+
+    assert_eq!(
+        params,
+        vec![
+            param("page", "2"),
+            param("q", "tome tui"),
+            param("name", "a b+c"),
+            param("flag", "")
+        ]
+    );
+}
+
+#[test]
+fn test_set_query_params() {
+    let url = set_query_params(
+        "https://example.com/users?page=1#top",
+        &[param("page", "2"), param("q", "a&b+c"), param("flag", "")],
+    );
+
+    assert_eq!(url, "https://example.com/users?page=2&q=a%26b%2Bc&flag#top");
+    assert_eq!(
+        parse_query_params(&url),
+        vec![param("page", "2"), param("q", "a&b+c"), param("flag", "")]
+    );
+
+    let url = set_query_params("https://example.com/users?page=1", &[]);
+    assert_eq!(url, "https://example.com/users");
+}
+
+#[test]
+fn test_apply_params_table() {
+    let rows = get_params_table("{{host}}/users?page=2", &[param("debug", "true")]);
+    assert_eq!(
+        rows,
+        vec![(param("page", "2"), true), (param("debug", "true"), false)]
+    );
+
+    let rows = vec![(param("page", "2"), false), (param("debug", "true"), true)];
+    let (url, disabled) = apply_params_table("{{host}}/users?page=2", &rows);
+
+    assert_eq!(url, "{{host}}/users?debug=true");
+    assert_eq!(disabled, vec![param("page", "2")]);
+}
+
+#[test]
+fn test_encode_url() {
+    let replace = |input: &str| {
+        Ok(input
+            .replace("{{users}}", "https://example.com/users?key=abc")
+            .replace("{{name}}", "Tome & TUI")
+            .replace("{{sum}}", "1+2=3#"))
+    };
+
+    let url = encode_url(
+        "{{users}}?q={{name}}&tags=a b&sum={{sum}}&plus=a%2Bb&space=a+b&flag#top",
+        &[],
+        replace,
+    )
+    .unwrap();
+
+    assert_eq!(
+        url,
+        "https://example.com/users?key=abc&q=Tome%20%26%20TUI&tags=a%20b&sum=1%2B2%3D3%23&plus=a%2Bb&space=a%20b&flag"
+    );
+
+    let (_, query, _) = split_url(&url);
+    assert_eq!(
+        parse_query(query.unwrap()),
+        vec![
+            param("key", "abc"),
+            param("q", "Tome & TUI"),
+            param("tags", "a b"),
+            param("sum", "1+2=3#"),
+            param("plus", "a+b"),
+            param("space", "a b"),
+            param("flag", "")
+        ]
+    );
+
+    let path_params = [PathParam {
+        name: "id".to_string(),
+        value: "{{name}}".to_string(),
+    }];
+    let url = encode_url("https://example.com/users/:id?q=1", &path_params, replace).unwrap();
+
+    assert_eq!(url, "https://example.com/users/Tome%20&%20TUI?q=1");
+}
@@ -1,67 +1,64 @@
 use std::{
     collections::HashMap,
-    fs::{self, OpenOptions},
-    io::Write,
-    path::PathBuf,
+    fs::File,
+    io::{self, BufReader, Read},
+    path::Path,
     sync::Arc,
+    time::Instant,
 };
 
 use anathema::prelude::Context;
-use anyhow::bail;
+use anyhow::{anyhow, bail};
+use body::{
+    parse_binary_body, parse_file_reference, parse_form_fields, parse_form_value, FormValue,
+};
+use log::error;
+use metrics::{
+    format_size, get_form_size, ConnectionTimer, RequestSize, RequestTimer, ResponseBody,
+    ResponseHead, ResponseMetrics, TimedTlsConnector,
+};
 use mime::Mime;
-use ureq::Response;
+use ureq::{Agent, AgentBuilder, Request, Response, Transport};
 use ureq_multipart::MultipartBuilder;
 
 use crate::{
+    benchmark::format_latency,
+    binary::{
+        get_response_content_type, get_response_text, write_decoded_text, write_hex_dump,
+        ResponseEncoding,
+    },
     components::{
         dashboard::{DashboardComponent, DashboardDisplay, DashboardState},
         floating_windows::FloatingWindow,
         response_renderer::ResponseRendererMessages,
         send_message,
     },
-    projects::{HeaderState, PersistedEndpoint, PersistedProject},
+    dynamic_variables::add_dynamic_variables,
+    environment::{get_cli_variables, get_environment_variables, load_dotenv_files},
+    graphql::{
+        build_graphql_body, is_graphql_mode, parse_introspection, save_schema, GraphQLSchema,
+        INTROSPECTION_QUERY,
+    },
+    history::{add_history_entry, HistoryEntry},
+    json_schema::get_schema_failures,
+    projects::{set_project_variable, Header, HeaderState, PersistedEndpoint, PersistedProject},
+    query_params::encode_url,
+    response_files::ResponseFiles,
+    scripting::{
+        run_post_response_script, run_pre_request_script, ScriptOutput, ScriptRequest,
+        ScriptResponse,
+    },
+    snapshots::{check_snapshot, find_snapshot_mismatch, Snapshot},
+    templating::{check_templates, render, render_json, render_segments},
+    tls::{find_client_certificate, load_identity},
 };
 
-fn replace_variables(
-    mut input: &str,
-    variables: &HashMap<String, String>,
-) -> anyhow::Result<String> {
-    let (left, right) = input.as_bytes().windows(2).fold((0, 0), |mut a, s| {
-        if s == b"{{" {
-            a.0 += 1
-        }
-
-        if s == b"}}" {
-            a.1 += 1
-        }
-
-        a
-    });
-
-    if left != right {
-        bail!("The opening and closing brackets are imbalanced");
-    }
-
-    let mut replaced = String::from(input);
+mod body;
+pub mod metrics;
+pub mod preview;
 
-    while let Some(mut start) = input.find("{{") {
-        if let Some(mut end) = input.find("}}") {
-            start += 2;
-
-            let variable = &input[start..end];
-
-            if let Some(value) = variables.get(variable) {
-                replaced = replaced.replace(&format!("{{{{{}}}}}", variable), value);
-            }
-
-            end += 2;
-            input = &input[end..];
-        } else {
-            break;
-        }
-    }
-
-    Ok(replaced)
+fn replace_variables(input: &str, variables: &HashMap<String, String>) -> anyhow::Result<String> {
+    render(input, variables)
 }
 
 #[test]
@@ -135,112 +132,636 @@ pub fn do_request(
     dashboard: &mut DashboardComponent,
 ) -> anyhow::Result<()> {
     let project: PersistedProject = (&*state.project.to_ref()).into();
-    let variables = project
-        .variable
-        .iter()
-        .map(|variable| {
-            (
-                variable.key.clone().unwrap_or_default(),
-                variable
-                    .private
-                    .clone()
-                    .unwrap_or(variable.value.clone().unwrap_or_default()),
-            )
-        })
-        .collect::<HashMap<String, String>>();
-
     let endpoint: PersistedEndpoint = (&*state.endpoint.to_ref()).into();
 
-    let content_type = get_content_type(&endpoint);
-    let mut url = endpoint.url.clone();
+    let ResolvedRequest {
+        endpoint,
+        variables,
+        script_variables,
+    } = resolve_request(&project, &endpoint, &HashMap::new())?;
+    save_script_variables(state, &script_variables);
+
+    let private = uses_private_values(&project, &endpoint, &variables)?;
+    let SentRequest { response, timer } = send_endpoint(&project, &endpoint, &variables)?;
+    let script_response = match response {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => {
+            handle_response(response, timer, private, state, context, dashboard)?
+        }
+        Err(ureq::Error::Transport(transport_error)) => {
+            show_transport_error(&transport_error, state);
+            None
+        }
+    };
 
-    url = replace_variables(&url, &variables)?;
+    if let Some(script_response) = script_response {
+        let response = Snapshot::from(&script_response);
+        let snapshot_mismatch = find_snapshot_mismatch(&project.name, &endpoint, &response)?;
+        dashboard.last_response = Some(response.clone());
+
+        // NOTE: A snapshot mismatch is shown first, the schema violations are
+        // one key press away from the response body
+        if snapshot_mismatch.is_some() {
+            dashboard.show_response_diff(Some(response), state, context);
+        } else if !get_schema_failures(&endpoint.response_schema, &script_response.body).is_empty()
+        {
+            dashboard.show_response_validation(state, context);
+        }
 
-    let method = endpoint.method.clone();
-    let headers = endpoint.headers;
+        let output = run_post_response_scripts(&project, &endpoint, &script_response, variables)?;
+        save_script_variables(state, &output.variables);
+        check_script_failures(&output.failures)?;
+    }
 
-    let agent = ureq::AgentBuilder::new()
-        .tls_connector(Arc::new(native_tls::TlsConnector::new()?))
-        .build();
+    Ok(())
+}
 
-    let mut request = agent.request(&method, &url);
-    for header_value in headers.iter() {
-        let header = header_value;
-        let mut header_name = header.name.to_string();
-        let mut header_value = header.value.to_string();
+/// Sends one request of a collection run, the variables the scripts set are
+/// added to the iteration variables so the next requests can use them.
+/// Violations of the response schema fail like assertions. The response is
+/// checked against the snapshot of the endpoint, or approved as its snapshot
+/// with `update_snapshots`. Returns the status code of the
+/// response.
+pub fn send_collection_request(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    iteration_variables: &mut HashMap<String, String>,
+    update_snapshots: bool,
+) -> anyhow::Result<u16> {
+    let ResolvedRequest {
+        endpoint,
+        variables,
+        script_variables,
+    } = resolve_request(project, endpoint, iteration_variables)?;
+    iteration_variables.extend(script_variables);
+
+    let script_response = match send_endpoint(project, &endpoint, &variables)?.response {
+        Ok(response) => get_script_response(response)?,
+        Err(ureq::Error::Status(_, response)) => get_script_response(response)?,
+        Err(ureq::Error::Transport(transport_error)) => {
+            bail!(get_transport_error_message(&transport_error))
+        }
+    };
 
-        header_name = replace_variables(&header_name, &variables)?;
-        header_value = replace_variables(&header_value, &variables)?;
+    let output = run_post_response_scripts(project, &endpoint, &script_response, variables)?;
+    iteration_variables.extend(output.variables);
 
-        // NOTE: Skip content-type header, this should be calculated based
-        // on the body mode and/or raw type
-        if header_name == "content-type" {
-            continue;
-        }
+    let mut failures = output.failures;
+    failures.extend(get_schema_failures(
+        &endpoint.response_schema,
+        &script_response.body,
+    ));
+    check_script_failures(&failures)?;
+    check_snapshot(&project.name, &endpoint, &script_response, update_snapshots)?;
 
-        request = request.set(&header_name, &header_value);
+    Ok(script_response.status)
+}
+
+/// The same request sent many times by a benchmark, it is resolved once and
+/// every request shares the connections of one agent
+pub struct BenchmarkRequest {
+    agent: Agent,
+    url: String,
+    endpoint: PersistedEndpoint,
+    variables: HashMap<String, String>,
+}
+
+impl BenchmarkRequest {
+    /// Resolves the endpoint the way the dashboard sends it. Scripts and
+    /// dynamic variables run once, so every request is the same.
+    pub fn new(
+        project: &PersistedProject,
+        endpoint: &PersistedEndpoint,
+        concurrency: usize,
+    ) -> anyhow::Result<Self> {
+        let ResolvedRequest {
+            endpoint,
+            variables,
+            ..
+        } = resolve_request(project, endpoint, &HashMap::new())?;
+
+        let url = get_url(&endpoint, &variables)?;
+        let agent = get_agent_builder(project, &url, &variables)?
+            .max_idle_connections_per_host(concurrency)
+            .build();
+
+        Ok(BenchmarkRequest {
+            agent,
+            url,
+            endpoint,
+            variables,
+        })
+    }
+
+    /// Sends the request and reads the whole response body, returns the
+    /// status code of the response
+    pub fn send(&self) -> anyhow::Result<u16> {
+        let (response, _) =
+            send_with_agent(&self.agent, &self.url, &self.endpoint, &self.variables)?;
+
+        let response = match response {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(ureq::Error::Transport(transport_error)) => {
+                bail!(get_transport_error_message(&transport_error))
+            }
+        };
+
+        let status = response.status();
+        io::copy(&mut response.into_reader(), &mut io::sink())?;
+
+        Ok(status)
     }
+}
+
+/// An endpoint after its pre-request scripts ran, with every variable it uses
+struct ResolvedRequest {
+    endpoint: PersistedEndpoint,
+    variables: HashMap<String, String>,
+
+    /// The variables the pre-request scripts set
+    script_variables: HashMap<String, String>,
+}
+
+/// Runs the pre-request scripts and resolves the variables of a request.
+/// The overrides replace every variable except `--var` arguments.
+fn resolve_request(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    overrides: &HashMap<String, String>,
+) -> anyhow::Result<ResolvedRequest> {
+    let mut endpoint = endpoint.clone();
+    let mut variables = get_request_variables(project, &endpoint)?;
+    variables.extend(overrides.clone());
+    variables.extend(get_cli_variables());
+
+    let script_variables = run_pre_request_scripts(project, &mut endpoint, &mut variables)?;
+
+    // Scripts can add dynamic variables to the request
+    add_dynamic_variables(&get_template_inputs(&endpoint), &mut variables);
+    check_templates(&get_template_inputs(&endpoint), &variables)?;
+
+    Ok(ResolvedRequest {
+        endpoint,
+        variables,
+        script_variables,
+    })
+}
+
+fn get_url(
+    endpoint: &PersistedEndpoint,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+    encode_url(&endpoint.url, &endpoint.path_params, |input| {
+        replace_variables(input, variables)
+    })
+}
+
+/// A sent request, responses with an error status are an `Err` of `response`
+struct SentRequest {
+    response: Result<Response, ureq::Error>,
+    timer: RequestTimer,
+}
+
+/// Sends the endpoint with its variables replaced on a new connection, the
+/// timer measures every phase of the request
+fn send_endpoint(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<SentRequest> {
+    let url = get_url(endpoint, variables)?;
+
+    let connection_timer = ConnectionTimer::default();
+    let tls_connector = TimedTlsConnector::new(
+        get_tls_connector(project, &url, variables)?,
+        connection_timer.clone(),
+    );
+    let agent = AgentBuilder::new()
+        .tls_connector(Arc::new(tls_connector))
+        .resolver(connection_timer.clone())
+        .build();
 
-    let response = match content_type {
+    let start = Instant::now();
+    let (response, request_size) = send_with_agent(&agent, &url, endpoint, variables)?;
+
+    Ok(SentRequest {
+        response,
+        timer: RequestTimer::new(start, connection_timer, request_size),
+    })
+}
+
+fn send_with_agent(
+    agent: &Agent,
+    url: &str,
+    endpoint: &PersistedEndpoint,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<(Result<Response, ureq::Error>, RequestSize)> {
+    let content_type = get_content_type(endpoint);
+    let request = agent.request(&endpoint.method, url);
+    let request = set_headers(request, &endpoint.headers, variables)?;
+
+    let sent = match content_type {
         Some(content_type) => match content_type.as_str() {
             "application/json"
             | "application/javascript"
             | "text/plain"
             | "text/html"
             | "text/xml" => {
-                let req_body = endpoint.body.clone();
+                let req_body = match is_graphql_mode(&endpoint.body_mode) {
+                    true => {
+                        // GraphQL strings are escaped the same way as JSON strings
+                        let query = render_json(&endpoint.body, &variables)?;
+                        let graphql_variables =
+                            render_json(&endpoint.graphql_variables, &variables)?;
+
+                        build_graphql_body(&query, &graphql_variables)?
+                    }
+                    false => match content_type.as_str() {
+                        "application/json" => render_json(&endpoint.body, &variables)?,
+                        _ => replace_variables(&endpoint.body, &variables)?,
+                    },
+                };
 
-                request.send_string(&req_body)
+                let request = request.set("Content-Type", &content_type);
+                let request_size = RequestSize::new(&request, req_body.len());
+
+                (request.send_string(&req_body), request_size)
             }
 
-            "x-www-form-urlencoded" => {
-                let form: Vec<(&str, &str)> = endpoint
-                    .body
-                    .split("\n")
-                    .filter_map(|entry| entry.split_once("="))
+            "application/x-www-form-urlencoded" => {
+                let form = get_form_fields(&endpoint.body, &variables)?;
+                let form: Vec<(&str, &str)> = form
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.text()))
                     .collect();
 
-                request.send_form(&form)
+                let request_size = RequestSize::new(&request, get_form_size(&form));
+
+                (request.send_form(&form), request_size)
             }
 
             "multipart/form-data" => {
-                let form: Vec<(&str, &str)> = endpoint
-                    .body
-                    .split("\n")
-                    .filter_map(|entry| entry.split_once("="))
-                    .collect();
+                let form = get_form_fields(&endpoint.body, &variables)?;
 
-                // TODO: Come back to this to remove/handle the .expect() call
                 let mut builder = MultipartBuilder::new();
-                builder = form.into_iter().fold(builder, |builder, (k, v)| {
-                    builder
-                        .add_text(k, v)
-                        .expect("Should not error while adding headers?")
-                });
-
-                let (content_type, data) = builder.finish().unwrap();
-                request.set("Content-Type", &content_type).send_bytes(&data)
+                for (name, value) in &form {
+                    let file_reference = match value {
+                        FormValue::File(file) => parse_file_reference(file),
+                        FormValue::Text(_) => None,
+                    };
+
+                    builder = match file_reference {
+                        Some(file_reference) => {
+                            let path = file_reference.path.to_string_lossy().to_string();
+                            let mut file = File::open(&file_reference.path).map_err(|error| {
+                                anyhow!("Could not open {path} for form field {name}: {error}")
+                            })?;
+
+                            let file_name = file_reference.file_name();
+                            let mime: Option<Mime> = file_reference.content_type().parse().ok();
+
+                            builder.add_stream(&mut file, name, file_name.as_deref(), mime)?
+                        }
+
+                        None => builder.add_text(name, value.text())?,
+                    };
+                }
+
+                let (content_type, data) = builder.finish()?;
+                let request = request.set("Content-Type", &content_type);
+                let request_size = RequestSize::new(&request, data.len());
+
+                (request.send_bytes(&data), request_size)
+            }
+
+            "application/octet-stream" => {
+                let body = replace_variables(&endpoint.body, &variables)?;
+                let Some(file_reference) = parse_binary_body(&body) else {
+                    bail!("Choose a file to send as the binary request body");
+                };
+
+                let path = file_reference.path.to_string_lossy().to_string();
+                let file = File::open(&file_reference.path).map_err(|error| {
+                    anyhow!("Could not open {path} for the request body: {error}")
+                })?;
+
+                let file_size = file.metadata()?.len() as usize;
+                let request = request.set("Content-Type", &file_reference.content_type());
+                let request_size = RequestSize::new(&request, file_size);
+
+                (request.send(file), request_size)
             }
 
-            _ => request.send_string(""),
+            _ => send_without_body(request),
         },
 
-        None => request.send_string(""),
+        None => send_without_body(request),
     };
 
-    match response {
-        Ok(response) => handle_successful_response(response, state, context, dashboard),
-        Err(error) => handle_error_response(error, state, context, dashboard),
-    }?;
+    Ok(sent)
+}
 
-    Ok(())
+fn send_without_body(request: Request) -> (Result<Response, ureq::Error>, RequestSize) {
+    let request_size = RequestSize::new(&request, 0);
+
+    (request.send_string(""), request_size)
+}
+
+/// Runs the project and then the endpoint pre-request script, the scripts can
+/// change the request and set variables. Returns the variables the scripts set.
+fn run_pre_request_scripts(
+    project: &PersistedProject,
+    endpoint: &mut PersistedEndpoint,
+    variables: &mut HashMap<String, String>,
+) -> anyhow::Result<HashMap<String, String>> {
+    let scripts = [
+        project.pre_request_script.clone(),
+        endpoint.pre_request_script.clone(),
+    ];
+
+    let mut request = ScriptRequest {
+        method: endpoint.method.clone(),
+        url: endpoint.url.clone(),
+        headers: endpoint.headers.clone(),
+        body: endpoint.body.clone(),
+    };
+
+    let mut script_variables = HashMap::new();
+    for script in scripts.iter().filter(|script| !script.trim().is_empty()) {
+        let output = run_pre_request_script(script, &mut request, variables)?;
+        check_script_failures(&output.failures)?;
+
+        variables.extend(output.variables.clone());
+        script_variables.extend(output.variables);
+    }
+
+    endpoint.method = request.method;
+    endpoint.url = request.url;
+    endpoint.headers = request.headers;
+    endpoint.body = request.body;
+
+    Ok(script_variables)
+}
+
+/// Runs the project and then the endpoint post-response script. Returns the
+/// variables the scripts set and the assertions that failed.
+fn run_post_response_scripts(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    response: &ScriptResponse,
+    mut variables: HashMap<String, String>,
+) -> anyhow::Result<ScriptOutput> {
+    let scripts = [
+        &project.post_response_script,
+        &endpoint.post_response_script,
+    ];
+
+    let mut script_output = ScriptOutput::default();
+    for script in scripts.iter().filter(|script| !script.trim().is_empty()) {
+        let output = run_post_response_script(script, response, &variables)?;
+
+        variables.extend(output.variables.clone());
+        script_output.variables.extend(output.variables);
+        script_output.failures.extend(output.failures);
+    }
+
+    Ok(script_output)
+}
+
+fn check_script_failures(failures: &[String]) -> anyhow::Result<()> {
+    match failures.len() {
+        0 => Ok(()),
+        1 => bail!("Assertion failed: {}", failures[0]),
+        count => bail!("{count} assertions failed:\n{}", failures.join("\n")),
+    }
+}
+
+/// Saves the variables set by scripts as project variables, so the next
+/// requests can use them
+fn save_script_variables(state: &mut DashboardState, variables: &HashMap<String, String>) {
+    if variables.is_empty() {
+        return;
+    }
+
+    let mut project: PersistedProject = (&*state.project.to_ref()).into();
+    variables
+        .iter()
+        .for_each(|(key, value)| set_project_variable(&mut project, key, value));
+
+    state.project.set((&project).into());
+}
+
+/// Fetches the schema of a GraphQL endpoint and caches it for query completion
+pub fn introspect_graphql_schema(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+) -> anyhow::Result<GraphQLSchema> {
+    let variables = get_request_variables(project, endpoint)?;
+    let url = get_url(endpoint, &variables)?;
+
+    let agent = get_agent_builder(project, &url, &variables)?.build();
+    let request = set_headers(agent.post(&url), &endpoint.headers, &variables)?;
+
+    let body = build_graphql_body(INTROSPECTION_QUERY, "")?;
+    let response = request
+        .set("Content-Type", "application/json")
+        .send_string(&body);
+
+    let mut response = match response {
+        Ok(response) => ResponseBody::new(response),
+        Err(ureq::Error::Status(status, _)) => {
+            bail!("Schema introspection failed with status {status}")
+        }
+        Err(error) => bail!("Schema introspection failed: {error}"),
+    };
+
+    let mut response_body = String::new();
+    response.read_to_string(&mut response_body)?;
+
+    let schema = parse_introspection(&response_body)?;
+    save_schema(&endpoint.url, &response_body)?;
+
+    Ok(schema)
+}
+
+fn get_project_variables(project: &PersistedProject) -> HashMap<String, String> {
+    project
+        .variable
+        .iter()
+        .map(|variable| {
+            (
+                variable.key.clone().unwrap_or_default(),
+                variable
+                    .private
+                    .clone()
+                    .unwrap_or(variable.value.clone().unwrap_or_default()),
+            )
+        })
+        .collect()
+}
+
+/// Every part of an endpoint that can use variables
+fn get_template_inputs(endpoint: &PersistedEndpoint) -> Vec<&str> {
+    let mut inputs: Vec<&str> = vec![&endpoint.url, &endpoint.body, &endpoint.graphql_variables];
+    endpoint.headers.iter().for_each(|header| {
+        inputs.push(&header.name);
+        inputs.push(&header.value);
+    });
+    endpoint
+        .path_params
+        .iter()
+        .for_each(|path_param| inputs.push(&path_param.value));
+
+    inputs
+}
+
+/// Whether the request sends the value of a private variable or of a `.env`
+/// file, the response may contain it so it is not saved in the history
+fn uses_private_values(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<bool> {
+    let mut private_values: Vec<String> = project
+        .variable
+        .iter()
+        .filter_map(|variable| variable.private.clone())
+        .collect();
+    private_values.extend(load_dotenv_files(&project.dotenv_files)?.into_values());
+
+    let sent: Vec<String> = get_template_inputs(endpoint)
+        .into_iter()
+        .map(|input| {
+            render_segments(input, variables, false)
+                .into_iter()
+                .map(|segment| segment.text)
+                .collect::<String>()
+        })
+        .collect();
+
+    Ok(private_values
+        .iter()
+        .filter(|value| !value.is_empty())
+        .any(|value| sent.iter().any(|text| text.contains(value.as_str()))))
+}
+
+fn get_endpoint_variables(endpoint: &PersistedEndpoint) -> HashMap<String, String> {
+    endpoint
+        .variables
+        .iter()
+        .map(|variable| (variable.key.clone(), variable.value.clone()))
+        .collect()
+}
+
+/// The variables for a request, from lowest to highest precedence: project
+/// variables, `.env` files, `TOME_VAR_` process environment variables, endpoint
+/// variables and `--var` arguments. Dynamic variables used by the endpoint are evaluated
+/// again for every request.
+fn get_request_variables(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+) -> anyhow::Result<HashMap<String, String>> {
+    let mut variables = get_project_variables(project);
+    variables.extend(load_dotenv_files(&project.dotenv_files)?);
+    variables.extend(get_environment_variables());
+    variables.extend(get_endpoint_variables(endpoint));
+    variables.extend(get_cli_variables());
+
+    // Variables can use dynamic variables too
+    let values: Vec<String> = variables.values().cloned().collect();
+    let mut inputs: Vec<&str> = values.iter().map(|value| value.as_str()).collect();
+    inputs.extend(get_template_inputs(endpoint));
+
+    add_dynamic_variables(&inputs, &mut variables);
+
+    Ok(variables)
+}
+
+/// The fields of a form body with variables replaced in names and values.
+/// File references are found before variables are replaced, so a variable
+/// value that starts with `@` is sent as text.
+fn get_form_fields(
+    body: &str,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<Vec<(String, FormValue)>> {
+    parse_form_fields(body)
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match parse_form_value(value) {
+                FormValue::Text(text) => FormValue::Text(replace_variables(&text, variables)?),
+                FormValue::File(file) => FormValue::File(replace_variables(&file, variables)?),
+            };
+
+            Ok((replace_variables(name, variables)?, value))
+        })
+        .collect()
+}
+
+fn get_agent_builder(
+    project: &PersistedProject,
+    url: &str,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<AgentBuilder> {
+    let tls_connector = get_tls_connector(project, url, variables)?;
+
+    Ok(AgentBuilder::new().tls_connector(Arc::new(tls_connector)))
+}
+
+fn get_tls_connector(
+    project: &PersistedProject,
+    url: &str,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<native_tls::TlsConnector> {
+    let mut tls_builder = native_tls::TlsConnector::builder();
+    if let Some(certificate) = find_client_certificate(&project.client_certificates, url) {
+        let password = match &certificate.password {
+            Some(password) => Some(replace_variables(password, variables)?),
+            None => None,
+        };
+
+        let identity = load_identity(certificate, password.as_deref())?;
+        tls_builder.identity(identity);
+    }
+
+    Ok(tls_builder.build()?)
+}
+
+fn set_headers(
+    mut request: Request,
+    headers: &[Header],
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<Request> {
+    for header in headers.iter() {
+        let header_name = replace_variables(&header.name, variables)?;
+        let header_value = replace_variables(&header.value, variables)?;
+
+        // NOTE: Skip content-type header, this should be calculated based
+        // on the body mode and/or raw type
+        if header_name.to_lowercase() == "content-type" {
+            continue;
+        }
+
+        request = request.set(&header_name, &header_value);
+    }
+
+    // NOTE: Responses are decoded by Tome so their compressed size is known
+    if request.header("accept-encoding").is_none() {
+        request = request.set("Accept-Encoding", "gzip, deflate");
+    }
+
+    Ok(request)
 }
 
 fn get_content_type(endpoint: &PersistedEndpoint) -> Option<String> {
-    match endpoint.body_mode.as_str() {
+    // NOTE: The body mode selector stores "FormData", "UrlEncoded", etc while
+    // Postman imports store "formdata", "urlencoded", etc
+    match endpoint.body_mode.to_lowercase().as_str() {
         "none" => None,
         "formdata" => Some("multipart/form-data".to_string()),
-        "x-www-form-urlencoded" => Some("application/x-www-form-urlencoded".to_string()),
+        "urlencoded" | "x-www-form-urlencoded" => {
+            Some("application/x-www-form-urlencoded".to_string())
+        }
+        "binary" | "file" => Some("application/octet-stream".to_string()),
         "graphql" => Some("application/json".to_string()),
         "raw" => match endpoint.raw_type.to_lowercase().as_str() {
             "text" => Some("text/plain".to_string()),
@@ -266,123 +787,183 @@ fn get_extension(content_type: &str) -> String {
     }
 }
 
-fn handle_successful_response(
+/// Saves the response to the response files and shows it. The body is
+/// streamed to a file so large responses are never held in memory, they are
+/// only loaded for the scripts when they are small enough. Responses of
+/// `private` requests are not kept in the request history.
+fn handle_response(
     response: Response,
+    timer: RequestTimer,
+    private: bool,
     state: &mut DashboardState,
     context: &mut Context<'_, DashboardState>,
     dashboard: &mut DashboardComponent,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<ScriptResponse>> {
     let status = response.status();
 
-    loop {
-        if state.response_headers.len() > 0 {
-            state.response_headers.pop_back();
-        } else {
-            break;
-        }
-    }
-
-    let mut ext = String::from("txt");
-    for name in response.headers_names() {
-        let Some(value) = response.header(&name) else {
-            continue;
-        };
-
-        if name.to_lowercase() == "content-type" {
-            ext = get_extension(value);
-        }
-
-        state.response_headers.push(HeaderState {
-            name: name.clone().into(),
-            value: value.to_string().clone().into(),
-            row_color: "".to_string().into(),
-            row_fg_color: "".to_string().into(),
-        });
-    }
+    let headers = get_response_headers(&response);
+    set_response_headers(state, &headers);
 
-    let mut response_path = PathBuf::from("/tmp");
-    response_path.push("tome_response.txt");
+    let content_type = get_response_content_type(&headers);
+    let ext = content_type.map_or(String::from("txt"), get_extension);
 
-    let mut response_reader = response.into_reader();
-    let mut buf: Vec<u8> = vec![];
-    response_reader.read_to_end(&mut buf)?;
+    let response_head = ResponseHead::new(&response);
 
-    let mut file_path = PathBuf::from("/tmp");
-    file_path.push("tome_response.txt");
+    let mut files = ResponseFiles::new()?;
+    let mut body = ResponseBody::new(response);
+    io::copy(&mut body, &mut File::create(&files.raw)?)?;
 
-    let mut file = OpenOptions::new()
-        .create(true)
-        .truncate(true)
-        .write(true)
-        .open(file_path.clone())?;
+    let metrics = timer.finish(response_head, &body);
 
-    let write_result = file.write_all(buf.as_slice());
-    // TODO: Fix the error handling to message the user
-    if write_result.is_err() {
-        return Ok(());
+    // NOTE: Binary responses are shown as a hex dump, text in other charsets
+    // is shown as UTF-8. The raw response is kept for saving it.
+    let encoding = ResponseEncoding::detect_file(content_type, &files.raw)?;
+    match encoding {
+        ResponseEncoding::Binary => {
+            let raw = BufReader::new(File::open(&files.raw)?);
+            write_hex_dump(raw, File::create(&files.text)?)?
+        }
+        ResponseEncoding::Charset(charset) => {
+            write_decoded_text(charset, File::open(&files.raw)?, File::create(&files.text)?)?
+        }
+        ResponseEncoding::Utf8 => files.text = files.raw.clone(),
     }
 
-    let window_label = format!("Response Body (Status Code: {status})");
+    let binary = encoding == ResponseEncoding::Binary;
+    let body = files.read_text()?;
+    if body.is_none() {
+        dashboard.last_response = None;
+    }
 
-    // TODO: Fix the response handling so it doesnt have to be read from file since
-    // response renderer is reading it all into lines anyway
-    let full_response = fs::read_to_string(file_path)?;
-    state.response.set(full_response);
+    let latency = format_latency(metrics.total);
+    let size = format_size(metrics.response_body);
+    let window_label = match (body.is_some(), binary) {
+        (false, _) => {
+            format!("Response Body (Status Code: {status}, {latency}, {size}, Scripts Skipped)")
+        }
+        (true, true) => format!("Response Body (Status Code: {status}, {latency}, Binary {size})"),
+        (true, false) => format!("Response Body (Status Code: {status}, {latency})"),
+    };
+    let history_body = (!private).then_some(files.text.as_path());
+    show_response_metrics(state, status, metrics, history_body);
 
     state.response_body_window_label.set(window_label);
     state.main_display.set(DashboardDisplay::ResponseBody);
 
     context.set_focus("id", "response_renderer");
 
-    let response_msg = ResponseRendererMessages::ResponseUpdate(ext);
+    if let Some(last_files) = dashboard.response_files.replace(files.clone()) {
+        last_files.remove();
+    }
+
+    let response_msg = ResponseRendererMessages::ResponseUpdate {
+        extension: ext,
+        binary,
+        files,
+    };
     if let Ok(msg) = serde_json::to_string(&response_msg) {
         if let Ok(component_ids) = dashboard.component_ids.try_borrow() {
             let _ = send_message("response_renderer", msg, &component_ids, context.emitter);
+
+            // NOTE: The input sends the query back to the renderer, which runs
+            // it on the new response
+            let response_query = state.endpoint.to_ref().response_query.clone();
+            let _ = send_message(
+                "response_query_input",
+                response_query,
+                &component_ids,
+                context.emitter,
+            );
         };
     };
 
-    Ok(())
+    Ok(body.map(|body| ScriptResponse {
+        status,
+        headers,
+        body,
+    }))
 }
 
-fn handle_error_response(
-    error: ureq::Error,
-    state: &mut DashboardState,
-    context: &mut Context<'_, DashboardState>,
-    dashboard: &mut DashboardComponent,
-) -> anyhow::Result<()> {
-    match error {
-        ureq::Error::Status(code, response) => {
-            let body = response
-                .into_string()
-                .unwrap_or("Could not read error response body".to_string());
-            let window_label = format!("Response Body (Status Code: {code})");
-
-            // TODO: The error response handling needs to extract headers from the response
-            // to display the response headers when there is an error
-
-            state.response.set(body.clone());
-            state.response_body_window_label.set(window_label);
-            state.main_display.set(DashboardDisplay::ResponseBody);
-            context.set_focus("id", "response_renderer");
-
-            // TODO: Once the response headers are being extracted, figure out the correct
-            // extension type to use to syntax highlight the response
-            let response_msg = ResponseRendererMessages::SyntaxPreview(None);
-            if let Ok(msg) = serde_json::to_string(&response_msg) {
-                if let Ok(component_ids) = dashboard.component_ids.try_borrow() {
-                    let _ = send_message("response_renderer", msg, &component_ids, context.emitter);
-                };
-            };
+fn get_script_response(response: Response) -> anyhow::Result<ScriptResponse> {
+    let status = response.status();
+    let headers = get_response_headers(&response);
 
-            Ok(())
-        }
+    let mut buf: Vec<u8> = vec![];
+    ResponseBody::new(response).read_to_end(&mut buf)?;
+    let body = get_response_text(get_response_content_type(&headers), &buf);
+
+    Ok(ScriptResponse {
+        status,
+        headers,
+        body,
+    })
+}
 
-        ureq::Error::Transport(transport_error) => {
-            let error = transport_error.message().unwrap_or("Network error");
-            state.error_message.set(error.to_string());
-            state.floating_window.set(FloatingWindow::Error);
+fn set_response_headers(state: &mut DashboardState, headers: &[Header]) {
+    while state.response_headers.len() > 0 {
+        state.response_headers.pop_back();
+    }
 
-            Ok(())
-        }
+    for Header { name, value } in headers {
+        state.response_headers.push(HeaderState {
+            name: name.clone().into(),
+            value: value.clone().into(),
+            row_color: "".to_string().into(),
+            row_fg_color: "".to_string().into(),
+        });
+    }
+}
+
+fn get_response_headers(response: &Response) -> Vec<Header> {
+    response
+        .headers_names()
+        .into_iter()
+        .filter_map(|name| {
+            let value = response.header(&name)?.to_string();
+
+            Some(Header { name, value })
+        })
+        .collect()
+}
+
+fn show_transport_error(transport_error: &Transport, state: &mut DashboardState) {
+    let error = get_transport_error_message(transport_error);
+    state.error_message.set(error);
+    state.floating_window.set(FloatingWindow::Error);
+}
+
+/// Shows the metrics of a response under the response body and adds them to
+/// the request history of the project, with a copy of the response body when
+/// there is one
+fn show_response_metrics(
+    state: &mut DashboardState,
+    status: u16,
+    metrics: ResponseMetrics,
+    body: Option<&Path>,
+) {
+    state.response_timing.set(metrics.get_timing_line());
+    state.response_sizes.set(metrics.get_size_line());
+
+    let project_name = state.project.to_ref().name.to_ref().to_string();
+    let endpoint = state.endpoint.to_ref();
+    let entry = HistoryEntry::new(
+        &endpoint.name.to_ref(),
+        &endpoint.method.to_ref(),
+        &endpoint.url.to_ref(),
+        status,
+        metrics,
+    );
+
+    if let Err(error) = add_history_entry(&project_name, entry, body) {
+        error!("Could not save the request history: {error}");
+    }
+}
+
+fn get_transport_error_message(transport_error: &Transport) -> String {
+    // NOTE: TLS handshake failures, like a server rejecting the client
+    // certificate, only carry their details in the error source
+    match transport_error.message() {
+        Some(message) => message.to_string(),
+        None => transport_error.to_string(),
     }
 }
@@ -0,0 +1,180 @@
+use std::path::{Path, PathBuf};
+
+/// A file referenced from a request body, written the same way as curl's
+/// `@/path/to/file;type=mime/type` syntax
+#[derive(Debug, PartialEq)]
+pub struct FileReference {
+    pub path: PathBuf,
+    pub content_type: Option<String>,
+}
+
+impl FileReference {
+    /// The user set content type, or one detected from the file extension
+    pub fn content_type(&self) -> String {
+        self.content_type
+            .clone()
+            .unwrap_or_else(|| detect_content_type(&self.path))
+    }
+
+    pub fn file_name(&self) -> Option<String> {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+    }
+}
+
+/// Parses a `@/path/to/file` or `@/path/to/file;type=mime/type` value
+pub fn parse_file_reference(value: &str) -> Option<FileReference> {
+    let reference = value.trim().strip_prefix('@')?;
+
+    let (path, content_type) = match reference.rsplit_once(";type=") {
+        Some((path, content_type)) => (path.trim(), Some(content_type.trim())),
+        None => (reference.trim(), None),
+    };
+
+    if path.is_empty() {
+        return None;
+    }
+
+    Some(FileReference {
+        path: PathBuf::from(path),
+        content_type: content_type
+            .filter(|content_type| !content_type.is_empty())
+            .map(|content_type| content_type.to_string()),
+    })
+}
+
+/// Parses the body of a binary request, the leading `@` is optional since
+/// the whole body is always a single file
+pub fn parse_binary_body(body: &str) -> Option<FileReference> {
+    let body = body.trim();
+
+    match body.starts_with('@') {
+        true => parse_file_reference(body),
+        false => parse_file_reference(&format!("@{body}")),
+    }
+}
+
+/// The value of a form field as written in the body
+#[derive(Debug, PartialEq)]
+pub enum FormValue {
+    Text(String),
+
+    /// A `@/path/to/file` reference, with the `@`
+    File(String),
+}
+
+impl FormValue {
+    /// The text that is sent when files can not be attached, file references
+    /// are sent as written
+    pub fn text(&self) -> &str {
+        match self {
+            FormValue::Text(text) | FormValue::File(text) => text,
+        }
+    }
+}
+
+/// Values that start with `@` are file references, `\@` starts a text value
+/// with a literal `@`
+pub fn parse_form_value(value: &str) -> FormValue {
+    if let Some(text) = value
+        .strip_prefix('\\')
+        .filter(|text| text.starts_with('@'))
+    {
+        return FormValue::Text(text.to_string());
+    }
+
+    match value.starts_with('@') {
+        true => FormValue::File(value.to_string()),
+        false => FormValue::Text(value.to_string()),
+    }
+}
+
+/// Splits a form body into its `name=value` fields, one field per line
+pub fn parse_form_fields(body: &str) -> Vec<(&str, &str)> {
+    body.lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+pub fn detect_content_type(path: &Path) -> String {
+    mime_guess::from_path(path)
+        .first_or_octet_stream()
+        .to_string()
+}
+
+#[test]
+fn test_parse_file_reference() {
+    assert_eq!(
+        parse_file_reference("@/home/me/avatar.png"),
+        Some(FileReference {
+            path: PathBuf::from("/home/me/avatar.png"),
+            content_type: None
+        })
+    );
+
+    assert_eq!(
+        parse_file_reference("@/home/me/users.csv;type=text/csv"),
+        Some(FileReference {
+            path: PathBuf::from("/home/me/users.csv"),
+            content_type: Some("text/csv".to_string())
+        })
+    );
+
+    assert_eq!(parse_file_reference("/home/me/avatar.png"), None);
+    assert_eq!(parse_file_reference("@"), None);
+}
+
+#[test]
+fn test_parse_binary_body() {
+    let reference = parse_binary_body(" /home/me/avatar.png\n").unwrap();
+    assert_eq!(reference.path, PathBuf::from("/home/me/avatar.png"));
+    assert_eq!(reference.content_type(), "image/png");
+
+    let reference = parse_binary_body("@/home/me/data.bin;type=application/x-custom").unwrap();
+    assert_eq!(reference.content_type(), "application/x-custom");
+
+    assert_eq!(parse_binary_body(""), None);
+}
+
+#[test]
+fn test_parse_form_fields() {
+    let fields = parse_form_fields("name=Jane\navatar=@/home/me/avatar.png\n\nbroken\n=empty");
+
+    assert_eq!(
+        fields,
+        vec![("name", "Jane"), ("avatar", "@/home/me/avatar.png")]
+    );
+}
+
+#[test]
+fn test_parse_form_value() {
+    assert_eq!(
+        parse_form_value("@/home/me/avatar.png"),
+        FormValue::File("@/home/me/avatar.png".to_string())
+    );
+    assert_eq!(
+        parse_form_value("\\@handle"),
+        FormValue::Text("@handle".to_string())
+    );
+    assert_eq!(
+        parse_form_value("\\handle"),
+        FormValue::Text("\\handle".to_string())
+    );
+    assert_eq!(
+        parse_form_value("me@example.com"),
+        FormValue::Text("me@example.com".to_string())
+    );
+}
+
+#[test]
+fn test_detect_content_type() {
+    assert_eq!(detect_content_type(Path::new("users.csv")), "text/csv");
+    assert_eq!(detect_content_type(Path::new("avatar.jpg")), "image/jpeg");
+    assert_eq!(
+        detect_content_type(Path::new("unknown")),
+        "application/octet-stream"
+    );
+}
@@ -0,0 +1,495 @@
+use std::{
+    cell::Cell,
+    io::{self, Read},
+    net::{SocketAddr, ToSocketAddrs},
+    rc::Rc,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde::{Deserialize, Serialize};
+use ureq::{ReadWrite, Request, Resolver, Response, TlsConnector};
+
+use crate::benchmark::format_latency;
+
+/// When the connection phases of a request started and ended
+#[derive(Debug, Default)]
+struct ConnectionTimes {
+    dns_start: Option<Instant>,
+    dns_end: Option<Instant>,
+    tls_start: Option<Instant>,
+    tls_end: Option<Instant>,
+}
+
+/// Records the connection phases of an agent, it is the resolver of the agent
+/// and is shared with its TLS connector
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionTimer(Arc<Mutex<ConnectionTimes>>);
+
+impl ConnectionTimer {
+    fn record(&self, update: impl FnOnce(&mut ConnectionTimes)) {
+        if let Ok(mut times) = self.0.lock() {
+            update(&mut times);
+        }
+    }
+
+    fn get_phase(start: Option<Instant>, end: Option<Instant>) -> Option<Duration> {
+        Some(end?.saturating_duration_since(start?))
+    }
+
+    /// The DNS, connect and TLS durations, phases that did not happen are None
+    fn get_phases(&self) -> (Option<Duration>, Option<Duration>, Option<Duration>) {
+        let Ok(times) = self.0.lock() else {
+            return (None, None, None);
+        };
+
+        (
+            Self::get_phase(times.dns_start, times.dns_end),
+            // NOTE: The TCP connection is made between resolving the host and
+            // the TLS handshake, plain http connections can not be measured
+            Self::get_phase(times.dns_end, times.tls_start),
+            Self::get_phase(times.tls_start, times.tls_end),
+        )
+    }
+}
+
+impl Resolver for ConnectionTimer {
+    fn resolve(&self, netloc: &str) -> io::Result<Vec<SocketAddr>> {
+        self.record(|times| times.dns_start = Some(Instant::now()));
+        let addresses = netloc.to_socket_addrs()?.collect();
+        self.record(|times| times.dns_end = Some(Instant::now()));
+
+        Ok(addresses)
+    }
+}
+
+/// A native TLS connector that records how long the handshake takes
+pub struct TimedTlsConnector {
+    connector: native_tls::TlsConnector,
+    timer: ConnectionTimer,
+}
+
+impl TimedTlsConnector {
+    pub fn new(connector: native_tls::TlsConnector, timer: ConnectionTimer) -> Self {
+        TimedTlsConnector { connector, timer }
+    }
+}
+
+impl TlsConnector for TimedTlsConnector {
+    fn connect(
+        &self,
+        dns_name: &str,
+        io: Box<dyn ReadWrite>,
+    ) -> Result<Box<dyn ReadWrite>, ureq::Error> {
+        self.timer
+            .record(|times| times.tls_start = Some(Instant::now()));
+        let stream = TlsConnector::connect(&self.connector, dns_name, io);
+        self.timer
+            .record(|times| times.tls_end = Some(Instant::now()));
+
+        stream
+    }
+}
+
+/// The size of a request as it is written to the connection
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RequestSize {
+    pub headers: usize,
+    pub body: usize,
+}
+
+impl RequestSize {
+    /// Counts the request line, the headers Tome sets, `Host` and
+    /// `Content-Length`. The default headers ureq adds are not counted.
+    pub fn new(request: &Request, body: usize) -> Self {
+        let target = get_request_target(request.url());
+        let mut headers = format!("{} {target} HTTP/1.1\r\n", request.method()).len();
+
+        headers += request
+            .header_names()
+            .iter()
+            .filter_map(|name| Some(get_header_size(name, request.header(name)?)))
+            .sum::<usize>();
+
+        if let Some(host) = get_host(request.url()) {
+            headers += get_header_size("Host", host);
+        }
+
+        if body > 0 {
+            headers += get_header_size("Content-Length", &body.to_string());
+        }
+
+        RequestSize {
+            headers: headers + 2,
+            body,
+        }
+    }
+}
+
+fn get_header_size(name: &str, value: &str) -> usize {
+    name.len() + value.len() + 4
+}
+
+fn get_host(url: &str) -> Option<&str> {
+    let (_, rest) = url.split_once("://")?;
+
+    rest.split(['/', '?', '#']).next()
+}
+
+fn get_request_target(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let target = rest.find(['/', '?']).map_or("/", |index| &rest[index..]);
+
+    target.split('#').next().unwrap_or("/")
+}
+
+/// The length of `application/x-www-form-urlencoded` form fields
+pub fn get_form_size(form: &[(&str, &str)]) -> usize {
+    let fields: usize = form
+        .iter()
+        .map(|(name, value)| get_encoded_size(name) + get_encoded_size(value) + 1)
+        .sum();
+
+    fields + form.len().saturating_sub(1)
+}
+
+/// Spaces are encoded as `+`, other reserved bytes are percent encoded
+fn get_encoded_size(input: &str) -> usize {
+    let is_unreserved = |byte: &u8| byte.is_ascii_alphanumeric() || b" *-._".contains(byte);
+
+    input
+        .bytes()
+        .map(|byte| match is_unreserved(&byte) {
+            true => 1,
+            false => 3,
+        })
+        .sum()
+}
+
+/// What the response told about itself before its body was read
+pub struct ResponseHead {
+    headers: usize,
+    content_encoding: Option<String>,
+    remote_address: String,
+}
+
+impl ResponseHead {
+    pub fn new(response: &Response) -> Self {
+        let status_line = format!(
+            "{} {} {}\r\n",
+            response.http_version(),
+            response.status(),
+            response.status_text()
+        );
+
+        let headers: usize = response
+            .headers_names()
+            .iter()
+            .filter_map(|name| Some(get_header_size(name, response.header(name)?)))
+            .sum();
+
+        ResponseHead {
+            headers: status_line.len() + headers + 2,
+            content_encoding: get_content_encoding(response),
+            remote_address: response.remote_addr().to_string(),
+        }
+    }
+}
+
+fn get_content_encoding(response: &Response) -> Option<String> {
+    response
+        .header("content-encoding")
+        .map(|encoding| encoding.trim().to_lowercase())
+        .filter(|encoding| encoding != "identity")
+}
+
+/// Counts the bytes of a body as they are received
+struct CountingReader<R> {
+    reader: R,
+    count: Rc<Cell<usize>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.count.set(self.count.get() + read);
+
+        Ok(read)
+    }
+}
+
+/// A response body that is decoded as it is read. Agents do not decode
+/// bodies themselves so the received size is the size of the compressed body.
+pub struct ResponseBody {
+    reader: Box<dyn Read>,
+    received: Rc<Cell<usize>>,
+    decoded: usize,
+
+    /// The body has a content encoding that is decoded
+    decoding: bool,
+}
+
+impl ResponseBody {
+    pub fn new(response: Response) -> Self {
+        let encoding = get_content_encoding(&response);
+
+        Self::decode(encoding.as_deref(), response.into_reader())
+    }
+
+    /// Bodies with an encoding that can not be decoded are read as they are
+    fn decode(encoding: Option<&str>, body: impl Read + 'static) -> Self {
+        let received = Rc::new(Cell::new(0));
+        let body = CountingReader {
+            reader: body,
+            count: received.clone(),
+        };
+
+        let (reader, decoding): (Box<dyn Read>, bool) = match encoding {
+            Some("gzip" | "x-gzip") => (Box::new(GzDecoder::new(body)), true),
+            Some("deflate") => (Box::new(ZlibDecoder::new(body)), true),
+            _ => (Box::new(body), false),
+        };
+
+        ResponseBody {
+            reader,
+            received,
+            decoded: 0,
+            decoding,
+        }
+    }
+
+    /// The bytes that were received so far
+    pub fn get_received_size(&self) -> usize {
+        self.received.get()
+    }
+
+    /// The size of the decoded body, None when the body is not decoded
+    fn get_uncompressed_size(&self) -> Option<usize> {
+        self.decoding.then_some(self.decoded)
+    }
+}
+
+impl Read for ResponseBody {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.decoded += read;
+
+        Ok(read)
+    }
+}
+
+/// Measures a request from the moment it is sent until its body is read
+pub struct RequestTimer {
+    start: Instant,
+    first_byte: Duration,
+    connection: ConnectionTimer,
+    request_size: RequestSize,
+}
+
+impl RequestTimer {
+    /// Create the timer as soon as the response headers arrived, `start` is
+    /// when the request was sent
+    pub fn new(start: Instant, connection: ConnectionTimer, request_size: RequestSize) -> Self {
+        RequestTimer {
+            start,
+            first_byte: start.elapsed(),
+            connection,
+            request_size,
+        }
+    }
+
+    /// Call once the whole response body was read
+    pub fn finish(self, head: ResponseHead, body: &ResponseBody) -> ResponseMetrics {
+        let total = self.start.elapsed();
+        let (dns, connect, tls) = self.connection.get_phases();
+
+        ResponseMetrics {
+            total,
+            dns,
+            connect,
+            tls,
+            first_byte: self.first_byte,
+            download: total.saturating_sub(self.first_byte),
+            request_headers: self.request_size.headers,
+            request_body: self.request_size.body,
+            response_headers: head.headers,
+            response_body: body.get_received_size(),
+            uncompressed_body: body.get_uncompressed_size(),
+            content_encoding: head.content_encoding,
+            remote_address: head.remote_address,
+        }
+    }
+}
+
+/// How long a response took and how big it was, it is stored with the
+/// history entry of the request
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ResponseMetrics {
+    pub total: Duration,
+    pub dns: Option<Duration>,
+    pub connect: Option<Duration>,
+    pub tls: Option<Duration>,
+
+    /// From sending the request until the response headers arrived
+    pub first_byte: Duration,
+
+    /// Reading the response body
+    pub download: Duration,
+
+    pub request_headers: usize,
+    pub request_body: usize,
+    pub response_headers: usize,
+
+    /// The body as it was received, compressed when there is a content encoding
+    pub response_body: usize,
+    pub uncompressed_body: Option<usize>,
+    pub content_encoding: Option<String>,
+
+    pub remote_address: String,
+}
+
+impl ResponseMetrics {
+    pub fn get_timing_line(&self) -> String {
+        let phases = [
+            ("DNS", self.dns),
+            ("Connect", self.connect),
+            ("TLS", self.tls),
+            ("First byte", Some(self.first_byte)),
+            ("Download", Some(self.download)),
+        ];
+
+        let mut parts = vec![format!("Time {}", format_latency(self.total))];
+        parts.extend(phases.iter().filter_map(|(label, phase)| {
+            phase.map(|phase| format!("{label} {}", format_latency(phase)))
+        }));
+
+        parts.join(" │ ")
+    }
+
+    pub fn get_size_line(&self) -> String {
+        let received_body = match (&self.content_encoding, self.uncompressed_body) {
+            (Some(encoding), Some(uncompressed)) => format!(
+                "{} body ({encoding}, {} uncompressed)",
+                format_size(self.response_body),
+                format_size(uncompressed)
+            ),
+            (Some(encoding), None) => {
+                format!("{} body ({encoding})", format_size(self.response_body))
+            }
+            (None, _) => format!("{} body", format_size(self.response_body)),
+        };
+
+        format!(
+            "Sent {} headers + {} body │ Received {} headers + {received_body} │ Remote {}",
+            format_size(self.request_headers),
+            format_size(self.request_body),
+            format_size(self.response_headers),
+            self.remote_address
+        )
+    }
+}
+
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[test]
+fn test_format_size() {
+    assert_eq!(format_size(0), "0 B");
+    assert_eq!(format_size(1023), "1023 B");
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
+    assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+}
+
+#[test]
+fn test_get_form_size() {
+    assert_eq!(get_form_size(&[]), 0);
+    assert_eq!(get_form_size(&[("name", "tome")]), "name=tome".len());
+    assert_eq!(
+        get_form_size(&[("q", "a b"), ("path", "/x")]),
+        "q=a+b&path=%2Fx".len()
+    );
+}
+
+#[test]
+fn test_get_request_target() {
+    assert_eq!(get_request_target("https://example.com"), "/");
+    assert_eq!(
+        get_request_target("https://example.com/users?page=2#top"),
+        "/users?page=2"
+    );
+    assert_eq!(get_request_target("http://localhost:8080?q=1"), "?q=1");
+    assert_eq!(
+        get_host("http://localhost:8080/users"),
+        Some("localhost:8080")
+    );
+}
+
+#[test]
+fn test_response_metrics_lines() {
+    let metrics = ResponseMetrics {
+        total: Duration::from_millis(120),
+        dns: Some(Duration::from_millis(2)),
+        connect: None,
+        tls: None,
+        first_byte: Duration::from_millis(100),
+        download: Duration::from_millis(20),
+        request_headers: 120,
+        request_body: 0,
+        response_headers: 300,
+        response_body: 2048,
+        uncompressed_body: Some(8192),
+        content_encoding: Some(String::from("gzip")),
+        remote_address: String::from("127.0.0.1:8080"),
+    };
+
+    assert_eq!(
+        metrics.get_timing_line(),
+        "Time 120.0ms │ DNS 2.0ms │ First byte 100.0ms │ Download 20.0ms"
+    );
+    assert_eq!(
+        metrics.get_size_line(),
+        "Sent 120 B headers + 0 B body │ Received 300 B headers + 2.0 KB body (gzip, 8.0 KB uncompressed) │ Remote 127.0.0.1:8080"
+    );
+}
+
+#[test]
+fn test_response_body_gzip() {
+    use std::io::{Cursor, Write};
+
+    use flate2::{write::GzEncoder, Compression};
+
+    let text = "{\"name\": \"tome\"}\n".repeat(100);
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let mut body = ResponseBody::decode(Some("gzip"), Cursor::new(compressed.clone()));
+    let mut decoded = String::new();
+    body.read_to_string(&mut decoded).unwrap();
+
+    assert_eq!(decoded, text);
+    assert_eq!(body.get_received_size(), compressed.len());
+    assert_eq!(body.get_uncompressed_size(), Some(text.len()));
+
+    let mut body = ResponseBody::decode(None, Cursor::new(text.clone()));
+    io::copy(&mut body, &mut io::sink()).unwrap();
+
+    assert_eq!(body.get_received_size(), text.len());
+    assert_eq!(body.get_uncompressed_size(), None);
+}
@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::{
+    dynamic_variables::add_dynamic_variables,
+    environment::load_dotenv_files,
+    graphql::is_graphql_mode,
+    projects::{Header, PersistedEndpoint, PersistedProject},
+    query_params::encode_url,
+    templating::{render, render_segments, Segment},
+};
+
+use super::{
+    get_content_type, get_request_variables, get_template_inputs, run_pre_request_scripts,
+};
+
+const MASK: &str = "********";
+
+/// Header names that carry credentials, their values are masked in the preview
+const SECRET_HEADERS: [&str; 7] = [
+    "authorization",
+    "cookie",
+    "token",
+    "secret",
+    "password",
+    "api-key",
+    "apikey",
+];
+
+/// A line of the request preview
+pub type PreviewLine = Vec<Segment>;
+
+/// The request as it would be sent for the endpoint, after its pre-request
+/// scripts ran and with variables replaced. Private variables, `.env` values
+/// and credential headers are masked.
+pub fn preview_request(
+    project: &PersistedProject,
+    endpoint: &PersistedEndpoint,
+) -> anyhow::Result<Vec<PreviewLine>> {
+    let mut lines: Vec<PreviewLine> = vec![];
+
+    let mut variables = get_request_variables(project, endpoint)?;
+
+    // NOTE: The scripts run on copies, the variables they set are only saved
+    // when the request is sent
+    let mut scripted_endpoint = endpoint.clone();
+    let mut scripted_variables = variables.clone();
+    let scripts = run_pre_request_scripts(project, &mut scripted_endpoint, &mut scripted_variables);
+    let endpoint = match scripts {
+        Ok(_) => {
+            variables = scripted_variables;
+            add_dynamic_variables(&get_template_inputs(&scripted_endpoint), &mut variables);
+
+            scripted_endpoint
+        }
+        Err(error) => {
+            lines.push(vec![Segment::resolved(format!(
+                "# Before pre-request scripts, they failed: {error}"
+            ))]);
+
+            endpoint.clone()
+        }
+    };
+    let endpoint = &endpoint;
+
+    mask_variables(project, &mut variables)?;
+
+    let url = match encode_url(&endpoint.url, &endpoint.path_params, |input| {
+        render(input, &variables)
+    }) {
+        Ok(url) => vec![Segment::resolved(url)],
+        Err(_) => render_segments(&endpoint.url, &variables, false),
+    };
+
+    let mut request_line = vec![Segment::resolved(format!("{} ", endpoint.method))];
+    request_line.extend(url);
+    lines.push(request_line);
+
+    let content_type = get_content_type(endpoint);
+    if let Some(content_type) = &content_type {
+        lines.push(vec![Segment::resolved(format!(
+            "Content-Type: {content_type}"
+        ))]);
+    }
+
+    for header in &endpoint.headers {
+        lines.push(get_header_line(header, &variables));
+    }
+
+    let Some(content_type) = content_type else {
+        return Ok(lines);
+    };
+
+    lines.push(vec![]);
+
+    if is_graphql_mode(&endpoint.body_mode) {
+        push_segments(
+            &mut lines,
+            render_segments(&endpoint.body, &variables, true),
+        );
+
+        if !endpoint.graphql_variables.trim().is_empty() {
+            lines.push(vec![]);
+            lines.push(vec![Segment::resolved("# Variables")]);
+            push_segments(
+                &mut lines,
+                render_segments(&endpoint.graphql_variables, &variables, true),
+            );
+        }
+
+        return Ok(lines);
+    }
+
+    let json = content_type == "application/json";
+    push_segments(
+        &mut lines,
+        render_segments(&endpoint.body, &variables, json),
+    );
+
+    Ok(lines)
+}
+
+/// Masks the values of private variables and variables from `.env` files
+fn mask_variables(
+    project: &PersistedProject,
+    variables: &mut HashMap<String, String>,
+) -> anyhow::Result<()> {
+    // Values from `.env` files replaced by a higher precedence source are shown
+    for (key, value) in load_dotenv_files(&project.dotenv_files)? {
+        if variables.get(&key) == Some(&value) {
+            variables.insert(key, MASK.to_string());
+        }
+    }
+
+    project
+        .variable
+        .iter()
+        .filter(|variable| {
+            variable
+                .private
+                .as_ref()
+                .is_some_and(|private| !private.is_empty())
+        })
+        .filter_map(|variable| variable.key.clone())
+        .for_each(|key| {
+            variables.insert(key, MASK.to_string());
+        });
+
+    Ok(())
+}
+
+fn is_secret_header(name: &str) -> bool {
+    let name = name.to_lowercase();
+
+    SECRET_HEADERS.iter().any(|secret| name.contains(secret))
+}
+
+fn get_header_line(header: &Header, variables: &HashMap<String, String>) -> PreviewLine {
+    let mut line = render_segments(&header.name, variables, false);
+    line.push(Segment::resolved(": "));
+
+    let value = render_segments(&header.value, variables, false);
+    if !is_secret_header(&header.name) {
+        line.extend(value);
+        return line;
+    }
+
+    // Keep unresolved placeholders visible so they can still be fixed
+    match value.iter().all(|segment| segment.resolved) {
+        true => line.push(Segment::resolved(MASK)),
+        false => line.extend(value.into_iter().map(|segment| match segment.resolved {
+            true => Segment::resolved(MASK),
+            false => segment,
+        })),
+    }
+
+    line
+}
+
+/// Adds the segments to the preview, splitting them into lines
+fn push_segments(lines: &mut Vec<PreviewLine>, segments: Vec<Segment>) {
+    let mut line: PreviewLine = vec![];
+
+    for segment in segments {
+        let mut parts = segment.text.split('\n').peekable();
+
+        while let Some(part) = parts.next() {
+            if !part.is_empty() {
+                line.push(Segment {
+                    text: part.to_string(),
+                    resolved: segment.resolved,
+                });
+            }
+
+            if parts.peek().is_some() {
+                lines.push(std::mem::take(&mut line));
+            }
+        }
+    }
+
+    lines.push(line);
+}
@@ -0,0 +1,472 @@
+use std::collections::HashSet;
+
+use serde_json::Value;
+
+use crate::{json_query::select_json_path, json_tree::get_key_path};
+
+/// Line diffs of responses with more lines than this between their first and
+/// last difference pair the lines up instead of finding the common lines
+const MAX_COMMON_LINES_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffKind {
+    Unchanged,
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A row of the side by side diff, the side without the line is empty for
+/// added and removed lines
+#[derive(Debug, PartialEq)]
+pub struct DiffRow {
+    pub kind: DiffKind,
+    pub left: String,
+    pub right: String,
+}
+
+impl DiffRow {
+    pub fn new(kind: DiffKind, left: impl Into<String>, right: impl Into<String>) -> Self {
+        DiffRow {
+            kind,
+            left: left.into(),
+            right: right.into(),
+        }
+    }
+}
+
+/// Compares two responses, JSON responses are compared by structure and
+/// other responses line by line. Nodes selected by the ignored JSON paths,
+/// e.g. `$..updated_at`, are left out of JSON diffs.
+pub fn diff_responses(
+    left: &str,
+    right: &str,
+    ignore_paths: &[String],
+) -> anyhow::Result<Vec<DiffRow>> {
+    let left_json = serde_json::from_str::<Value>(left);
+    let right_json = serde_json::from_str::<Value>(right);
+
+    match (left_json, right_json) {
+        (Ok(left), Ok(right)) => diff_json(&left, &right, ignore_paths),
+        _ => Ok(diff_lines(left, right)),
+    }
+}
+
+/// Splits the ignored JSON paths and header names typed in the diff view,
+/// commas inside brackets like `$['a','b']` don't split the path
+pub fn parse_ignore_paths(text: &str) -> Vec<String> {
+    let mut paths = vec![];
+    let mut path = String::new();
+    let mut depth = 0;
+
+    for c in text.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            ',' if depth == 0 => {
+                paths.push(path.trim().to_string());
+                path.clear();
+                continue;
+            }
+            _ => {}
+        }
+
+        path.push(c);
+    }
+
+    paths.push(path.trim().to_string());
+    paths.retain(|path| !path.is_empty());
+
+    paths
+}
+
+/// Separates the JSON paths from the header names of the ignored entries,
+/// every JSON path starts with `$`
+pub fn split_ignore_entries(entries: Vec<String>) -> (Vec<String>, Vec<String>) {
+    entries
+        .into_iter()
+        .partition(|entry| entry.starts_with('$'))
+}
+
+pub fn has_changes(rows: &[DiffRow]) -> bool {
+    rows.iter().any(|row| row.kind != DiffKind::Unchanged)
+}
+
+/// e.g. `2 changed, 1 added, 0 removed`
+pub fn get_diff_summary(rows: &[DiffRow]) -> String {
+    let count = |kind: DiffKind| rows.iter().filter(|row| row.kind == kind).count();
+
+    let (changed, added, removed) = (
+        count(DiffKind::Changed),
+        count(DiffKind::Added),
+        count(DiffKind::Removed),
+    );
+
+    match changed + added + removed {
+        0 => String::from("No differences"),
+        _ => format!("{changed} changed, {added} added, {removed} removed"),
+    }
+}
+
+/// The changed rows as unified diff lines, `-` for the left side and `+` for
+/// the right side
+pub fn get_diff_lines(rows: &[DiffRow]) -> Vec<String> {
+    rows.iter()
+        .flat_map(|row| match row.kind {
+            DiffKind::Unchanged => vec![],
+            DiffKind::Removed => vec![format!("- {}", row.left)],
+            DiffKind::Added => vec![format!("+ {}", row.right)],
+            DiffKind::Changed => vec![format!("- {}", row.left), format!("+ {}", row.right)],
+        })
+        .collect()
+}
+
+/// One row for every value, keyed by its JSON path so the order of the keys
+/// in objects does not matter
+pub fn diff_json(
+    left: &Value,
+    right: &Value,
+    ignore_paths: &[String],
+) -> anyhow::Result<Vec<DiffRow>> {
+    let mut diff = JsonDiff {
+        ignored: HashSet::new(),
+        rows: vec![],
+    };
+
+    for path in ignore_paths.iter().filter(|path| !path.trim().is_empty()) {
+        for root in [left, right] {
+            let nodes = select_json_path(path, root)?;
+            diff.ignored
+                .extend(nodes.into_iter().map(|node| node as *const Value));
+        }
+    }
+
+    diff.push_rows("$", Some(left), Some(right));
+
+    Ok(diff.rows)
+}
+
+struct JsonDiff {
+    /// Addresses of the nodes the ignored paths select in both responses
+    ignored: HashSet<*const Value>,
+    rows: Vec<DiffRow>,
+}
+
+impl JsonDiff {
+    fn is_ignored(&self, node: Option<&Value>) -> bool {
+        node.is_some_and(|node| self.ignored.contains(&(node as *const Value)))
+    }
+
+    fn push_rows(&mut self, path: &str, left: Option<&Value>, right: Option<&Value>) {
+        if self.is_ignored(left) || self.is_ignored(right) {
+            return;
+        }
+
+        match (left, right) {
+            (Some(Value::Object(left_map)), Some(Value::Object(right_map)))
+                if !left_map.is_empty() || !right_map.is_empty() =>
+            {
+                for (key, left_value) in left_map {
+                    let key_path = get_key_path(path, key);
+                    self.push_rows(&key_path, Some(left_value), right_map.get(key));
+                }
+
+                for (key, right_value) in right_map {
+                    if !left_map.contains_key(key) {
+                        self.push_rows(&get_key_path(path, key), None, Some(right_value));
+                    }
+                }
+            }
+
+            (Some(Value::Array(left_items)), Some(Value::Array(right_items)))
+                if !left_items.is_empty() || !right_items.is_empty() =>
+            {
+                for index in 0..left_items.len().max(right_items.len()) {
+                    let index_path = format!("{path}[{index}]");
+                    self.push_rows(&index_path, left_items.get(index), right_items.get(index));
+                }
+            }
+
+            // NOTE: Objects and arrays that are only in one response are
+            // listed value by value like the rest of the diff
+            (Some(value), None) if has_children(value) => {
+                for (child_path, child) in get_children(path, value) {
+                    self.push_rows(&child_path, Some(child), None);
+                }
+            }
+
+            (None, Some(value)) if has_children(value) => {
+                for (child_path, child) in get_children(path, value) {
+                    self.push_rows(&child_path, None, Some(child));
+                }
+            }
+
+            (Some(left), Some(right)) => {
+                let kind = match left == right {
+                    true => DiffKind::Unchanged,
+                    false => DiffKind::Changed,
+                };
+
+                let row = DiffRow::new(
+                    kind,
+                    get_value_line(path, left),
+                    get_value_line(path, right),
+                );
+                self.rows.push(row);
+            }
+
+            (Some(left), None) => {
+                let row = DiffRow::new(DiffKind::Removed, get_value_line(path, left), "");
+                self.rows.push(row);
+            }
+
+            (None, Some(right)) => {
+                let row = DiffRow::new(DiffKind::Added, "", get_value_line(path, right));
+                self.rows.push(row);
+            }
+
+            (None, None) => {}
+        }
+    }
+}
+
+fn has_children(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => !map.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+fn get_children<'value>(path: &str, value: &'value Value) -> Vec<(String, &'value Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (get_key_path(path, key), child))
+            .collect(),
+
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (format!("{path}[{index}]"), child))
+            .collect(),
+
+        _ => vec![],
+    }
+}
+
+/// e.g. `$.user.name: "Ann"`
+fn get_value_line(path: &str, value: &Value) -> String {
+    format!("{path}: {value}")
+}
+
+/// Lines that are in both responses are matched up, the lines removed and
+/// added between them are shown next to each other as changed lines
+pub fn diff_lines(left: &str, right: &str) -> Vec<DiffRow> {
+    let left_lines: Vec<&str> = left.lines().collect();
+    let right_lines: Vec<&str> = right.lines().collect();
+
+    let prefix = left_lines
+        .iter()
+        .zip(&right_lines)
+        .take_while(|(left, right)| left == right)
+        .count();
+
+    let suffix = left_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(right_lines[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+
+    let left_middle = &left_lines[prefix..left_lines.len() - suffix];
+    let right_middle = &right_lines[prefix..right_lines.len() - suffix];
+
+    let mut rows: Vec<DiffRow> = left_lines[..prefix]
+        .iter()
+        .map(|line| DiffRow::new(DiffKind::Unchanged, *line, *line))
+        .collect();
+
+    match left_middle.len() * right_middle.len() <= MAX_COMMON_LINES_CELLS {
+        true => push_common_lines_diff(left_middle, right_middle, &mut rows),
+        false => push_changed_block(left_middle, right_middle, &mut rows),
+    }
+
+    let suffix_lines = left_lines[left_lines.len() - suffix..].iter();
+    rows.extend(suffix_lines.map(|line| DiffRow::new(DiffKind::Unchanged, *line, *line)));
+
+    rows
+}
+
+/// Walks the longest common subsequence of lines of both responses
+fn push_common_lines_diff(left: &[&str], right: &[&str], rows: &mut Vec<DiffRow>) {
+    let columns = right.len() + 1;
+
+    // NOTE: common[i * columns + j] is the number of common lines of
+    // left[i..] and right[j..]
+    let mut common = vec![0u32; (left.len() + 1) * columns];
+    for i in (0..left.len()).rev() {
+        for j in (0..right.len()).rev() {
+            common[i * columns + j] = match left[i] == right[j] {
+                true => common[(i + 1) * columns + j + 1] + 1,
+                false => common[(i + 1) * columns + j].max(common[i * columns + j + 1]),
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut removed: Vec<&str> = vec![];
+    let mut added: Vec<&str> = vec![];
+
+    while i < left.len() && j < right.len() {
+        if left[i] == right[j] {
+            push_changed_block(&removed, &added, rows);
+            removed.clear();
+            added.clear();
+
+            rows.push(DiffRow::new(DiffKind::Unchanged, left[i], right[j]));
+            i += 1;
+            j += 1;
+        } else if common[(i + 1) * columns + j] >= common[i * columns + j + 1] {
+            removed.push(left[i]);
+            i += 1;
+        } else {
+            added.push(right[j]);
+            j += 1;
+        }
+    }
+
+    removed.extend(&left[i..]);
+    added.extend(&right[j..]);
+    push_changed_block(&removed, &added, rows);
+}
+
+/// Pairs the removed lines with the added lines, the lines left over are
+/// only removed or only added
+fn push_changed_block(removed: &[&str], added: &[&str], rows: &mut Vec<DiffRow>) {
+    for index in 0..removed.len().max(added.len()) {
+        let row = match (removed.get(index), added.get(index)) {
+            (Some(left), Some(right)) => DiffRow::new(DiffKind::Changed, *left, *right),
+            (Some(left), None) => DiffRow::new(DiffKind::Removed, *left, ""),
+            (None, Some(right)) => DiffRow::new(DiffKind::Added, "", *right),
+            (None, None) => continue,
+        };
+
+        rows.push(row);
+    }
+}
+
+#[cfg(test)]
+fn get_changes(rows: &[DiffRow]) -> Vec<(DiffKind, &str, &str)> {
+    rows.iter()
+        .filter(|row| row.kind != DiffKind::Unchanged)
+        .map(|row| (row.kind, row.left.as_str(), row.right.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_diff_json() {
+    let left = r#"{"id": 1, "name": "Ann", "tags": ["a"], "meta": {"at": "10:00"}}"#;
+    let right =
+        r#"{"meta": {"at": "10:05"}, "name": "Bo", "id": 1, "tags": ["a", "b"], "x": {"y": null}}"#;
+
+    let rows = diff_responses(left, right, &[]).unwrap();
+    assert_eq!(
+        get_changes(&rows),
+        vec![
+            (DiffKind::Changed, "$.name: \"Ann\"", "$.name: \"Bo\""),
+            (DiffKind::Added, "", "$.tags[1]: \"b\""),
+            (
+                DiffKind::Changed,
+                "$.meta.at: \"10:00\"",
+                "$.meta.at: \"10:05\""
+            ),
+            (DiffKind::Added, "", "$.x.y: null"),
+        ]
+    );
+
+    let unchanged = rows.iter().filter(|row| row.kind == DiffKind::Unchanged);
+    assert_eq!(unchanged.count(), 2);
+}
+
+#[test]
+fn test_diff_json_ignore_paths() {
+    let left = r#"{"items": [{"id": 1, "updated_at": "a"}], "request_id": "x"}"#;
+    let right = r#"{"items": [{"id": 1, "updated_at": "b"}], "request_id": "y"}"#;
+
+    let ignore_paths = vec![String::from("$..updated_at"), String::from("$.request_id")];
+    let rows = diff_responses(left, right, &ignore_paths).unwrap();
+
+    assert!(get_changes(&rows).is_empty());
+    assert_eq!(rows.len(), 1);
+
+    let invalid_paths = vec![String::from("$.items[")];
+    assert!(diff_responses(left, right, &invalid_paths).is_err());
+}
+
+#[test]
+fn test_diff_lines() {
+    let left = "a\nb\nc\nd\ne";
+    let right = "a\nc\nD\ne\nf";
+
+    let rows = diff_responses(left, right, &[]).unwrap();
+    let kinds: Vec<DiffKind> = rows.iter().map(|row| row.kind).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            DiffKind::Unchanged,
+            DiffKind::Removed,
+            DiffKind::Unchanged,
+            DiffKind::Changed,
+            DiffKind::Unchanged,
+            DiffKind::Added,
+        ]
+    );
+    assert_eq!((rows[3].left.as_str(), rows[3].right.as_str()), ("d", "D"));
+}
+
+#[test]
+fn test_diff_lines_changed_block() {
+    let mut rows = vec![];
+    push_changed_block(&["a", "b"], &["c"], &mut rows);
+
+    assert_eq!(
+        get_changes(&rows),
+        vec![(DiffKind::Changed, "a", "c"), (DiffKind::Removed, "b", "")]
+    );
+}
+
+#[test]
+fn test_parse_ignore_paths() {
+    assert_eq!(
+        parse_ignore_paths(" $..updated_at, $['a','b'] ,,$.id"),
+        vec!["$..updated_at", "$['a','b']", "$.id"]
+    );
+    assert!(parse_ignore_paths("  ").is_empty());
+}
+
+#[test]
+fn test_split_ignore_entries() {
+    let entries = parse_ignore_paths("$..updated_at, X-Request-Id, $.id");
+    let (paths, headers) = split_ignore_entries(entries);
+
+    assert_eq!(paths, vec!["$..updated_at", "$.id"]);
+    assert_eq!(headers, vec!["X-Request-Id"]);
+}
+
+#[test]
+fn test_get_diff_lines() {
+    let rows = diff_json(
+        &serde_json::json!({"id": 1, "name": "Ann"}),
+        &serde_json::json!({"id": 1, "name": "Bo", "age": 3}),
+        &[],
+    )
+    .unwrap();
+
+    assert_eq!(get_diff_summary(&rows), "1 changed, 1 added, 0 removed");
+    assert_eq!(
+        get_diff_lines(&rows),
+        vec![r#"- $.name: "Ann""#, r#"+ $.name: "Bo""#, "+ $.age: 3"]
+    );
+}
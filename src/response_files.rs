@@ -0,0 +1,157 @@
+use std::{
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, SystemTime},
+};
+
+use log::error;
+use serde::{Deserialize, Serialize};
+
+use crate::fs::get_cache_dir;
+
+/// Larger responses are only shown, scripts, snapshots, schemas and the
+/// clipboard need the whole body in memory
+pub const MAX_LOADED_BODY_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Sessions of other instances of the app are removed once they are this
+/// old, they are left behind when the app does not exit cleanly
+const STALE_SESSION_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The files of one response in the cache folder of the app, they are
+/// removed when the next response arrives
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFiles {
+    /// The body as it arrived
+    pub raw: PathBuf,
+
+    /// The body as UTF-8 text, a hex dump for binary responses. It is the raw
+    /// file when the response is already UTF-8.
+    pub text: PathBuf,
+
+    /// The text formatted as JSON, XML or HTML
+    pub pretty: PathBuf,
+
+    /// A hex dump of the raw body
+    pub hex: PathBuf,
+}
+
+impl ResponseFiles {
+    pub fn new() -> anyhow::Result<Self> {
+        let dir = get_session_dir()?;
+        fs::create_dir_all(&dir)?;
+
+        let id = uuid::Uuid::new_v4();
+
+        Ok(ResponseFiles {
+            raw: dir.join(format!("{id}.bin")),
+            text: dir.join(format!("{id}.txt")),
+            pretty: dir.join(format!("{id}_pretty.txt")),
+            hex: dir.join(format!("{id}_hex.txt")),
+        })
+    }
+
+    /// The text of the response, None when it is too large to load
+    pub fn read_text(&self) -> anyhow::Result<Option<String>> {
+        if fs::metadata(&self.text)?.len() > MAX_LOADED_BODY_BYTES {
+            return Ok(None);
+        }
+
+        let text = fs::read(&self.text)?;
+
+        Ok(Some(String::from_utf8_lossy(&text).into_owned()))
+    }
+
+    pub fn remove(&self) {
+        for path in [&self.raw, &self.text, &self.pretty, &self.hex] {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Writes a file of a response unless it was written before, e.g. the pretty
+/// printed body is kept for every toggle of the response view. The file only
+/// exists once it was written completely.
+pub fn write_once(
+    path: &Path,
+    write: impl FnOnce(File) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+
+    let partial = path.with_extension("partial");
+    if let Err(error) = write(File::create(&partial)?) {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+
+    Ok(fs::rename(partial, path)?)
+}
+
+/// Removes the responses of the app when it exits, the responses left behind
+/// by instances that crashed are removed when the next one starts
+pub struct ResponseSession;
+
+impl ResponseSession {
+    pub fn start() -> Self {
+        if let Err(error) = remove_stale_sessions() {
+            error!("Could not remove old responses: {error}");
+        }
+
+        ResponseSession
+    }
+}
+
+impl Drop for ResponseSession {
+    fn drop(&mut self) {
+        let Some(dir) = get_session_dir().ok().filter(|dir| dir.exists()) else {
+            return;
+        };
+
+        if let Err(error) = fs::remove_dir_all(dir) {
+            error!("Could not remove the responses: {error}");
+        }
+    }
+}
+
+/// Every instance of the app keeps its responses in a folder named after its
+/// process id
+fn get_session_dir() -> anyhow::Result<PathBuf> {
+    Ok(get_cache_dir("responses")?.join(process::id().to_string()))
+}
+
+fn remove_stale_sessions() -> anyhow::Result<()> {
+    let now = SystemTime::now();
+
+    for entry in fs::read_dir(get_cache_dir("responses")?)? {
+        let entry = entry?;
+        let modified = entry.metadata()?.modified()?;
+        let age = now.duration_since(modified).unwrap_or_default();
+
+        if age > STALE_SESSION_AGE {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_write_once() {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("tome_write_once_{}.txt", process::id()));
+    let _ = fs::remove_file(&path);
+
+    let failed = write_once(&path, |_| anyhow::bail!("Not formatted"));
+    assert!(failed.is_err());
+    assert!(!path.exists());
+
+    write_once(&path, |mut file| Ok(file.write_all(b"first")?)).unwrap();
+    write_once(&path, |mut file| Ok(file.write_all(b"second")?)).unwrap();
+    let text = fs::read_to_string(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(text, "first");
+}
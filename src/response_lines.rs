@@ -0,0 +1,282 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// The offset of every line that starts a block of this many lines is kept
+const CHECKPOINT_LINES: usize = 64;
+
+/// Longer lines are cut, they could not be shown on a screen anyway
+const MAX_LINE_BYTES: usize = 256 * 1024;
+
+/// The lines of a response, read from its file when they are shown. Query
+/// results are small enough to be kept in memory.
+pub enum ResponseLines {
+    File(IndexedLines),
+    Memory(Vec<String>),
+}
+
+impl Default for ResponseLines {
+    fn default() -> Self {
+        ResponseLines::Memory(vec![])
+    }
+}
+
+impl ResponseLines {
+    pub fn len(&self) -> usize {
+        match self {
+            ResponseLines::File(lines) => lines.len(),
+            ResponseLines::Memory(lines) => lines.len(),
+        }
+    }
+
+    /// Up to count lines from the start line on
+    pub fn get_lines(&mut self, start: usize, count: usize) -> anyhow::Result<Vec<String>> {
+        match self {
+            ResponseLines::File(lines) => lines.get_lines(start, count),
+            ResponseLines::Memory(lines) => {
+                Ok(lines.iter().skip(start).take(count).cloned().collect())
+            }
+        }
+    }
+
+    /// The index of every line the predicate matches
+    pub fn find_lines(
+        &mut self,
+        mut predicate: impl FnMut(&str) -> bool,
+    ) -> anyhow::Result<Vec<usize>> {
+        let mut indexes = vec![];
+
+        match self {
+            ResponseLines::File(lines) => lines.for_each_line(|index, line| {
+                if predicate(&line) {
+                    indexes.push(index);
+                }
+            })?,
+
+            ResponseLines::Memory(lines) => lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| predicate(line))
+                .for_each(|(index, _)| indexes.push(index)),
+        }
+
+        Ok(indexes)
+    }
+}
+
+/// The lines of a file, the file is scanned once when it is opened so any
+/// line can be read without reading the lines before it
+pub struct IndexedLines {
+    reader: BufReader<File>,
+
+    /// Offsets of the lines 0, 64, 128...
+    checkpoints: Vec<u64>,
+    line_count: usize,
+}
+
+impl IndexedLines {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut checkpoints = vec![0];
+        let mut line_count = 0;
+        let mut offset = 0;
+        let mut last_byte = None;
+
+        loop {
+            let buffer = reader.fill_buf()?;
+            let Some(last) = buffer.last() else {
+                break;
+            };
+            last_byte = Some(*last);
+
+            for (index, _) in buffer
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+            {
+                line_count += 1;
+
+                if line_count % CHECKPOINT_LINES == 0 {
+                    checkpoints.push(offset + index as u64 + 1);
+                }
+            }
+
+            let length = buffer.len();
+            offset += length as u64;
+            reader.consume(length);
+        }
+
+        // NOTE: The last line has no line break unless the file ends with one
+        if last_byte.is_some_and(|byte| byte != b'\n') {
+            line_count += 1;
+        }
+
+        Ok(IndexedLines {
+            reader,
+            checkpoints,
+            line_count,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.line_count
+    }
+
+    /// Up to count lines from the start line on, only the lines from the
+    /// checkpoint before the start line are read
+    pub fn get_lines(&mut self, start: usize, count: usize) -> anyhow::Result<Vec<String>> {
+        // NOTE: A file that does not end with a line break has no checkpoint
+        // after its last line
+        if start >= self.line_count {
+            return Ok(vec![]);
+        }
+
+        let end = start.saturating_add(count).min(self.line_count);
+
+        let checkpoint = start / CHECKPOINT_LINES;
+        self.reader
+            .seek(SeekFrom::Start(self.checkpoints[checkpoint]))?;
+
+        for _ in checkpoint * CHECKPOINT_LINES..start {
+            self.skip_line()?;
+        }
+
+        let lines = (start..end)
+            .map(|_| self.read_line())
+            .collect::<io::Result<Vec<String>>>()?;
+
+        Ok(lines)
+    }
+
+    /// Reads every line from the start of the file
+    pub fn for_each_line(&mut self, mut f: impl FnMut(usize, String)) -> anyhow::Result<()> {
+        self.reader.seek(SeekFrom::Start(0))?;
+
+        for index in 0..self.line_count {
+            f(index, self.read_line()?);
+        }
+
+        Ok(())
+    }
+
+    fn read_line(&mut self) -> io::Result<String> {
+        let mut line = vec![];
+        (&mut self.reader)
+            .take(MAX_LINE_BYTES as u64)
+            .read_until(b'\n', &mut line)?;
+
+        match line.last() {
+            Some(b'\n') => {
+                line.pop();
+            }
+            _ if line.len() == MAX_LINE_BYTES => self.skip_line()?,
+            _ => {}
+        }
+
+        Ok(get_line_text(line))
+    }
+
+    fn skip_line(&mut self) -> io::Result<()> {
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            if buffer.is_empty() {
+                return Ok(());
+            }
+
+            match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(index) => {
+                    self.reader.consume(index + 1);
+
+                    return Ok(());
+                }
+
+                None => {
+                    let length = buffer.len();
+                    self.reader.consume(length);
+                }
+            }
+        }
+    }
+}
+
+/// A response line without its line break, bytes that are not valid UTF-8
+/// are replaced
+fn get_line_text(line: Vec<u8>) -> String {
+    let line = String::from_utf8_lossy(&line);
+
+    line.strip_suffix('\r').unwrap_or(&line).to_string()
+}
+
+#[cfg(test)]
+fn write_lines(name: &str, text: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("tome_{name}_{}.txt", std::process::id()));
+    std::fs::write(&path, text).unwrap();
+
+    path
+}
+
+#[test]
+fn test_indexed_lines() {
+    let text: String = (0..200).map(|index| format!("line {index}\r\n")).collect();
+    let path = write_lines("indexed_lines", &text);
+
+    let mut lines = IndexedLines::open(&path).unwrap();
+    assert_eq!(lines.len(), 200);
+    assert_eq!(lines.get_lines(0, 2).unwrap(), vec!["line 0", "line 1"]);
+    assert_eq!(
+        lines.get_lines(127, 3).unwrap(),
+        vec!["line 127", "line 128", "line 129"]
+    );
+    assert_eq!(
+        lines.get_lines(198, 10).unwrap(),
+        vec!["line 198", "line 199"]
+    );
+    assert!(lines.get_lines(300, 10).unwrap().is_empty());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_indexed_lines_without_last_line_break() {
+    let path = write_lines("last_line", "{\n\n  \"a\": 1\n}");
+
+    let mut lines = IndexedLines::open(&path).unwrap();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines.get_lines(1, 10).unwrap(), vec!["", "  \"a\": 1", "}"]);
+
+    let mut found = ResponseLines::File(lines);
+    assert_eq!(
+        found.find_lines(|line| line.contains('a')).unwrap(),
+        vec![2]
+    );
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_indexed_lines_checkpoint_without_last_line_break() {
+    let text: Vec<String> = (0..64).map(|index| format!("line {index}")).collect();
+    let path = write_lines("checkpoint", &text.join("\n"));
+
+    let mut lines = IndexedLines::open(&path).unwrap();
+    assert_eq!(lines.len(), 64);
+    assert_eq!(lines.get_lines(63, 10).unwrap(), vec!["line 63"]);
+    assert!(lines.get_lines(64, 10).unwrap().is_empty());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn test_indexed_lines_long_line() {
+    let long_line = "a".repeat(MAX_LINE_BYTES + 10);
+    let path = write_lines("long_line", &format!("{long_line}\nnext"));
+
+    let mut lines = IndexedLines::open(&path).unwrap();
+    let read = lines.get_lines(0, 2).unwrap();
+    assert_eq!(read[0].len(), MAX_LINE_BYTES);
+    assert_eq!(read[1], "next");
+
+    std::fs::remove_file(path).unwrap();
+}
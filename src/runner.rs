@@ -0,0 +1,433 @@
+use std::{
+    collections::HashMap,
+    fs, mem,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail};
+use serde_json::Value;
+
+use crate::{
+    projects::{get_projects, PersistedProject},
+    requests::send_collection_request,
+    response_diff::get_diff_lines,
+    snapshots::SnapshotMismatch,
+};
+
+/// The variables of one iteration, from one row of a data file
+pub type DataRow = HashMap<String, String>;
+
+#[derive(Debug)]
+pub struct RequestResult {
+    pub endpoint: String,
+    pub status: Option<u16>,
+    pub duration: Duration,
+    pub error: Option<String>,
+
+    /// The diff of a response that does not match the snapshot of its endpoint
+    pub diff: Vec<String>,
+}
+
+impl RequestResult {
+    /// A request fails when it can not be sent, a script or assertion fails,
+    /// or the response has an error status
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.status.is_some_and(|status| status < 400)
+    }
+}
+
+#[derive(Debug)]
+pub struct IterationResult {
+    pub requests: Vec<RequestResult>,
+}
+
+/// Loads the rows of a data file, `.json` files must be an array of objects
+/// and any other file is read as CSV with a header row
+pub fn load_data_file(path: &str) -> anyhow::Result<Vec<DataRow>> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| anyhow!("Could not read the data file at {path}: {error}"))?;
+
+    let is_json = Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+
+    let rows = match is_json {
+        true => parse_json_data(&contents),
+        false => parse_csv_data(&contents),
+    };
+
+    rows.map_err(|error| anyhow!("Could not parse the data file at {path}: {error}"))
+}
+
+fn parse_json_data(contents: &str) -> anyhow::Result<Vec<DataRow>> {
+    let Value::Array(rows) = serde_json::from_str::<Value>(contents)? else {
+        bail!("The data file must be a JSON array of objects");
+    };
+
+    rows.into_iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let Value::Object(row) = row else {
+                bail!("Item {} of the array is not an object", index + 1);
+            };
+
+            Ok(row
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(value) => value,
+                        Value::Null => String::new(),
+                        value => value.to_string(),
+                    };
+
+                    (key, value)
+                })
+                .collect())
+        })
+        .collect()
+}
+
+fn parse_csv_data(contents: &str) -> anyhow::Result<Vec<DataRow>> {
+    let mut records = parse_csv(contents)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(vec![]);
+    };
+
+    let header: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+
+    records
+        .enumerate()
+        .map(|(index, record)| {
+            if record.len() != header.len() {
+                bail!(
+                    "Row {} has {} columns but the header has {}",
+                    index + 1,
+                    record.len(),
+                    header.len()
+                );
+            }
+
+            Ok(header.iter().cloned().zip(record).collect())
+        })
+        .collect()
+}
+
+/// Splits CSV into records. Quoted fields can contain commas, new lines and
+/// quotes written as `""`, empty lines are skipped.
+fn parse_csv(contents: &str) -> anyhow::Result<Vec<Vec<String>>> {
+    let contents = contents.strip_prefix('\u{feff}').unwrap_or(contents);
+
+    let mut records = vec![];
+    let mut record = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+
+    let mut chars = contents.chars().peekable();
+    while let Some(char) = chars.next() {
+        match (quoted, char) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, char) => field.push(char),
+
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                record.push(mem::take(&mut field));
+                push_record(&mut records, mem::take(&mut record));
+            }
+            (false, char) => field.push(char),
+        }
+    }
+
+    if quoted {
+        bail!("A quoted value is never closed");
+    }
+
+    record.push(field);
+    push_record(&mut records, record);
+
+    Ok(records)
+}
+
+fn push_record(records: &mut Vec<Vec<String>>, record: Vec<String>) {
+    let is_empty_line = record.len() == 1 && record[0].is_empty();
+    if !is_empty_line {
+        records.push(record);
+    }
+}
+
+/// Runs the endpoints in order once for every data row, or once when there
+/// are no rows. Variables set by scripts are kept until the iteration ends.
+/// With `update_snapshots` the responses that do not match their snapshot
+/// are approved instead of failing. Once `cancel` is set no more requests
+/// are sent and the results so far are returned.
+pub fn run_collection(
+    project: &PersistedProject,
+    endpoint_names: &[String],
+    rows: &[DataRow],
+    update_snapshots: bool,
+    cancel: &AtomicBool,
+) -> anyhow::Result<Vec<IterationResult>> {
+    let endpoints = endpoint_names
+        .iter()
+        .map(|name| {
+            project
+                .endpoints
+                .iter()
+                .find(|endpoint| endpoint.name == *name)
+                .ok_or_else(|| anyhow!("The project {} has no endpoint named {name}", project.name))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if endpoints.is_empty() {
+        bail!("Choose at least one endpoint to run");
+    }
+
+    let no_data = [DataRow::new()];
+    let rows = match rows.is_empty() {
+        true => &no_data[..],
+        false => rows,
+    };
+
+    let is_running = || !cancel.load(Ordering::SeqCst);
+    let results = rows
+        .iter()
+        .take_while(|_| is_running())
+        .map(|row| {
+            let mut variables = row.clone();
+            let requests = endpoints
+                .iter()
+                .take_while(|_| is_running())
+                .map(|endpoint| {
+                    let start = Instant::now();
+                    let result = send_collection_request(
+                        project,
+                        endpoint,
+                        &mut variables,
+                        update_snapshots,
+                    );
+
+                    let diff = result
+                        .as_ref()
+                        .err()
+                        .and_then(|error| error.downcast_ref::<SnapshotMismatch>())
+                        .map(|mismatch| get_diff_lines(&mismatch.rows))
+                        .unwrap_or_default();
+
+                    RequestResult {
+                        endpoint: endpoint.name.clone(),
+                        status: result.as_ref().ok().copied(),
+                        duration: start.elapsed(),
+                        error: result.err().map(|error| error.to_string()),
+                        diff,
+                    }
+                })
+                .collect();
+
+            IterationResult { requests }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+pub fn count_failures(results: &[IterationResult]) -> usize {
+    results
+        .iter()
+        .flat_map(|iteration| &iteration.requests)
+        .filter(|request| !request.passed())
+        .count()
+}
+
+/// The lines of a run summary, the totals first and then every iteration
+pub fn get_summary(results: &[IterationResult]) -> Vec<String> {
+    let total: usize = results
+        .iter()
+        .map(|iteration| iteration.requests.len())
+        .sum();
+    let failed = count_failures(results);
+
+    let mut lines = vec![format!(
+        "Ran {total} requests in {} iterations: {} passed, {failed} failed",
+        results.len(),
+        total - failed
+    )];
+
+    for (index, iteration) in results.iter().enumerate() {
+        let failed = count_failures(std::slice::from_ref(iteration));
+        lines.push(format!(
+            "Iteration {}: {} passed, {failed} failed",
+            index + 1,
+            iteration.requests.len() - failed
+        ));
+
+        for request in &iteration.requests {
+            let mark = match request.passed() {
+                true => "✓",
+                false => "✗",
+            };
+            let status = match request.status {
+                Some(status) => status.to_string(),
+                None => String::from("---"),
+            };
+
+            let mut line = format!(
+                "  {mark} {} {status} {}ms",
+                request.endpoint,
+                request.duration.as_millis()
+            );
+            if let Some(error) = &request.error {
+                line.push_str(&format!(" {}", error.replace('\n', " ")));
+            }
+
+            lines.push(line);
+            lines.extend(
+                request
+                    .diff
+                    .iter()
+                    .map(|diff_line| format!("    {diff_line}")),
+            );
+        }
+    }
+
+    lines
+}
+
+/// Runs a collection for `tome run`, prints the summary and returns whether
+/// every request passed. Every endpoint of the project runs when no endpoint
+/// names are given.
+pub fn run_collection_from_cli(
+    project_name: &str,
+    data_file: Option<&str>,
+    endpoint_names: &[String],
+    update_snapshots: bool,
+) -> anyhow::Result<bool> {
+    let project = get_projects()?
+        .into_iter()
+        .find(|project| project.name == project_name)
+        .ok_or_else(|| anyhow!("There is no project named {project_name}"))?;
+
+    let endpoint_names = match endpoint_names.is_empty() {
+        true => project
+            .endpoints
+            .iter()
+            .map(|endpoint| endpoint.name.clone())
+            .collect(),
+        false => endpoint_names.to_vec(),
+    };
+
+    let rows = match data_file {
+        Some(path) => load_data_file(path)?,
+        None => vec![],
+    };
+
+    let results = run_collection(
+        &project,
+        &endpoint_names,
+        &rows,
+        update_snapshots,
+        &AtomicBool::new(false),
+    )?;
+    get_summary(&results)
+        .iter()
+        .for_each(|line| println!("{line}"));
+
+    Ok(count_failures(&results) == 0)
+}
+
+#[test]
+fn test_parse_csv_data() {
+    let contents = "\u{feff}id, name ,note\r\n1,Ada,\"likes \"\"tea\"\", coffee\"\r\n\r\n2,Grace,\"two\nlines\"\n";
+
+    let rows = parse_csv_data(contents).unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["id"], "1");
+    assert_eq!(rows[0]["name"], "Ada");
+    assert_eq!(rows[0]["note"], "likes \"tea\", coffee");
+    assert_eq!(rows[1]["note"], "two\nlines");
+
+    assert!(parse_csv_data("").unwrap().is_empty());
+    assert!(parse_csv_data("id,name\n1").is_err());
+    assert!(parse_csv_data("id\n\"never closed").is_err());
+}
+
+#[test]
+fn test_parse_json_data() {
+    let contents = r#"[{"id": 1, "name": "Ada", "admin": true, "team": null}, {"id": 2}]"#;
+
+    let rows = parse_json_data(contents).unwrap();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["id"], "1");
+    assert_eq!(rows[0]["name"], "Ada");
+    assert_eq!(rows[0]["admin"], "true");
+    assert_eq!(rows[0]["team"], "");
+    assert_eq!(rows[1]["id"], "2");
+
+    assert!(parse_json_data(r#"{"id": 1}"#).is_err());
+    assert!(parse_json_data(r#"[1, 2]"#).is_err());
+}
+
+#[test]
+fn test_get_summary() {
+    let results = vec![IterationResult {
+        requests: vec![
+            RequestResult {
+                endpoint: "Login".to_string(),
+                status: Some(200),
+                duration: Duration::from_millis(12),
+                error: None,
+                diff: vec![],
+            },
+            RequestResult {
+                endpoint: "Get User".to_string(),
+                status: None,
+                duration: Duration::from_millis(3),
+                error: Some("2 assertions failed:\nA\nB".to_string()),
+                diff: vec![],
+            },
+        ],
+    }];
+
+    assert_eq!(
+        get_summary(&results),
+        vec![
+            "Ran 2 requests in 1 iterations: 1 passed, 1 failed",
+            "Iteration 1: 1 passed, 1 failed",
+            "  ✓ Login 200 12ms",
+            "  ✗ Get User --- 3ms 2 assertions failed: A B",
+        ]
+    );
+}
+
+#[test]
+fn test_get_summary_snapshot_diff() {
+    let results = vec![IterationResult {
+        requests: vec![RequestResult {
+            endpoint: "Get User".to_string(),
+            status: None,
+            duration: Duration::from_millis(5),
+            error: Some("Snapshot mismatch: 1 changed, 0 added, 0 removed".to_string()),
+            diff: vec![
+                r#"- $.name: "Ann""#.to_string(),
+                r#"+ $.name: "Bo""#.to_string(),
+            ],
+        }],
+    }];
+
+    assert_eq!(
+        &get_summary(&results)[2..],
+        vec![
+            "  ✗ Get User --- 5ms Snapshot mismatch: 1 changed, 0 added, 0 removed",
+            r#"    - $.name: "Ann""#,
+            r#"    + $.name: "Bo""#,
+        ]
+    );
+}
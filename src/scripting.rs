@@ -0,0 +1,325 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use anyhow::anyhow;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use openssl::{hash::MessageDigest, pkey::PKey, sha::sha256, sign::Signer};
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+
+use crate::projects::Header;
+
+/// Stops scripts with endless loops from freezing the app
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The request a pre-request script can change before it is sent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<Header>,
+    pub body: String,
+}
+
+/// The response a post-response script can read
+#[derive(Debug, Clone, Default)]
+pub struct ScriptResponse {
+    pub status: u16,
+    pub headers: Vec<Header>,
+    pub body: String,
+}
+
+/// What a script run left behind
+#[derive(Debug, Default)]
+pub struct ScriptOutput {
+    /// Variables set with `set_var`
+    pub variables: HashMap<String, String>,
+
+    /// Messages of the `assert` calls that failed
+    pub failures: Vec<String>,
+}
+
+/// Shared by the functions registered in the engine
+#[derive(Default)]
+struct ScriptContext {
+    variables: HashMap<String, String>,
+    output: ScriptOutput,
+}
+
+/// Runs a pre-request script. The script gets a `request` map with the
+/// `method`, `url`, `headers` and `body` of the request and changes to it are
+/// applied to the request.
+pub fn run_pre_request_script(
+    script: &str,
+    request: &mut ScriptRequest,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<ScriptOutput> {
+    let mut scope = Scope::new();
+    scope.push("request", get_request_map(request));
+
+    let output = run_script("pre-request", script, &mut scope, variables)?;
+
+    if let Some(map) = scope.get_value::<Map>("request") {
+        update_request(request, &map);
+    }
+
+    Ok(output)
+}
+
+/// Runs a post-response script. The script gets a `response` map with the
+/// `status`, `headers` and `body` of the response, and the body parsed as
+/// `json` when it is valid JSON.
+pub fn run_post_response_script(
+    script: &str,
+    response: &ScriptResponse,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<ScriptOutput> {
+    let mut scope = Scope::new();
+    scope.push("response", get_response_map(response));
+
+    run_script("post-response", script, &mut scope, variables)
+}
+
+fn run_script(
+    kind: &str,
+    script: &str,
+    scope: &mut Scope,
+    variables: &HashMap<String, String>,
+) -> anyhow::Result<ScriptOutput> {
+    let context = Rc::new(RefCell::new(ScriptContext {
+        variables: variables.clone(),
+        output: ScriptOutput::default(),
+    }));
+
+    let engine = build_engine(&context);
+    engine
+        .run_with_scope(scope, script)
+        .map_err(|error| anyhow!("The {kind} script failed: {error}"))?;
+    drop(engine);
+
+    let context = Rc::try_unwrap(context)
+        .map_err(|_| anyhow!("The {kind} script is still running"))?
+        .into_inner();
+
+    Ok(context.output)
+}
+
+fn build_engine(context: &Rc<RefCell<ScriptContext>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    let get_context = context.clone();
+    engine.register_fn("get_var", move |name: &str| -> Dynamic {
+        match get_context.borrow().variables.get(name) {
+            Some(value) => value.clone().into(),
+            None => Dynamic::UNIT,
+        }
+    });
+
+    let set_context = context.clone();
+    engine.register_fn("set_var", move |name: &str, value: Dynamic| {
+        let value = value.to_string();
+        let mut context = set_context.borrow_mut();
+
+        context.variables.insert(name.to_string(), value.clone());
+        context.output.variables.insert(name.to_string(), value);
+    });
+
+    let assert_context = context.clone();
+    engine.register_fn("assert", move |condition: bool, message: &str| {
+        if !condition {
+            assert_context
+                .borrow_mut()
+                .output
+                .failures
+                .push(message.to_string());
+        }
+    });
+
+    engine.register_fn("sha256", |message: &str| {
+        to_hex(&sha256(message.as_bytes()))
+    });
+    engine.register_fn("hmac_sha256", hmac_sha256);
+    engine.register_fn("base64_encode", |value: &str| STANDARD.encode(value));
+
+    engine
+}
+
+/// The hex encoded HMAC-SHA256 of the message, for request signatures
+fn hmac_sha256(key: &str, message: &str) -> Result<String, Box<EvalAltResult>> {
+    let sign = || -> Result<Vec<u8>, openssl::error::ErrorStack> {
+        let key = PKey::hmac(key.as_bytes())?;
+        let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+        signer.update(message.as_bytes())?;
+
+        signer.sign_to_vec()
+    };
+
+    sign()
+        .map(|signature| to_hex(&signature))
+        .map_err(|error| error.to_string().into())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn get_headers_map(headers: &[Header]) -> Map {
+    headers
+        .iter()
+        .map(|header| (header.name.as_str().into(), header.value.clone().into()))
+        .collect()
+}
+
+fn get_request_map(request: &ScriptRequest) -> Map {
+    let mut map = Map::new();
+    map.insert("method".into(), request.method.clone().into());
+    map.insert("url".into(), request.url.clone().into());
+    map.insert("headers".into(), get_headers_map(&request.headers).into());
+    map.insert("body".into(), request.body.clone().into());
+
+    map
+}
+
+fn get_response_map(response: &ScriptResponse) -> Map {
+    let json = serde_json::from_str::<serde_json::Value>(&response.body)
+        .ok()
+        .and_then(|json| rhai::serde::to_dynamic(json).ok())
+        .unwrap_or(Dynamic::UNIT);
+
+    // NOTE: Header names are lowercase so scripts do not have to guess the casing
+    let headers: Vec<Header> = response
+        .headers
+        .iter()
+        .map(|header| Header {
+            name: header.name.to_lowercase(),
+            value: header.value.clone(),
+        })
+        .collect();
+
+    let mut map = Map::new();
+    map.insert("status".into(), (response.status as i64).into());
+    map.insert("headers".into(), get_headers_map(&headers).into());
+    map.insert("body".into(), response.body.clone().into());
+    map.insert("json".into(), json);
+
+    map
+}
+
+/// Applies the changes a script made to the request map. Headers keep their
+/// order, headers removed from the map are removed and new ones are added last.
+fn update_request(request: &mut ScriptRequest, map: &Map) {
+    let get_string = |key: &str| map.get(key).map(|value| value.to_string());
+
+    if let Some(method) = get_string("method") {
+        request.method = method.to_uppercase();
+    }
+
+    if let Some(url) = get_string("url") {
+        request.url = url;
+    }
+
+    if let Some(body) = get_string("body") {
+        request.body = body;
+    }
+
+    let Some(headers) = map
+        .get("headers")
+        .and_then(|headers| headers.read_lock::<Map>())
+    else {
+        request.headers.clear();
+        return;
+    };
+
+    request
+        .headers
+        .retain(|header| headers.contains_key(header.name.as_str()));
+    request.headers.iter_mut().for_each(|header| {
+        if let Some(value) = headers.get(header.name.as_str()) {
+            header.value = value.to_string();
+        }
+    });
+
+    for (name, value) in headers.iter() {
+        if !request
+            .headers
+            .iter()
+            .any(|header| header.name == name.as_str())
+        {
+            request.headers.push(Header {
+                name: name.to_string(),
+                value: value.to_string(),
+            });
+        }
+    }
+}
+
+#[test]
+fn test_run_pre_request_script() {
+    let mut request = ScriptRequest {
+        method: "GET".to_string(),
+        url: "https://example.com/users".to_string(),
+        headers: vec![
+            Header {
+                name: "Accept".to_string(),
+                value: "*/*".to_string(),
+            },
+            Header {
+                name: "X-Remove".to_string(),
+                value: "gone".to_string(),
+            },
+        ],
+        body: String::new(),
+    };
+
+    let variables = HashMap::from([("secret".to_string(), "key".to_string())]);
+    let script = r#"
+        request.method = "post";
+        request.url += "?page=2";
+        request.body = `{"name": "tome"}`;
+        request.headers.remove("X-Remove");
+        request.headers["X-Signature"] = hmac_sha256(get_var("secret"), request.body);
+        set_var("count", 40 + 2);
+    "#;
+
+    let output = run_pre_request_script(script, &mut request, &variables).unwrap();
+
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.url, "https://example.com/users?page=2");
+    assert_eq!(request.body, "{\"name\": \"tome\"}");
+    assert_eq!(request.headers.len(), 2);
+    assert_eq!(request.headers[0].name, "Accept");
+    assert_eq!(request.headers[1].name, "X-Signature");
+    assert_eq!(request.headers[1].value.len(), 64);
+    assert_eq!(output.variables["count"], "42");
+    assert!(output.failures.is_empty());
+
+    let error = run_pre_request_script("let x = ;", &mut request, &variables).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("The pre-request script failed"));
+
+    assert!(run_pre_request_script("loop {}", &mut request, &variables).is_err());
+}
+
+#[test]
+fn test_run_post_response_script() {
+    let response = ScriptResponse {
+        status: 201,
+        headers: vec![Header {
+            name: "Content-Type".to_string(),
+            value: "application/json".to_string(),
+        }],
+        body: r#"{"token": "abc", "items": [1, 2]}"#.to_string(),
+    };
+
+    let script = r#"
+        assert(response.status == 201, "Status is 201");
+        assert(response.headers["content-type"] == "text/html", "Is HTML");
+        assert(response.json.items.len() == 3, "Has 3 items");
+        set_var("token", response.json.token);
+    "#;
+
+    let output = run_post_response_script(script, &response, &HashMap::new()).unwrap();
+
+    assert_eq!(output.variables["token"], "abc");
+    assert_eq!(output.failures, vec!["Is HTML", "Has 3 items"]);
+}
@@ -0,0 +1,341 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fs::get_app_dir,
+    history::get_safe_name,
+    projects::{Header, PersistedEndpoint},
+    response_diff::{diff_responses, get_diff_summary, has_changes, DiffKind, DiffRow},
+    scripting::ScriptResponse,
+};
+
+/// Headers that are different in every response, they are not saved in
+/// snapshots and never compared
+const VOLATILE_HEADERS: [&str; 7] = [
+    "age",
+    "content-length",
+    "date",
+    "etag",
+    "expires",
+    "last-modified",
+    "set-cookie",
+];
+
+/// The approved response of an endpoint, later responses of the endpoint
+/// are compared with it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Snapshot {
+    pub status: u16,
+    pub headers: Vec<Header>,
+    pub body: String,
+}
+
+impl From<&ScriptResponse> for Snapshot {
+    fn from(response: &ScriptResponse) -> Self {
+        Snapshot {
+            status: response.status,
+            headers: response
+                .headers
+                .iter()
+                .filter(|header| !is_ignored_header(&header.name, &[]))
+                .cloned()
+                .collect(),
+            body: response.body.clone(),
+        }
+    }
+}
+
+/// The error of a request whose response does not match the approved
+/// snapshot of its endpoint
+#[derive(Debug)]
+pub struct SnapshotMismatch {
+    pub rows: Vec<DiffRow>,
+}
+
+impl Display for SnapshotMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Snapshot mismatch: {}", get_diff_summary(&self.rows))
+    }
+}
+
+impl std::error::Error for SnapshotMismatch {}
+
+/// A snapshot file, it keeps the name of its endpoint as endpoints whose
+/// names sanitize the same would otherwise share the file
+#[derive(Debug, Deserialize, Serialize)]
+struct PersistedSnapshot {
+    /// Empty in snapshots saved before the name was kept
+    #[serde(default)]
+    endpoint: String,
+
+    #[serde(flatten)]
+    snapshot: Snapshot,
+}
+
+/// The approved snapshot of an endpoint, None when no response was approved
+pub fn load_snapshot(project_name: &str, endpoint_name: &str) -> anyhow::Result<Option<Snapshot>> {
+    let path = get_snapshot_path(project_name, endpoint_name)?;
+
+    Ok(read_snapshot(&path, endpoint_name)?.map(|persisted| persisted.snapshot))
+}
+
+pub fn save_snapshot(
+    project_name: &str,
+    endpoint_name: &str,
+    snapshot: &Snapshot,
+) -> anyhow::Result<()> {
+    let path = get_snapshot_path(project_name, endpoint_name)?;
+    read_snapshot(&path, endpoint_name)?;
+
+    let persisted = PersistedSnapshot {
+        endpoint: endpoint_name.to_string(),
+        snapshot: snapshot.clone(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&persisted)?)?;
+
+    Ok(())
+}
+
+/// Moves the snapshot of a renamed endpoint, fails without moving it when
+/// the new name already has the snapshot of another endpoint
+pub fn rename_snapshot(project_name: &str, old_name: &str, new_name: &str) -> anyhow::Result<()> {
+    let Some(snapshot) = load_snapshot(project_name, old_name)? else {
+        return Ok(());
+    };
+
+    let old_path = get_snapshot_path(project_name, old_name)?;
+    let new_path = get_snapshot_path(project_name, new_name)?;
+    if old_path != new_path && new_path.exists() {
+        bail!(
+            "A snapshot for {new_name} already exists, remove {} first",
+            new_path.display()
+        );
+    }
+
+    fs::remove_file(old_path)?;
+    save_snapshot(project_name, new_name, &snapshot)
+}
+
+/// Compares the status, the headers and the body of a response with the
+/// approved snapshot. Header names are compared without case.
+pub fn compare_snapshot(
+    approved: &Snapshot,
+    response: &Snapshot,
+    ignore_paths: &[String],
+    ignore_headers: &[String],
+) -> anyhow::Result<Vec<DiffRow>> {
+    let status_kind = match approved.status == response.status {
+        true => DiffKind::Unchanged,
+        false => DiffKind::Changed,
+    };
+
+    let mut rows = vec![DiffRow::new(
+        status_kind,
+        format!("status: {}", approved.status),
+        format!("status: {}", response.status),
+    )];
+
+    let approved_headers = get_compared_headers(&approved.headers, ignore_headers);
+    let response_headers = get_compared_headers(&response.headers, ignore_headers);
+    let names: BTreeSet<&String> = approved_headers
+        .keys()
+        .chain(response_headers.keys())
+        .collect();
+
+    for name in names {
+        let header_line = |value: &String| format!("header {name}: {value}");
+
+        let row = match (approved_headers.get(name), response_headers.get(name)) {
+            (Some(left), Some(right)) if left == right => {
+                DiffRow::new(DiffKind::Unchanged, header_line(left), header_line(right))
+            }
+            (Some(left), Some(right)) => {
+                DiffRow::new(DiffKind::Changed, header_line(left), header_line(right))
+            }
+            (Some(left), None) => DiffRow::new(DiffKind::Removed, header_line(left), ""),
+            (None, Some(right)) => DiffRow::new(DiffKind::Added, "", header_line(right)),
+            (None, None) => continue,
+        };
+
+        rows.push(row);
+    }
+
+    rows.extend(diff_responses(
+        &approved.body,
+        &response.body,
+        ignore_paths,
+    )?);
+
+    Ok(rows)
+}
+
+/// The diff of a response with the approved snapshot of its endpoint, None
+/// when it matches or the endpoint has no snapshot
+pub fn find_snapshot_mismatch(
+    project_name: &str,
+    endpoint: &PersistedEndpoint,
+    response: &Snapshot,
+) -> anyhow::Result<Option<Vec<DiffRow>>> {
+    let Some(approved) = load_snapshot(project_name, &endpoint.name)? else {
+        return Ok(None);
+    };
+
+    let rows = compare_snapshot(
+        &approved,
+        response,
+        &endpoint.diff_ignore_paths,
+        &endpoint.snapshot_ignore_headers,
+    )?;
+
+    Ok(has_changes(&rows).then_some(rows))
+}
+
+/// Fails with a `SnapshotMismatch` when a response of a collection run does
+/// not match the snapshot of its endpoint. With `update` the response is
+/// approved instead when it does not match or the endpoint has no snapshot.
+pub fn check_snapshot(
+    project_name: &str,
+    endpoint: &PersistedEndpoint,
+    response: &ScriptResponse,
+    update: bool,
+) -> anyhow::Result<()> {
+    let response = Snapshot::from(response);
+    let has_snapshot = get_snapshot_path(project_name, &endpoint.name)?.exists();
+    let mismatch = find_snapshot_mismatch(project_name, endpoint, &response)?;
+
+    match (mismatch, update) {
+        (None, true) if !has_snapshot => save_snapshot(project_name, &endpoint.name, &response),
+        (None, _) => Ok(()),
+        (Some(_), true) => save_snapshot(project_name, &endpoint.name, &response),
+        (Some(rows), false) => Err(SnapshotMismatch { rows }.into()),
+    }
+}
+
+/// Reads a snapshot file, fails when it belongs to another endpoint whose
+/// name sanitizes to the same file name
+fn read_snapshot(path: &Path, endpoint_name: &str) -> anyhow::Result<Option<PersistedSnapshot>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let persisted: PersistedSnapshot = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if !persisted.endpoint.is_empty() && persisted.endpoint != endpoint_name {
+        bail!(
+            "The snapshot {} belongs to the endpoint {}, rename {endpoint_name} to use a snapshot of its own",
+            path.display(),
+            persisted.endpoint
+        );
+    }
+
+    Ok(Some(persisted))
+}
+
+/// The snapshots of a project are kept in a folder next to the project file
+fn get_snapshot_path(project_name: &str, endpoint_name: &str) -> anyhow::Result<PathBuf> {
+    let mut path = get_app_dir("projects")?;
+    path.push(format!("{project_name}.snapshots"));
+    fs::create_dir_all(&path)?;
+
+    path.push(format!("{}.json", get_safe_name(endpoint_name)));
+
+    Ok(path)
+}
+
+fn get_compared_headers(headers: &[Header], ignore_headers: &[String]) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|header| !is_ignored_header(&header.name, ignore_headers))
+        .map(|header| (header.name.to_lowercase(), header.value.clone()))
+        .collect()
+}
+
+fn is_ignored_header(name: &str, ignore_headers: &[String]) -> bool {
+    let name = name.to_lowercase();
+
+    VOLATILE_HEADERS.contains(&name.as_str())
+        || ignore_headers
+            .iter()
+            .any(|ignored| ignored.to_lowercase() == name)
+}
+
+#[cfg(test)]
+fn get_test_snapshot(status: u16, request_id: &str, body: &str) -> Snapshot {
+    let header = |name: &str, value: &str| Header {
+        name: name.to_string(),
+        value: value.to_string(),
+    };
+
+    Snapshot {
+        status,
+        headers: vec![
+            header("Content-Type", "application/json"),
+            header("Date", "Tue, 01 Jan 2030 10:00:00 GMT"),
+            header("X-Request-Id", request_id),
+        ],
+        body: body.to_string(),
+    }
+}
+
+#[test]
+fn test_compare_snapshot() {
+    let approved = get_test_snapshot(200, "a1", r#"{"id": 1, "name": "Ann"}"#);
+    let response = get_test_snapshot(201, "b2", r#"{"id": 1, "name": "Bo"}"#);
+
+    let rows = compare_snapshot(&approved, &response, &[], &[]).unwrap();
+    let changes: Vec<(&str, &str)> = rows
+        .iter()
+        .filter(|row| row.kind != DiffKind::Unchanged)
+        .map(|row| (row.left.as_str(), row.right.as_str()))
+        .collect();
+
+    assert_eq!(
+        changes,
+        vec![
+            ("status: 200", "status: 201"),
+            ("header x-request-id: a1", "header x-request-id: b2"),
+            (r#"$.name: "Ann""#, r#"$.name: "Bo""#),
+        ]
+    );
+}
+
+#[test]
+fn test_compare_snapshot_ignored() {
+    let approved = get_test_snapshot(200, "a1", r#"{"id": 1, "at": "10:00"}"#);
+    let response = get_test_snapshot(200, "b2", r#"{"id": 1, "at": "10:05"}"#);
+
+    let ignore_paths = vec![String::from("$.at")];
+    let ignore_headers = vec![String::from("x-request-id")];
+    let rows = compare_snapshot(&approved, &response, &ignore_paths, &ignore_headers).unwrap();
+
+    assert!(!has_changes(&rows));
+    assert!(rows.iter().all(|row| !row.left.contains("date")));
+}
+
+#[test]
+fn test_read_snapshot_other_endpoint() {
+    let path = std::env::temp_dir().join(format!("tome_snapshot_{}.json", std::process::id()));
+    let snapshot = get_test_snapshot(200, "a1", r#"{"id": 1}"#);
+    let persisted = PersistedSnapshot {
+        endpoint: String::from("Get user"),
+        snapshot: snapshot.clone(),
+    };
+    fs::write(&path, serde_json::to_string(&persisted).unwrap()).unwrap();
+
+    let own = read_snapshot(&path, "Get user").unwrap().unwrap();
+    let other = read_snapshot(&path, "Get user?");
+
+    fs::write(&path, serde_json::to_string(&snapshot).unwrap()).unwrap();
+    let unnamed = read_snapshot(&path, "Get user?").unwrap().unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(own.snapshot, snapshot);
+    assert!(other.is_err());
+    assert_eq!(unnamed.snapshot, snapshot);
+}
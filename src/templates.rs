@@ -43,6 +43,22 @@ pub static TEMPLATE_MAP: LazyLock<HashMap<&str, &str>> =
             "floating_windows/templates/project_variables",
             include_str!("components/floating_windows/templates/project_variables.aml"),
         );
+        theme_map.insert(
+            "floating_windows/templates/request_preview",
+            include_str!("components/floating_windows/templates/request_preview.aml"),
+        );
+        theme_map.insert(
+            "floating_windows/templates/collection_runner",
+            include_str!("components/floating_windows/templates/collection_runner.aml"),
+        );
+        theme_map.insert(
+            "floating_windows/templates/benchmark",
+            include_str!("components/floating_windows/templates/benchmark.aml"),
+        );
+        theme_map.insert(
+            "floating_windows/templates/form_field_name",
+            include_str!("components/floating_windows/templates/form_field_name.aml"),
+        );
         theme_map.insert(
             "floating_windows/templates/add_project_variable",
             include_str!("components/floating_windows/templates/add_project_variable.aml"),
@@ -113,6 +129,21 @@ pub static TEMPLATE_MAP: LazyLock<HashMap<&str, &str>> =
             include_str!("components/templates/response_renderer.aml"),
         );
 
+        theme_map.insert(
+            "templates/response_diff",
+            include_str!("components/templates/response_diff.aml"),
+        );
+
+        theme_map.insert(
+            "templates/response_validation",
+            include_str!("components/templates/response_validation.aml"),
+        );
+
+        theme_map.insert(
+            "templates/response_tree",
+            include_str!("components/templates/response_tree.aml"),
+        );
+
         theme_map.insert(
             "templates/syntax_highlighter_renderer",
             include_str!("components/templates/syntax_highlighter_renderer.aml"),
@@ -138,6 +169,11 @@ pub static TEMPLATE_MAP: LazyLock<HashMap<&str, &str>> =
             include_str!("components/templates/edit_header_selector.aml"),
         );
 
+        theme_map.insert(
+            "templates/edit_param_selector",
+            include_str!("components/templates/edit_param_selector.aml"),
+        );
+
         theme_map.insert(
             "templates/app_section",
             include_str!("components/templates/app_section.aml"),
@@ -178,6 +214,21 @@ pub static TEMPLATE_MAP: LazyLock<HashMap<&str, &str>> =
             include_str!("components/templates/response_filter_input.aml"),
         );
 
+        theme_map.insert(
+            "templates/response_query_input",
+            include_str!("components/templates/response_query_input.aml"),
+        );
+
+        theme_map.insert(
+            "templates/response_diff_ignore_input",
+            include_str!("components/templates/response_diff_ignore_input.aml"),
+        );
+
+        theme_map.insert(
+            "templates/response_schema_input",
+            include_str!("components/templates/response_schema_input.aml"),
+        );
+
         theme_map.insert(
             "templates/no_border_input",
             include_str!("components/templates/no_border_input.aml"),
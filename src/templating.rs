@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::dynamic_variables::get_dynamic_variable;
+
+const DEFAULT_SEPARATOR: &str = ":-";
+
+/// A piece of a rendered template, placeholders that can not be resolved are
+/// kept as they were written so they can be highlighted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Segment {
+    pub text: String,
+    pub resolved: bool,
+}
+
+impl Segment {
+    pub fn resolved(text: impl Into<String>) -> Self {
+        Segment {
+            text: text.into(),
+            resolved: true,
+        }
+    }
+
+    pub fn unresolved(text: impl Into<String>) -> Self {
+        Segment {
+            text: text.into(),
+            resolved: false,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Text(String),
+
+    /// A `{{name}}` or `{{name:-default}}` placeholder
+    Variable {
+        name: &'a str,
+        default: Option<&'a str>,
+    },
+}
+
+/// Splits a template into text and placeholders, `\{{` and `\}}` are written
+/// as literal braces and a `}}` without an opening `{{` is kept as text so
+/// JSON bodies with nested objects do not need escaping
+fn tokenize(input: &str) -> anyhow::Result<Vec<Token<'_>>> {
+    let mut tokens: Vec<Token> = vec![];
+    let mut text = String::new();
+    let mut index = 0;
+
+    while index < input.len() {
+        let rest = &input[index..];
+
+        if rest.starts_with("\\{{") || rest.starts_with("\\}}") {
+            text.push_str(&rest[1..3]);
+            index += 3;
+            continue;
+        }
+
+        if rest.starts_with("{{") {
+            let inner_start = index + 2;
+            let Some(inner_length) = input[inner_start..].find("}}") else {
+                bail!("The {{{{ at position {index} is never closed");
+            };
+
+            let inner = &input[inner_start..inner_start + inner_length];
+            if inner.contains("{{") {
+                bail!("The {{{{ at position {index} is never closed");
+            }
+
+            let (name, default) = match inner.split_once(DEFAULT_SEPARATOR) {
+                Some((name, default)) => (name.trim(), Some(default)),
+                None => (inner.trim(), None),
+            };
+
+            if name.is_empty() || name.contains(['{', '}']) {
+                bail!("{{{{{inner}}}}} is not a valid variable name");
+            }
+
+            if !text.is_empty() {
+                tokens.push(Token::Text(std::mem::take(&mut text)));
+            }
+
+            tokens.push(Token::Variable { name, default });
+            index = inner_start + inner_length + 2;
+            continue;
+        }
+
+        let Some(character) = rest.chars().next() else {
+            break;
+        };
+
+        text.push(character);
+        index += character.len_utf8();
+    }
+
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+
+    Ok(tokens)
+}
+
+struct Renderer<'a> {
+    variables: &'a HashMap<String, String>,
+
+    /// The variables being resolved, used to find variables that reference themselves
+    stack: Vec<String>,
+    undefined: Vec<String>,
+}
+
+impl Renderer<'_> {
+    fn render(&mut self, input: &str) -> anyhow::Result<String> {
+        let mut output = String::new();
+
+        for token in tokenize(input)? {
+            match token {
+                Token::Text(text) => output.push_str(&text),
+                Token::Variable { name, default } => {
+                    output.push_str(&self.render_variable(name, default)?)
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Renders a JSON document, values replaced inside of a JSON string are
+    /// escaped while values outside of strings are written as they are so
+    /// variables can hold numbers, booleans or whole objects
+    fn render_json(&mut self, input: &str) -> anyhow::Result<String> {
+        let mut output = String::new();
+        let mut in_string = false;
+
+        for token in tokenize(input)? {
+            match token {
+                Token::Text(text) => {
+                    in_string = is_in_json_string(&text, in_string);
+                    output.push_str(&text);
+                }
+
+                Token::Variable { name, default } => {
+                    let value = self.render_variable(name, default)?;
+
+                    match in_string {
+                        true => output.push_str(&escape_json_string(&value)?),
+                        false => output.push_str(&value),
+                    }
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn render_variable(&mut self, name: &str, default: Option<&str>) -> anyhow::Result<String> {
+        match (self.resolve(name)?, default) {
+            (Some(value), None) => Ok(value),
+            (Some(value), Some(_)) if !value.is_empty() => Ok(value),
+            (_, Some(default)) => self.render(default),
+            (None, None) => {
+                if !self.undefined.iter().any(|undefined| undefined == name) {
+                    self.undefined.push(name.to_string());
+                }
+
+                Ok(String::new())
+            }
+        }
+    }
+
+    fn check_undefined(&self) -> anyhow::Result<()> {
+        if !self.undefined.is_empty() {
+            bail!("Undefined variables: {}", self.undefined.join(", "));
+        }
+
+        Ok(())
+    }
+
+    fn resolve(&mut self, name: &str) -> anyhow::Result<Option<String>> {
+        if self.stack.iter().any(|variable| variable == name) {
+            bail!(
+                "The variable {name} references itself: {} -> {name}",
+                self.stack.join(" -> ")
+            );
+        }
+
+        if let Some(value) = self.variables.get(name) {
+            self.stack.push(name.to_string());
+            let value = self.render(value)?;
+            self.stack.pop();
+
+            return Ok(Some(value));
+        }
+
+        if let Some(variable_name) = name.strip_prefix("$base64.") {
+            let value = self.resolve(variable_name)?;
+
+            return Ok(value.map(|value| STANDARD.encode(value)));
+        }
+
+        Ok(get_dynamic_variable(name))
+    }
+}
+
+/// Replaces the placeholders in the input, the values of variables can use
+/// other variables. Fails when a variable is not defined and has no default.
+pub fn render(input: &str, variables: &HashMap<String, String>) -> anyhow::Result<String> {
+    let mut renderer = Renderer {
+        variables,
+        stack: vec![],
+        undefined: vec![],
+    };
+
+    let output = renderer.render(input)?;
+    renderer.check_undefined()?;
+
+    Ok(output)
+}
+
+/// Replaces the placeholders in a JSON body, values used inside of JSON
+/// strings are escaped so quotes and new lines do not break the body
+pub fn render_json(input: &str, variables: &HashMap<String, String>) -> anyhow::Result<String> {
+    let mut renderer = Renderer {
+        variables,
+        stack: vec![],
+        undefined: vec![],
+    };
+
+    let output = renderer.render_json(input)?;
+    renderer.check_undefined()?;
+
+    Ok(output)
+}
+
+/// Renders every input so a request is not sent with placeholders left in it,
+/// the error lists all of the undefined variables at once
+pub fn check_templates(inputs: &[&str], variables: &HashMap<String, String>) -> anyhow::Result<()> {
+    let mut renderer = Renderer {
+        variables,
+        stack: vec![],
+        undefined: vec![],
+    };
+
+    for input in inputs {
+        renderer.render(input)?;
+    }
+
+    renderer.check_undefined()
+}
+
+/// Renders the input without failing, for showing a request before it is
+/// sent. Placeholders that can not be resolved are kept as unresolved segments.
+pub fn render_segments(
+    input: &str,
+    variables: &HashMap<String, String>,
+    json: bool,
+) -> Vec<Segment> {
+    let Ok(tokens) = tokenize(input) else {
+        return vec![Segment::unresolved(input)];
+    };
+
+    let mut segments: Vec<Segment> = vec![];
+    let mut in_string = false;
+
+    for token in tokens {
+        match token {
+            Token::Text(text) => {
+                in_string = json && is_in_json_string(&text, in_string);
+                segments.push(Segment::resolved(text));
+            }
+
+            Token::Variable { name, default } => {
+                let mut renderer = Renderer {
+                    variables,
+                    stack: vec![],
+                    undefined: vec![],
+                };
+
+                let value = renderer
+                    .render_variable(name, default)
+                    .and_then(|value| renderer.check_undefined().map(|_| value))
+                    .and_then(|value| match in_string {
+                        true => escape_json_string(&value),
+                        false => Ok(value),
+                    });
+
+                segments.push(match value {
+                    Ok(value) => Segment::resolved(value),
+                    Err(_) => match default {
+                        Some(default) => Segment::unresolved(format!(
+                            "{{{{{name}{DEFAULT_SEPARATOR}{default}}}}}"
+                        )),
+                        None => Segment::unresolved(format!("{{{{{name}}}}}")),
+                    },
+                });
+            }
+        }
+    }
+
+    segments
+}
+
+/// Whether the end of the text is inside of a JSON string, given whether the
+/// start of it is
+fn is_in_json_string(text: &str, mut in_string: bool) -> bool {
+    let mut escaped = false;
+
+    for character in text.chars() {
+        match (in_string, escaped, character) {
+            (true, false, '\\') => escaped = true,
+            (true, true, _) => escaped = false,
+            (_, false, '"') => in_string = !in_string,
+            _ => {}
+        }
+    }
+
+    in_string
+}
+
+/// Escapes a value to be written between the quotes of a JSON string
+fn escape_json_string(value: &str) -> anyhow::Result<String> {
+    let quoted = serde_json::to_string(value)?;
+
+    Ok(quoted[1..quoted.len() - 1].to_string())
+}
+
+#[cfg(test)]
+fn get_test_variables() -> HashMap<String, String> {
+    let mut variables = HashMap::<String, String>::new();
+    variables.insert("host".to_string(), "localhost:3000".to_string());
+    variables.insert("baseUrl".to_string(), "https://{{host}}/api".to_string());
+    variables.insert("empty".to_string(), "".to_string());
+    variables.insert("user".to_string(), "tome:secret".to_string());
+
+    variables
+}
+
+#[test]
+fn test_tokenize() {
+    let tokens = tokenize("\\{{a}} {{ b :-c}}{\"d\":{\"e\":1}}").unwrap();
+
+    assert_eq!(
+        tokens,
+        vec![
+            Token::Text("{{a}} ".to_string()),
+            Token::Variable {
+                name: "b",
+                default: Some("c")
+            },
+            Token::Text("{\"d\":{\"e\":1}}".to_string()),
+        ]
+    );
+
+    assert!(tokenize("{{a").is_err());
+    assert!(tokenize("{{a}/{{b}}").is_err());
+    assert!(tokenize("{{}}").is_err());
+}
+
+#[test]
+fn test_render_nested_variables() {
+    let variables = get_test_variables();
+
+    let output = render("{{baseUrl}}/users", &variables).unwrap();
+
+    assert_eq!(output, "https://localhost:3000/api/users");
+}
+
+#[test]
+fn test_render_defaults() {
+    let variables = get_test_variables();
+
+    let output = render("{{missing:-guest}}/{{empty:-none}}/{{host:-x}}", &variables).unwrap();
+
+    assert_eq!(output, "guest/none/localhost:3000");
+}
+
+#[test]
+fn test_render_base64() {
+    let variables = get_test_variables();
+
+    let output = render("Basic {{$base64.user}}", &variables).unwrap();
+
+    assert_eq!(output, "Basic dG9tZTpzZWNyZXQ=");
+}
+
+#[test]
+fn test_render_undefined_variables() {
+    let variables = get_test_variables();
+
+    let error = render("{{first}}/{{host}}/{{second}}/{{first}}", &variables).unwrap_err();
+    assert_eq!(error.to_string(), "Undefined variables: first, second");
+
+    let error = check_templates(&["{{baseUrl}}/{{id}}", "{{token}}"], &variables).unwrap_err();
+    assert_eq!(error.to_string(), "Undefined variables: id, token");
+}
+
+#[test]
+fn test_render_json() {
+    let mut variables = get_test_variables();
+    variables.insert("name".to_string(), "Tome \"TUI\"\nclient".to_string());
+    variables.insert("count".to_string(), "3".to_string());
+    variables.insert("tags".to_string(), "[\"api\", \"tui\"]".to_string());
+
+    let body = render_json(
+        "{\"name\": \"{{name}}\", \"note\": \"a \\\" {{count}}\", \"count\": {{count}}, \"tags\": {{tags}}}",
+        &variables,
+    )
+    .unwrap();
+
+    assert_eq!(
+        body,
+        "{\"name\": \"Tome \\\"TUI\\\"\\nclient\", \"note\": \"a \\\" 3\", \"count\": 3, \"tags\": [\"api\", \"tui\"]}"
+    );
+    assert!(serde_json::from_str::<serde_json::Value>(&body).is_ok());
+}
+
+#[test]
+fn test_render_segments() {
+    let mut variables = get_test_variables();
+    variables.insert("quote".to_string(), "\"".to_string());
+
+    let segments = render_segments("{\"a\": \"{{quote}}\", \"b\": {{id}}}", &variables, true);
+
+    assert_eq!(
+        segments,
+        vec![
+            Segment::resolved("{\"a\": \""),
+            Segment::resolved("\\\""),
+            Segment::resolved("\", \"b\": "),
+            Segment::unresolved("{{id}}"),
+            Segment::resolved("}"),
+        ]
+    );
+
+    let segments = render_segments("{{host}}/{{a:-{{b}}}", &variables, false);
+    assert_eq!(segments, vec![Segment::unresolved("{{host}}/{{a:-{{b}}}")]);
+}
+
+#[test]
+fn test_render_cycle() {
+    let mut variables = get_test_variables();
+    variables.insert("a".to_string(), "{{b}}".to_string());
+    variables.insert("b".to_string(), "{{a}}".to_string());
+
+    let error = render("{{a}}", &variables).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "The variable a references itself: a -> b -> a"
+    );
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, bail};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use openssl::{
+    hash::MessageDigest,
+    pkcs5::pbkdf2_hmac,
+    rand::rand_bytes,
+    symm::{decrypt_aead, encrypt_aead, Cipher},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::fs::get_app_dir;
+
+/// Environment variable with the passphrase used to derive the vault key,
+/// when it is not set the key is kept in the OS keyring
+pub const PASSPHRASE_ENV: &str = "TOME_VAULT_PASSPHRASE";
+
+const VAULT_FILE: &str = "secrets.vault";
+const KEY_FILE: &str = "vault.key";
+const KEYRING_SERVICE: &str = "tome";
+const KEYRING_USER: &str = "vault";
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+const SALT_LENGTH: usize = 16;
+const PBKDF2_ITERATIONS: usize = 600_000;
+
+/// Encrypted with the key so a wrong key is reported instead of failing on
+/// every secret
+const CHECK_VALUE: &str = "tome";
+
+/// The last passphrase derived key, deriving it takes a while and the vault is
+/// opened on every project load and save
+static DERIVED_KEY: Mutex<Option<DerivedKey>> = Mutex::new(None);
+
+struct DerivedKey {
+    passphrase: String,
+    salt: Vec<u8>,
+    key: Vec<u8>,
+}
+
+#[derive(Default, Debug, Deserialize, Serialize)]
+struct VaultFile {
+    /// Salt for the passphrase derived key
+    salt: String,
+    check: Option<String>,
+
+    /// Encrypted secrets by id
+    secrets: HashMap<String, String>,
+}
+
+/// Private variable values, encrypted with AES-256-GCM and saved apart from
+/// the project files
+pub struct Vault {
+    key: Vec<u8>,
+    file: VaultFile,
+    path: PathBuf,
+}
+
+impl Vault {
+    pub fn open() -> anyhow::Result<Self> {
+        let dir = get_app_dir("vault")?;
+        let path = dir.join(VAULT_FILE);
+
+        let mut file = match path.exists() {
+            true => serde_json::from_str::<VaultFile>(&fs::read_to_string(&path)?)?,
+            false => VaultFile::default(),
+        };
+
+        if file.salt.is_empty() {
+            file.salt = STANDARD.encode(random_bytes(SALT_LENGTH)?);
+        }
+
+        let key = match env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => {
+                get_derived_key(&passphrase, &STANDARD.decode(&file.salt)?)?
+            }
+            _ => get_stored_key(&dir)?,
+        };
+
+        let mut vault = Vault { key, file, path };
+        vault.check_key()?;
+
+        Ok(vault)
+    }
+
+    fn check_key(&mut self) -> anyhow::Result<()> {
+        match &self.file.check {
+            Some(check) => match decrypt(&self.key, check) {
+                Ok(value) if value == CHECK_VALUE => Ok(()),
+                _ => bail!("Could not unlock the vault, check the {PASSPHRASE_ENV} passphrase"),
+            },
+            None => {
+                self.file.check = Some(encrypt(&self.key, CHECK_VALUE)?);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn get(&self, id: &str) -> anyhow::Result<Option<String>> {
+        self.file
+            .secrets
+            .get(id)
+            .map(|secret| decrypt(&self.key, secret))
+            .transpose()
+    }
+
+    pub fn set(&mut self, id: &str, value: &str) -> anyhow::Result<()> {
+        let secret = encrypt(&self.key, value)?;
+        self.file.secrets.insert(id.to_string(), secret);
+
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.file.secrets.remove(id);
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let serialized_vault = serde_json::to_string(&self.file)?;
+        fs::write(&self.path, serialized_vault)?;
+
+        Ok(())
+    }
+}
+
+fn random_bytes(length: usize) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = vec![0; length];
+    rand_bytes(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut key = vec![0; KEY_LENGTH];
+    pbkdf2_hmac(
+        passphrase.as_bytes(),
+        salt,
+        PBKDF2_ITERATIONS,
+        MessageDigest::sha256(),
+        &mut key,
+    )?;
+
+    Ok(key)
+}
+
+/// The passphrase derived key, it is only derived again when the passphrase or
+/// the salt changes
+fn get_derived_key(passphrase: &str, salt: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut derived_key = DERIVED_KEY
+        .lock()
+        .map_err(|_| anyhow!("Could not read the vault key"))?;
+
+    if let Some(derived) = derived_key
+        .as_ref()
+        .filter(|derived| derived.passphrase == passphrase && derived.salt == salt)
+    {
+        return Ok(derived.key.clone());
+    }
+
+    let key = derive_key(passphrase, salt)?;
+    *derived_key = Some(DerivedKey {
+        passphrase: passphrase.to_string(),
+        salt: salt.to_vec(),
+        key: key.clone(),
+    });
+
+    Ok(key)
+}
+
+/// The random key kept in the OS keyring, or in a key file readable only by
+/// the user when there is no keyring, like on headless Linux
+fn get_stored_key(dir: &Path) -> anyhow::Result<Vec<u8>> {
+    let key_path = dir.join(KEY_FILE);
+    if key_path.exists() {
+        return Ok(STANDARD.decode(fs::read_to_string(&key_path)?.trim())?);
+    }
+
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+        match entry.get_password() {
+            Ok(key) => return Ok(STANDARD.decode(key)?),
+
+            Err(keyring::Error::NoEntry) => {
+                let key = random_bytes(KEY_LENGTH)?;
+                if entry.set_password(&STANDARD.encode(&key)).is_ok() {
+                    return Ok(key);
+                }
+            }
+
+            Err(_) => {}
+        }
+    }
+
+    let key = random_bytes(KEY_LENGTH)?;
+    write_key_file(&key_path, &STANDARD.encode(&key))?;
+
+    Ok(key)
+}
+
+#[cfg(unix)]
+fn write_key_file(path: &Path, key: &str) -> anyhow::Result<()> {
+    use std::{io::Write, os::unix::fs::OpenOptionsExt};
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(key.as_bytes())?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_key_file(path: &Path, key: &str) -> anyhow::Result<()> {
+    fs::write(path, key)?;
+
+    Ok(())
+}
+
+/// Encrypts the value, the nonce, ciphertext and tag are returned as base64
+fn encrypt(key: &[u8], value: &str) -> anyhow::Result<String> {
+    let nonce = random_bytes(NONCE_LENGTH)?;
+    let mut tag = [0; TAG_LENGTH];
+
+    let ciphertext = encrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(&nonce),
+        &[],
+        value.as_bytes(),
+        &mut tag,
+    )?;
+
+    Ok(STANDARD.encode([nonce.as_slice(), &ciphertext, &tag].concat()))
+}
+
+fn decrypt(key: &[u8], secret: &str) -> anyhow::Result<String> {
+    let bytes = STANDARD.decode(secret)?;
+    if bytes.len() < NONCE_LENGTH + TAG_LENGTH {
+        bail!("The vault secret is corrupted");
+    }
+
+    let (nonce, rest) = bytes.split_at(NONCE_LENGTH);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+
+    let value = decrypt_aead(
+        Cipher::aes_256_gcm(),
+        key,
+        Some(nonce),
+        &[],
+        ciphertext,
+        tag,
+    )
+    .map_err(|_| anyhow!("Could not decrypt the vault secret"))?;
+
+    Ok(String::from_utf8(value)?)
+}
+
+#[test]
+fn test_encrypt_decrypt() {
+    let key = derive_key("correct horse battery staple", b"tome salt").unwrap();
+    let secret = encrypt(&key, "s3cr3t").unwrap();
+
+    assert!(!secret.contains("s3cr3t"));
+    assert_ne!(secret, encrypt(&key, "s3cr3t").unwrap());
+    assert_eq!(decrypt(&key, &secret).unwrap(), "s3cr3t");
+
+    let wrong_key = derive_key("wrong passphrase", b"tome salt").unwrap();
+    assert!(decrypt(&wrong_key, &secret).is_err());
+}
+
+#[test]
+fn test_vault_check_key() {
+    let key = derive_key("passphrase", b"tome salt").unwrap();
+    let mut vault = Vault {
+        key,
+        file: VaultFile::default(),
+        path: PathBuf::new(),
+    };
+
+    vault.check_key().unwrap();
+    vault.set("id", "value").unwrap();
+    assert_eq!(vault.get("id").unwrap(), Some("value".to_string()));
+    assert_eq!(vault.get("missing").unwrap(), None);
+
+    vault.key = derive_key("other passphrase", b"tome salt").unwrap();
+    assert!(vault.check_key().is_err());
+}
+
+#[test]
+fn test_get_derived_key() {
+    let key = get_derived_key("passphrase", b"tome salt").unwrap();
+    assert_eq!(key, derive_key("passphrase", b"tome salt").unwrap());
+    assert_eq!(get_derived_key("passphrase", b"tome salt").unwrap(), key);
+    assert_ne!(get_derived_key("passphrase", b"other salt").unwrap(), key);
+}